@@ -7,13 +7,16 @@ use crate::{
         workspace_models::WorkspaceContext,
     },
     utils::{
-        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        constants::{
+            ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS, RAW_SOCKETS_WITH_PROXY_HINT,
+        },
+        dns_resolver::is_valid_hostname,
         general::render_validation_err,
         icmp::{
             DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
             DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
         },
-        ip::ping_ip_list,
+        ip::{add_nodes_by_hostname, ping_ip_list, PingIpListConfig},
     },
 };
 
@@ -43,7 +46,7 @@ impl AddNewDeviceWindowState {
             .show(egui_context, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.horizontal(|ui| {
-                        ui.label("IP Address");
+                        ui.label("IP Address or hostname");
                         ui.text_edit_singleline(
                             &mut app_context.ui_state.add_new_device_window_state.ip,
                         );
@@ -54,7 +57,7 @@ impl AddNewDeviceWindowState {
                             .ui_state
                             .add_new_device_window_state
                             .ip_validation_err,
-                        "IP is not valid.",
+                        "Neither valid IP nor hostname.",
                     );
 
                     ui.add_space(DEFAULT_SPACER);
@@ -83,13 +86,17 @@ impl AddNewDeviceWindowState {
                     );
 
                     ui.add_space(DEFAULT_SPACER);
-                    ui.checkbox(
-                        &mut app_context
-                            .ui_state
-                            .add_new_device_window_state
-                            .ping_after_creation,
-                        "Send ping after creation",
-                    );
+                    let is_proxy_active = app_context.app_state.project_settings.proxy.is_some();
+                    let window_state = &mut app_context.ui_state.add_new_device_window_state;
+                    window_state.ping_after_creation &= !is_proxy_active;
+                    ui.add_enabled(
+                        !is_proxy_active,
+                        egui::Checkbox::new(
+                            &mut window_state.ping_after_creation,
+                            "Send ping after creation",
+                        ),
+                    )
+                    .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT);
 
                     ui.add_space(ACTION_SPACER);
                     if ui.button("Add").clicked() {
@@ -139,14 +146,56 @@ impl AddNewDeviceWindowState {
                                 ping_ip_list(
                                     Arc::clone(&app_context.app_state.network_topology.graph),
                                     Arc::clone(&app_context.app_state.status_info),
-                                    vec![new_ip],
-                                    DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
-                                    DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
-                                    false,
-                                    false,
+                                    Arc::clone(&app_context.app_state.scan_snapshots),
+                                    vec![new_ip].into(),
+                                    PingIpListConfig {
+                                        ping_timeout_ms:
+                                            DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
+                                        ping_checkup_ms:
+                                            DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
+                                        remove_connectivity_status_when_unreachable: false,
+                                        reset_connectivity_status: false,
+                                        resolve_hostnames_with: None,
+                                        exclusions: app_context
+                                            .app_state
+                                            .project_settings
+                                            .exclusions
+                                            .clone(),
+                                        source: app_context
+                                            .app_state
+                                            .project_settings
+                                            .source
+                                            .clone(),
+                                    },
                                 );
                             }
                             app_context.ui_state.add_new_device_window_state = Default::default();
+                        } else if is_valid_hostname(
+                            &app_context.ui_state.add_new_device_window_state.ip,
+                        ) {
+                            let window_state = &app_context.ui_state.add_new_device_window_state;
+                            add_nodes_by_hostname(
+                                Arc::clone(&app_context.app_state.network_topology.graph),
+                                Arc::clone(&app_context.app_state.status_info),
+                                Arc::clone(&app_context.app_state.scan_snapshots),
+                                window_state.ip.trim().to_owned(),
+                                window_state.notes.clone(),
+                                app_context.app_state.project_settings.dns_resolver_config(),
+                                window_state.ping_after_creation.then(|| PingIpListConfig {
+                                    ping_timeout_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
+                                    ping_checkup_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
+                                    remove_connectivity_status_when_unreachable: false,
+                                    reset_connectivity_status: false,
+                                    resolve_hostnames_with: None,
+                                    exclusions: app_context
+                                        .app_state
+                                        .project_settings
+                                        .exclusions
+                                        .clone(),
+                                    source: app_context.app_state.project_settings.source.clone(),
+                                }),
+                            );
+                            app_context.ui_state.add_new_device_window_state = Default::default();
                         } else {
                             app_context
                                 .ui_state
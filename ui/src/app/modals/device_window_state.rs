@@ -1,19 +1,31 @@
-use std::{net::IpAddr, sync::Arc};
+use std::{net::{IpAddr, Ipv4Addr}, str::FromStr, sync::Arc, time::Duration};
 
 use eframe::{egui::{self, ScrollArea, TextStyle}, epaint::Vec2};
 use egui_extras::{TableBuilder, Column};
 use petgraph::stable_graph::NodeIndex;
+use pnet::util::MacAddr;
 use rand::random;
 
 use crate::{
-    app::{network_topology::NetworkTopology, workspace_models::WorkspaceContext},
+    app::{
+        modals::raw_data_viewer_window::RawDataViewerSection,
+        network_topology::{get_latency_color, NetworkTopology, NetworkTopologyNode, PortInfo},
+        workspace_models::WorkspaceContext,
+    },
     utils::{
         constants::{
             ACTION_SPACER, ALL_COMMON_PORTS, ALL_COMMON_PORTS_LENGHT, DEFAULT_SPACER,
-            DEFAULT_WINDOW_STARTING_POS, MOST_COMMON_PORTS, TRASH_ICON,
+            DEFAULT_WINDOW_STARTING_POS, LINE_ENDING, MOST_COMMON_PORTS, RAW_SOCKETS_WITH_PROXY_HINT,
+            TRASH_ICON,
         },
         general::{render_validation_err, render_numeric_textbox},
         ip::{scap_ip_ports, update_hostname_list, Port, ScanIpPortsConfig},
+        oui::lookup_mac_vendor,
+        snmp::probe_snmp_list,
+        wol::{
+            get_directed_broadcast, wake_on_lan, WakeOnLanConfig, WakeOnLanDestination,
+            DEFAULT_WAKE_ON_LAN_PORT, DEFAULT_WAKE_ON_LAN_WATCH_S,
+        },
     },
 };
 
@@ -33,6 +45,8 @@ enum PortScanSubWindowType {
 
 type AllCommonPortsParsed = Vec<(Port, String, String)>;
 
+const PING_SPARKLINE_LEN: usize = 60;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DeviceWindowState {
     window_id: egui::Id,
@@ -54,7 +68,17 @@ pub struct DeviceWindowState {
     port_scan_settings_connection_timeout_ms: String,
     port_scan_settings_should_banner_grab: bool,
     port_scan_settings_should_fuzz: bool,
+    port_scan_settings_should_check_access: bool,
     port_scan_settings_read_write_timeout_ms: String,
+
+    wake_on_lan_is_directed: bool,
+    wake_on_lan_directed_address: String,
+    wake_on_lan_directed_address_validation_err: bool,
+    wake_on_lan_port: String,
+    wake_on_lan_password: String, // empty => no SecureOn password
+    wake_on_lan_password_validation_err: bool,
+    wake_on_lan_should_watch: bool,
+    wake_on_lan_watch_s: String,
     
     should_show_port_scan_window: bool,
     scan_results_window_id: egui::Id,
@@ -87,7 +111,17 @@ impl DeviceWindowState {
             port_scan_settings_connection_timeout_ms: "50".to_owned(),
             port_scan_settings_should_banner_grab: true,
             port_scan_settings_should_fuzz: false,
+            port_scan_settings_should_check_access: false,
             port_scan_settings_read_write_timeout_ms: "250".to_owned(),
+
+            wake_on_lan_is_directed: false,
+            wake_on_lan_directed_address: get_directed_broadcast(ip).map(|s| s.to_string()).unwrap_or_default(),
+            wake_on_lan_directed_address_validation_err: false,
+            wake_on_lan_port: DEFAULT_WAKE_ON_LAN_PORT.to_string(),
+            wake_on_lan_password: "".to_owned(),
+            wake_on_lan_password_validation_err: false,
+            wake_on_lan_should_watch: true,
+            wake_on_lan_watch_s: DEFAULT_WAKE_ON_LAN_WATCH_S.to_string(),
             
             should_show_port_scan_window: false,
             scan_results_window_id: egui::Id::new(scan_results_window_id_raw),
@@ -111,10 +145,195 @@ impl DeviceWindowState {
         self.ip
     }
 
+    fn render_wake_on_lan(
+        ui: &mut egui::Ui,
+        app_context: &mut WorkspaceContext,
+        device_window_state_index: usize,
+        device_node_index: NodeIndex,
+        device_ip: IpAddr,
+        device_mac: Option<MacAddr>,
+    ) {
+        let is_proxy_active = app_context.app_state.project_settings.proxy.is_some();
+        let window_binding = &mut app_context.ui_state.device_window_states[device_window_state_index];
+
+        ui.add_space(ACTION_SPACER);
+        ui.strong("Wake-on-LAN");
+        let Some(device_mac) = device_mac else {
+            ui.label("MAC address of this device is unknown. ARP / neighbor cache, NetBIOS or passive discovery can find it.");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut window_binding.wake_on_lan_is_directed, false, "Broadcast")
+                .on_hover_text("255.255.255.255, only reaches devices on the same segment as the source interface.");
+            ui.selectable_value(&mut window_binding.wake_on_lan_is_directed, true, "Directed")
+                .on_hover_text("Subnet broadcast, reaches other subnets if the router forwards directed broadcasts.");
+        });
+        if window_binding.wake_on_lan_is_directed {
+            ui.horizontal(|ui| {
+                ui.label("Subnet broadcast address");
+                ui.text_edit_singleline(&mut window_binding.wake_on_lan_directed_address);
+            });
+            render_validation_err(ui, window_binding.wake_on_lan_directed_address_validation_err, "Broadcast address is not valid IPv4 address.");
+        }
+
+        ui.add_space(DEFAULT_SPACER);
+        ui.horizontal(|ui| {
+            ui.label("UDP port");
+            render_numeric_textbox(ui, &mut window_binding.wake_on_lan_port);
+        });
+
+        ui.add_space(DEFAULT_SPACER);
+        ui.horizontal(|ui| {
+            ui.label("SecureOn password (optional)");
+            ui.text_edit_singleline(&mut window_binding.wake_on_lan_password)
+                .on_hover_text("6 bytes, written like a MAC address (e.g. 01:02:03:04:05:06).");
+        });
+        render_validation_err(ui, window_binding.wake_on_lan_password_validation_err, "SecureOn password has to be 6 bytes, e.g. 01:02:03:04:05:06.");
+
+        ui.add_space(DEFAULT_SPACER);
+        let can_watch = !is_proxy_active && device_ip.is_ipv4();
+        let watch_response = ui.add_enabled_ui(can_watch, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut window_binding.wake_on_lan_should_watch, "Ping until it's up, for (s)");
+                render_numeric_textbox(ui, &mut window_binding.wake_on_lan_watch_s);
+            });
+        })
+        .response;
+        if !can_watch {
+            watch_response.on_hover_text(if is_proxy_active { RAW_SOCKETS_WITH_PROXY_HINT } else { "Only IPv4 hosts can be pinged atm." });
+        }
+
+        ui.add_space(ACTION_SPACER);
+        ui.vertical_centered_justified(|ui| {
+            if !ui.button("Wake").clicked() {
+                return;
+            }
+
+            let directed_address = Ipv4Addr::from_str(window_binding.wake_on_lan_directed_address.trim());
+            window_binding.wake_on_lan_directed_address_validation_err = window_binding.wake_on_lan_is_directed && directed_address.is_err();
+            let password = window_binding.wake_on_lan_password.trim();
+            let secure_on_password = (!password.is_empty()).then(|| MacAddr::from_str(password));
+            window_binding.wake_on_lan_password_validation_err = matches!(secure_on_password, Some(Err(_)));
+            if window_binding.wake_on_lan_directed_address_validation_err || window_binding.wake_on_lan_password_validation_err {
+                return;
+            }
+
+            wake_on_lan(
+                Arc::clone(&app_context.app_state.network_topology.graph),
+                Arc::clone(&app_context.app_state.status_info),
+                device_node_index,
+                device_ip,
+                WakeOnLanConfig {
+                    mac: device_mac,
+                    destination: match directed_address {
+                        Ok(s) if window_binding.wake_on_lan_is_directed => WakeOnLanDestination::Directed(s),
+                        _ => WakeOnLanDestination::Broadcast,
+                    },
+                    port: window_binding.wake_on_lan_port.parse().unwrap_or(DEFAULT_WAKE_ON_LAN_PORT),
+                    secure_on_password: secure_on_password.and_then(|s| s.ok()),
+                    watch_for: (window_binding.wake_on_lan_should_watch && can_watch).then(|| {
+                        Duration::from_secs(window_binding.wake_on_lan_watch_s.parse().unwrap_or(DEFAULT_WAKE_ON_LAN_WATCH_S))
+                    }),
+                    source: app_context.app_state.project_settings.source.clone(),
+                },
+            );
+        });
+    }
+
+    // Sparkline of the latest pings, timeouts are the red bars
+    fn render_ping_history(ui: &mut egui::Ui, node_data: &NetworkTopologyNode) {
+        let history = &node_data.ping_history;
+        let replies = history.iter().filter_map(|s| s.rtt).collect::<Vec<Duration>>();
+        ui.label(format!(
+            "Uptime: {:.1}% ({} of {} pings answered since {})",
+            node_data.uptime_percentage().unwrap_or_default(),
+            replies.len(),
+            history.len(),
+            history[0].at.format("%Y-%m-%d %H:%M:%S")
+        ));
+        if let (Some(min), Some(max)) = (replies.iter().min(), replies.iter().max()) {
+            let avg = replies.iter().sum::<Duration>() / replies.len() as u32;
+            ui.label(format!(
+                "RTT min / avg / max: {:.1} / {:.1} / {:.1} ms",
+                min.as_secs_f64() * 1000.0,
+                avg.as_secs_f64() * 1000.0,
+                max.as_secs_f64() * 1000.0
+            ));
+        }
+
+        let shown = &history[history.len().saturating_sub(PING_SPARKLINE_LEN)..];
+        let (rect, response) = ui.allocate_exact_size(
+            Vec2::new(ui.available_width().min(300.0), 40.0),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+        let max_rtt = shown.iter().filter_map(|s| s.rtt).max().unwrap_or_default().as_secs_f32().max(f32::EPSILON);
+        let step = rect.width() / (PING_SPARKLINE_LEN - 1) as f32;
+        let mut previous_point: Option<egui::Pos2> = None;
+        for (i, record) in shown.iter().enumerate() {
+            let x = rect.left() + i as f32 * step;
+            let Some(rtt) = record.rtt else {
+                painter.line_segment([egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())], (1.0, get_latency_color(None)));
+                previous_point = None;
+                continue;
+            };
+            let point = egui::pos2(x, rect.bottom() - rtt.as_secs_f32() / max_rtt * (rect.height() - 4.0) - 2.0);
+            match previous_point {
+                Some(s) => painter.line_segment([s, point], (1.5, get_latency_color(Some(rtt)))),
+                None => painter.circle_filled(point, 1.5, get_latency_color(Some(rtt))),
+            }
+            previous_point = Some(point);
+        }
+        response.on_hover_text(format!(
+            "Last {} pings, top is {:.1} ms",
+            shown.len(),
+            max_rtt * 1000.0
+        ));
+    }
+
     fn get_mapped_all_common_ports() -> AllCommonPortsParsed {
         ALL_COMMON_PORTS.map(|s| (s.0, s.1.to_string(), s.2.to_string())).to_vec()
     }
 
+    // Checked every frame for every row, so no cloning here
+    fn has_port_raw_data(port: &PortInfo) -> bool {
+        port.banner.as_ref().is_some_and(|s| !s.result_raw.is_empty())
+            || port
+                .fuzzing_results
+                .iter()
+                .flatten()
+                .any(|s| !s.result_raw.is_empty())
+            || !port.access_findings.is_empty()
+    }
+
+    fn get_port_raw_data_sections(port: &PortInfo) -> Vec<RawDataViewerSection> {
+        let mut sections = vec![];
+        if let Some(banner) = port.banner.as_ref().filter(|s| !s.result_raw.is_empty()) {
+            sections.push(RawDataViewerSection {
+                title: "Banner".to_owned(),
+                data: banner.result_raw.clone(),
+            });
+        }
+        for fuzzing_result in port.fuzzing_results.iter().flatten() {
+            if !fuzzing_result.result_raw.is_empty() {
+                sections.push(RawDataViewerSection {
+                    title: format!("Fuzzing \"{}\"", fuzzing_result.command.trim_end()),
+                    data: fuzzing_result.result_raw.clone(),
+                });
+            }
+        }
+        for finding in port.access_findings.iter() {
+            sections.push(RawDataViewerSection {
+                title: format!("{} evidence", finding.check),
+                data: finding.evidence.clone(),
+            });
+        }
+
+        sections
+    }
+
     pub fn render(
         egui_context: &egui::Context,
         app_context: &mut WorkspaceContext,
@@ -181,6 +400,7 @@ impl DeviceWindowState {
                             .column(Column::initial(100.0).range(40.0..=300.0))
                             .column(Column::initial(100.0).at_least(40.0).resizable(true))
                             .column(Column::initial(100.0).at_least(40.0).resizable(true))
+                            .column(Column::initial(100.0).at_least(40.0).resizable(true))
                             .column(Column::remainder())
                             .min_scrolled_height(0.0);
 
@@ -197,6 +417,9 @@ impl DeviceWindowState {
                             header.col(|ui| {
                                 ui.strong("Fuzzing results");
                             });
+                            header.col(|ui| {
+                                ui.strong("Access findings");
+                            });
                             header.col(|ui| {
                                 ui.strong("Actions");
                             });
@@ -212,8 +435,8 @@ impl DeviceWindowState {
                                     });
                                     row.col(|ui| {
                                         match port.banner.clone() {
-                                            Some(banner) if !banner.is_empty() => {
-                                                ui.label("found").on_hover_text(banner);
+                                            Some(banner) if !banner.result_raw.is_empty() => {
+                                                ui.label("found").on_hover_text(banner.result);
                                             }
                                             _ => {
                                                 ui.label("not found");
@@ -241,6 +464,25 @@ impl DeviceWindowState {
                                         }
                                     });
                                     row.col(|ui| {
+                                        if port.access_findings.is_empty() {
+                                            ui.label("none");
+                                        } else {
+                                            let popup_text = port.access_findings
+                                                .iter()
+                                                .map(|s| format!("{} ({}): {}", s.check, s.found_at.format("%Y-%m-%d %H:%M:%S"), s.summary))
+                                                .collect::<Vec<String>>()
+                                                .join(LINE_ENDING);
+                                            ui.colored_label(egui::Color32::LIGHT_RED, format!("{} found", port.access_findings.len()))
+                                                .on_hover_text(popup_text);
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        if Self::has_port_raw_data(port) && ui.button("view raw").clicked() {
+                                            app_context.ui_state.raw_data_viewer_window_state.show(
+                                                format!("{}:{}", device_ip, port.number),
+                                                Self::get_port_raw_data_sections(port),
+                                            );
+                                        }
                                         if ui.button(TRASH_ICON).clicked() {
                                             index_to_delete = Some(i);
                                         }
@@ -316,10 +558,159 @@ impl DeviceWindowState {
                                         Arc::clone(&app_context.app_state.network_topology.graph),
                                         Arc::clone(&app_context.app_state.status_info),
                                         vec![new_node_data.ip],
+                                        app_context.app_state.project_settings.dns_resolver_config(),
+                                        app_context.app_state.project_settings.exclusions.clone(),
+                                        app_context.app_state.project_settings.source.clone(),
                                     );
                                 }
                             });
 
+                            if let Some(mac) = new_node_data.mac {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.horizontal(|ui| {
+                                    ui.label("MAC Address");
+                                    ui.add_enabled_ui(false, |ui| {
+                                        ui.text_edit_singleline(&mut mac.to_string())
+                                    });
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Vendor");
+                                    ui.label(lookup_mac_vendor(mac).unwrap_or("unknown".to_owned()));
+                                });
+                            }
+
+                            if !new_node_data.hostnames.is_empty() {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.collapsing(format!("Known hostnames ({})", new_node_data.hostnames.len()), |ui| {
+                                    for hostname in new_node_data.hostnames.iter() {
+                                        ui.label(format!("{} ({})", hostname.name, hostname.source));
+                                    }
+                                });
+                            }
+
+                            if let Some(netbios_info) = &new_node_data.netbios_info {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.collapsing("NetBIOS", |ui| {
+                                    if let Some(workgroup) = &netbios_info.workgroup {
+                                        ui.label(format!("Workgroup / domain: {}", workgroup));
+                                    }
+                                    if netbios_info.is_domain_controller {
+                                        ui.label("Domain controller");
+                                    }
+                                    if let Some(mac) = netbios_info.mac {
+                                        ui.label(format!("MAC Address: {}", mac));
+                                    }
+                                    for name in netbios_info.names.iter() {
+                                        ui.label(format!(
+                                            "{}<{:02x}> {}",
+                                            name.name,
+                                            name.suffix,
+                                            if name.is_group { "GROUP" } else { "UNIQUE" }
+                                        ));
+                                    }
+                                });
+                            }
+
+                            ui.add_space(DEFAULT_SPACER);
+                            ui.horizontal(|ui| {
+                                ui.label("SNMP");
+                                if ui.button("probe").on_hover_text("Uses communities from the SNMP probe window").clicked() {
+                                    match app_context.ui_state.snmp_probe_window_state.get_probe_config(&app_context.app_state.project_settings) {
+                                        Some(probe_config) => probe_snmp_list(
+                                            Arc::clone(&app_context.app_state.network_topology.graph),
+                                            Arc::clone(&app_context.app_state.status_info),
+                                            vec![new_node_data.ip],
+                                            probe_config,
+                                        ),
+                                        None => app_context.ui_state.snmp_probe_window_state.open = true,
+                                    }
+                                }
+                            });
+                            if let Some(snmp_info) = &new_node_data.snmp_info {
+                                ui.collapsing(format!("SNMP {} (community \"{}\")", snmp_info.version, snmp_info.community), |ui| {
+                                    ui.label(format!("Name: {}", snmp_info.sys_name));
+                                    ui.label(format!("Description: {}", snmp_info.sys_descr));
+                                    ui.label(format!("Object ID: {}", snmp_info.sys_object_id));
+                                    ui.label(format!("Uptime: {}", snmp_info.uptime_label()));
+                                    ui.label(format!("Contact: {}", snmp_info.sys_contact));
+                                    ui.label(format!("Location: {}", snmp_info.sys_location));
+
+                                    if !snmp_info.interfaces.is_empty() {
+                                        ui.collapsing(format!("Interfaces ({})", snmp_info.interfaces.len()), |ui| {
+                                            for interface in snmp_info.interfaces.iter() {
+                                                ui.label(format!(
+                                                    "{}: {} {} ({})",
+                                                    interface.index,
+                                                    interface.description,
+                                                    interface.mac.map(|s| s.to_string()).unwrap_or_default(),
+                                                    match interface.is_up {
+                                                        Some(true) => "up",
+                                                        Some(false) => "down",
+                                                        None => "unknown",
+                                                    }
+                                                ));
+                                            }
+                                        });
+                                    }
+                                    if !snmp_info.arp_entries.is_empty() {
+                                        ui.collapsing(format!("ARP table ({})", snmp_info.arp_entries.len()), |ui| {
+                                            for arp_entry in snmp_info.arp_entries.iter() {
+                                                ui.label(format!(
+                                                    "{} => {} (interface {})",
+                                                    arp_entry.ip, arp_entry.mac, arp_entry.interface_index
+                                                ));
+                                            }
+                                        });
+                                    }
+                                });
+                            }
+
+                            if let (Some(first_seen), Some(last_seen)) = (new_node_data.first_seen, new_node_data.last_seen) {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.label(format!("First seen: {}", first_seen.format("%Y-%m-%d %H:%M:%S")));
+                                ui.label(format!("Last seen: {}", last_seen.format("%Y-%m-%d %H:%M:%S")));
+                            }
+
+                            if !new_node_data.ping_history.is_empty() {
+                                ui.add_space(DEFAULT_SPACER);
+                                Self::render_ping_history(ui, &new_node_data);
+                            }
+
+                            if !new_node_data.mdns_services.is_empty() {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.collapsing(format!("mDNS services ({})", new_node_data.mdns_services.len()), |ui| {
+                                    for service in new_node_data.mdns_services.iter() {
+                                        ui.label(format!(
+                                            "{} ({}) - {}:{}",
+                                            service.instance_label(),
+                                            service.service_label(),
+                                            service.hostname,
+                                            service.port
+                                        ))
+                                        .on_hover_text(service.txt_records.join(LINE_ENDING));
+                                    }
+                                });
+                            }
+
+                            if !new_node_data.upnp_devices.is_empty() {
+                                ui.add_space(DEFAULT_SPACER);
+                                ui.collapsing(format!("UPnP devices ({})", new_node_data.upnp_devices.len()), |ui| {
+                                    for device in new_node_data.upnp_devices.iter() {
+                                        ui.label(device.title()).on_hover_text(
+                                            [
+                                                format!("Location: {}", device.location),
+                                                format!("Server: {}", device.server),
+                                                format!("Device type: {}", device.device_type),
+                                                format!("Model number: {}", device.model_number),
+                                                format!("Model description: {}", device.model_description),
+                                                format!("Services:{}{}", LINE_ENDING, device.services.join(LINE_ENDING)),
+                                            ]
+                                            .join(LINE_ENDING),
+                                        );
+                                    }
+                                });
+                            }
+
                             ui.add_space(DEFAULT_SPACER);
                             ui.vertical(|ui| {
                                 ui.label("Notes (optional)");
@@ -490,6 +881,14 @@ impl DeviceWindowState {
                                 ).on_hover_text("You probably want to set read/write timeout to extremelly small amount of time.");
                             });
 
+                            ui.add_space(DEFAULT_SPACER);
+                            ui.horizontal(|ui| {
+                                ui.checkbox(
+                                    &mut window_binding.port_scan_settings_should_check_access,
+                                    "Should check for access without credentials",
+                                ).on_hover_text("Only on systems you're authorised to assess. Tries anonymous FTP login, Redis PING without AUTH, unauthenticated MongoDB / Elasticsearch / Memcached, SMB null session and HTTP directory listing on the identified services.");
+                            });
+
                             if window_binding.port_scan_settings_should_banner_grab || window_binding.port_scan_settings_should_fuzz {
                                 ui.add_space(DEFAULT_SPACER);
                                 ui.horizontal(|ui| {
@@ -568,6 +967,7 @@ impl DeviceWindowState {
                                     scap_ip_ports(
                                         Arc::clone(&app_context.app_state.network_topology.graph),
                                         Arc::clone(&app_context.app_state.status_info),
+                                        Arc::clone(&app_context.app_state.scan_snapshots),
                                         device_ip, 
                                         ports_to_try, 
                                         device_node_index,
@@ -575,7 +975,11 @@ impl DeviceWindowState {
                                             connection_timeout_ms: window_binding.port_scan_settings_connection_timeout_ms.parse().unwrap_or(1),
                                             should_banner_grab: window_binding.port_scan_settings_should_banner_grab,
                                             should_fuzz: window_binding.port_scan_settings_should_fuzz,
+                                            should_check_access: window_binding.port_scan_settings_should_check_access,
                                             read_write_timeout_ms: window_binding.port_scan_settings_read_write_timeout_ms.parse().unwrap_or(1),
+                                            exclusions: app_context.app_state.project_settings.exclusions.clone(),
+                                            source: app_context.app_state.project_settings.source.clone(),
+                                            proxy: app_context.app_state.project_settings.proxy.clone(),
                                         }
                                     );
                                 }
@@ -587,6 +991,12 @@ impl DeviceWindowState {
                             }
                         }
                         SubWindowType::Actions => {
+                            let device_mac = app_context.app_state.network_topology.graph.lock().unwrap()
+                                .node_weight(device_node_index)
+                                .and_then(|s| s.data().unwrap().mac);
+                            Self::render_wake_on_lan(ui, app_context, device_window_state_index, device_node_index, device_ip, device_mac);
+
+                            ui.separator();
                             ui.vertical_centered_justified(|ui| {
                                 ui.add_space(ACTION_SPACER);
                                 if ui.button("Delete").clicked() {
@@ -0,0 +1,168 @@
+use eframe::{egui, epaint::Vec2};
+use std::sync::Arc;
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        dns_resolver::is_valid_hostname,
+        domain_recon::{start_domain_recon, DomainReconConfig, DEFAULT_SUBDOMAIN_WORDLIST},
+        general::render_validation_err,
+    },
+};
+
+pub struct DomainReconWindowState {
+    pub open: bool,
+
+    pub domain: String,
+    pub domain_validation_err: bool,
+    pub wordlist_path: String, // empty => built-in wordlist
+    pub wordlist_err: Option<String>,
+
+    pub settings_enumerate_records: bool,
+    pub settings_attempt_zone_transfer: bool,
+    pub settings_brute_force_subdomains: bool,
+}
+
+impl Default for DomainReconWindowState {
+    fn default() -> Self {
+        Self {
+            open: false,
+
+            domain: "".to_owned(),
+            domain_validation_err: false,
+            wordlist_path: "".to_owned(),
+            wordlist_err: None,
+
+            settings_enumerate_records: true,
+            settings_attempt_zone_transfer: true,
+            settings_brute_force_subdomains: true,
+        }
+    }
+}
+
+impl DomainReconWindowState {
+    fn load_wordlist(&self) -> anyhow::Result<Vec<String>> {
+        if self.wordlist_path.trim().is_empty() {
+            return Ok(DEFAULT_SUBDOMAIN_WORDLIST
+                .iter()
+                .map(|s| s.to_string())
+                .collect());
+        }
+
+        Ok(std::fs::read_to_string(self.wordlist_path.trim())?
+            .lines()
+            .map(|s| s.trim().trim_end_matches('.').to_lowercase())
+            .filter(|s| !s.is_empty() && !s.starts_with('#'))
+            .collect())
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.domain_recon_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("Domain reconnaissance")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .fixed_size(Vec2::new(275.0, 250.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.domain_recon_window_state;
+
+                ui.vertical_centered(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Domain");
+                        ui.text_edit_singleline(&mut window_state.domain);
+                    });
+                    render_validation_err(
+                        ui,
+                        window_state.domain_validation_err,
+                        "Domain is not valid.",
+                    );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.checkbox(
+                        &mut window_state.settings_enumerate_records,
+                        "Enumerate records (A, AAAA, MX, NS, TXT, SOA, SRV)",
+                    );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.checkbox(
+                        &mut window_state.settings_attempt_zone_transfer,
+                        "Attempt zone transfer (AXFR) from every name server",
+                    );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.checkbox(
+                        &mut window_state.settings_brute_force_subdomains,
+                        "Brute force subdomains",
+                    );
+                    ui.add_enabled_ui(window_state.settings_brute_force_subdomains, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Wordlist file");
+                            ui.text_edit_singleline(&mut window_state.wordlist_path)
+                                .on_hover_text(
+                                "One subdomain per line. Leave empty to use the built-in wordlist.",
+                            );
+                        });
+                    });
+                    render_validation_err(
+                        ui,
+                        window_state.wordlist_err.is_some(),
+                        window_state.wordlist_err.as_deref().unwrap_or_default(),
+                    );
+
+                    ui.add_space(ACTION_SPACER);
+                    if ui.button("Start").clicked() {
+                        let domain = window_state
+                            .domain
+                            .trim()
+                            .trim_end_matches('.')
+                            .to_lowercase();
+                        window_state.domain_validation_err = !is_valid_hostname(&domain);
+                        window_state.wordlist_err = None;
+
+                        let wordlist = if window_state.settings_brute_force_subdomains {
+                            match window_state.load_wordlist() {
+                                Ok(s) => s,
+                                Err(e) => {
+                                    window_state.wordlist_err =
+                                        Some(format!("Unable to read wordlist => {}", e));
+                                    vec![]
+                                }
+                            }
+                        } else {
+                            vec![]
+                        };
+
+                        if !window_state.domain_validation_err
+                            && window_state.wordlist_err.is_none()
+                        {
+                            start_domain_recon(
+                                Arc::clone(&app_context.app_state.network_topology.graph),
+                                Arc::clone(&app_context.app_state.status_info),
+                                DomainReconConfig {
+                                    domain,
+                                    enumerate_records: window_state.settings_enumerate_records,
+                                    attempt_zone_transfer: window_state
+                                        .settings_attempt_zone_transfer,
+                                    brute_force_subdomains: window_state
+                                        .settings_brute_force_subdomains,
+                                    wordlist,
+                                    dns_resolver: app_context
+                                        .app_state
+                                        .project_settings
+                                        .dns_resolver_config(),
+                                },
+                            );
+                            window_state.open = false;
+                        }
+                    }
+                });
+            });
+
+        app_context.ui_state.domain_recon_window_state.open &= should_show_window;
+    }
+}
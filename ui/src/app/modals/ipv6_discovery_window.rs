@@ -0,0 +1,167 @@
+use eframe::{egui, epaint::Vec2};
+use std::sync::Arc;
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        general::{render_numeric_textbox, render_validation_err},
+        interfaces::SourceBinding,
+        ndp::{
+            get_ipv6_discovery_interfaces, start_ipv6_neighbor_discovery,
+            Ipv6NeighborDiscoveryConfig, DEFAULT_NEIGHBOR_DISCOVERY_LISTEN_MS,
+        },
+    },
+};
+
+pub struct Ipv6DiscoveryWindowState {
+    pub open: bool,
+
+    // (interface name, label to render)
+    interfaces: Vec<(String, String)>,
+    selected_interface: String, // empty => all interfaces
+    settings_listen_ms: String,
+
+    start_err: Option<String>,
+}
+
+impl Default for Ipv6DiscoveryWindowState {
+    fn default() -> Self {
+        let mut new_state = Self {
+            open: false,
+
+            interfaces: vec![],
+            selected_interface: "".to_owned(),
+            settings_listen_ms: DEFAULT_NEIGHBOR_DISCOVERY_LISTEN_MS.to_string(),
+
+            start_err: None,
+        };
+        new_state.refresh_interfaces();
+        new_state
+    }
+}
+
+impl Ipv6DiscoveryWindowState {
+    // Starts on the project's source interface, if there is one
+    pub fn show(&mut self, source: &SourceBinding) {
+        self.open = true;
+        self.refresh_interfaces();
+        if let Some(interface_name) = source.interface_name.as_ref() {
+            if self.interfaces.iter().any(|s| &s.0 == interface_name) {
+                self.selected_interface = interface_name.clone();
+            }
+        }
+    }
+
+    fn refresh_interfaces(&mut self) {
+        self.interfaces = vec![("".to_owned(), "All interfaces".to_owned())];
+        self.interfaces
+            .extend(get_ipv6_discovery_interfaces().iter().map(|s| {
+                (
+                    s.name.clone(),
+                    format!(
+                        "{} ({})",
+                        s.name,
+                        s.ips
+                            .iter()
+                            .filter(|s| s.is_ipv6())
+                            .map(|s| s.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                )
+            }));
+
+        if !self
+            .interfaces
+            .iter()
+            .any(|s| s.0 == self.selected_interface)
+        {
+            self.selected_interface = "".to_owned();
+        }
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.ipv6_discovery_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("IPv6 neighbor discovery")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .fixed_size(Vec2::new(275.0, 250.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.ipv6_discovery_window_state;
+
+                ui.vertical_centered(|ui| {
+                    ui.label("Pings all nodes (ff02::1) and sends neighbor solicitations on the local link.");
+                    ui.add_space(DEFAULT_SPACER);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Interface");
+                        let selected_label = window_state
+                            .interfaces
+                            .iter()
+                            .find(|s| s.0 == window_state.selected_interface)
+                            .map(|s| s.1.clone())
+                            .unwrap_or_default();
+                        egui::ComboBox::from_id_source("ipv6_discovery_interface")
+                            .selected_text(selected_label)
+                            .show_ui(ui, |ui| {
+                                for (name, label) in window_state.interfaces.iter() {
+                                    ui.selectable_value(
+                                        &mut window_state.selected_interface,
+                                        name.clone(),
+                                        label,
+                                    );
+                                }
+                            });
+                        if ui.button("⟳").on_hover_text("Refresh interfaces").clicked() {
+                            window_state.refresh_interfaces();
+                        }
+                    });
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Listen for (ms)");
+                        render_numeric_textbox(ui, &mut window_state.settings_listen_ms);
+                    });
+
+                    render_validation_err(
+                        ui,
+                        window_state.start_err.is_some(),
+                        window_state.start_err.as_deref().unwrap_or_default(),
+                    );
+
+                    ui.add_space(ACTION_SPACER);
+                    if ui.button("Start discovery").clicked() {
+                        window_state.start_err = None;
+                        match start_ipv6_neighbor_discovery(
+                            Arc::clone(&app_context.app_state.network_topology.graph),
+                            Arc::clone(&app_context.app_state.status_info),
+                            Ipv6NeighborDiscoveryConfig {
+                                interface_name: (!window_state.selected_interface.is_empty())
+                                    .then(|| window_state.selected_interface.clone()),
+                                listen_ms: window_state
+                                    .settings_listen_ms
+                                    .parse()
+                                    .unwrap_or(DEFAULT_NEIGHBOR_DISCOVERY_LISTEN_MS),
+                            },
+                        ) {
+                            Ok(_) => window_state.open = false,
+                            Err(e) => {
+                                window_state.start_err = Some(format!(
+                                    "Unable to start discovery (are you running as root/admin?) => {}",
+                                    e
+                                ))
+                            }
+                        }
+                    }
+                });
+            });
+
+        app_context.ui_state.ipv6_discovery_window_state.open &= should_show_window;
+    }
+}
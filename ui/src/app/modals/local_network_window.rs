@@ -0,0 +1,159 @@
+use eframe::{egui, epaint::Vec2};
+use ipnet::IpNet;
+use pnet::datalink::NetworkInterface;
+use std::sync::Arc;
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{
+            ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS, RAW_SOCKETS_WITH_PROXY_HINT,
+        },
+        general::render_validation_err,
+        interfaces::get_source_interfaces,
+        routes::{add_default_gateways, get_routes, Route},
+    },
+};
+
+// Snapshot of the host's interfaces and routing table, refreshed on demand
+#[derive(Default)]
+pub struct LocalNetworkWindowState {
+    pub open: bool,
+
+    interfaces: Vec<NetworkInterface>,
+    routes: Vec<Route>,
+    routes_err: Option<String>,
+}
+
+impl LocalNetworkWindowState {
+    pub fn show(&mut self) {
+        self.open = true;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.interfaces = get_source_interfaces();
+        match get_routes() {
+            Ok(s) => {
+                self.routes = s;
+                self.routes_err = None;
+            }
+            Err(e) => {
+                self.routes = vec![];
+                self.routes_err = Some(format!("Unable to read the routing table => {}", e));
+            }
+        }
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.local_network_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        let mut subnet_to_scan: Option<IpNet> = None;
+        egui::Window::new("Local network")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .default_size(Vec2::new(400.0, 300.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.local_network_window_state;
+                let is_proxy_active = app_context.app_state.project_settings.proxy.is_some();
+
+                ui.strong("Interfaces");
+                for interface in window_state.interfaces.iter() {
+                    ui.horizontal(|ui| {
+                        ui.label(&interface.name);
+                        if let Some(mac) = interface.mac {
+                            ui.weak(mac.to_string());
+                        }
+                    });
+                    for network in interface.ips.iter() {
+                        ui.horizontal(|ui| {
+                            ui.add_space(ACTION_SPACER);
+                            ui.label(format!(
+                                "{}/{} (subnet {}/{})",
+                                network.ip(),
+                                network.prefix(),
+                                network.network(),
+                                network.prefix()
+                            ));
+                            let subnet = IpNet::new(network.ip(), network.prefix())
+                                .map(|s| s.trunc())
+                                .ok()
+                                .filter(|s| s.addr().is_ipv4() && s.prefix_len() < 32);
+                            if let Some(subnet) = subnet {
+                                if ui
+                                    .add_enabled(!is_proxy_active, egui::Button::new("Scan subnet"))
+                                    .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
+                                    .clicked()
+                                {
+                                    subnet_to_scan = Some(subnet);
+                                }
+                            }
+                        });
+                    }
+                }
+
+                ui.add_space(DEFAULT_SPACER);
+                ui.separator();
+                ui.strong("Routes");
+                render_validation_err(
+                    ui,
+                    window_state.routes_err.is_some(),
+                    window_state.routes_err.as_deref().unwrap_or_default(),
+                );
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("local_network_routes")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Destination");
+                                ui.strong("Gateway");
+                                ui.strong("Interface");
+                                ui.strong("Metric");
+                                ui.end_row();
+                                for route in window_state.routes.iter() {
+                                    ui.label(if route.is_default() {
+                                        format!("default ({})", route.destination)
+                                    } else {
+                                        route.destination.to_string()
+                                    });
+                                    ui.label(
+                                        route
+                                            .gateway
+                                            .map(|s| s.to_string())
+                                            .unwrap_or("directly connected".to_owned()),
+                                    );
+                                    ui.label(&route.interface_name);
+                                    ui.label(route.metric.to_string());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.add_space(ACTION_SPACER);
+                ui.horizontal(|ui| {
+                    if ui.button("Add default gateway(s) to topology").clicked() {
+                        add_default_gateways(
+                            Arc::clone(&app_context.app_state.network_topology.graph),
+                            Arc::clone(&app_context.app_state.status_info),
+                        );
+                    }
+                    if ui.button("⟳").on_hover_text("Refresh").clicked() {
+                        window_state.refresh();
+                    }
+                });
+            });
+
+        if let Some(subnet) = subnet_to_scan {
+            app_context
+                .ui_state
+                .scan_ip_range_window_state
+                .show_with_subnet(subnet);
+        }
+        app_context.ui_state.local_network_window_state.open &= should_show_window;
+    }
+}
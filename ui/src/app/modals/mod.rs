@@ -1,4 +1,13 @@
 pub mod add_new_device_window;
 pub mod device_window_state;
+pub mod domain_recon_window;
 pub mod generic_info_window;
+pub mod ipv6_discovery_window;
+pub mod local_network_window;
+pub mod monitoring_window;
+pub mod passive_discovery_window;
+pub mod project_settings_window;
+pub mod raw_data_viewer_window;
+pub mod scan_diff_window;
 pub mod scan_ip_range_window;
+pub mod snmp_probe_window;
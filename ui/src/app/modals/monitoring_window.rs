@@ -0,0 +1,264 @@
+use eframe::{egui, epaint::Vec2};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        general::{render_numeric_textbox, render_validation_err},
+        monitoring::{
+            start_monitoring, MonitoringConfig, MonitoringHistoryRef,
+            DEFAULT_MONITORING_INTERVAL_MIN, DEFAULT_MONITORING_PORTS,
+        },
+        ports::parse_port_list,
+        targets::{parse_target_expressions, TargetExpressions},
+    },
+};
+
+pub struct MonitoringWindowState {
+    pub open: bool,
+
+    targets: String,
+    include_project_nodes: bool,
+    ports: String,
+    interval_min: String,
+    ping_timeout_ms: String,
+    connection_timeout_ms: String,
+    should_notify: bool,
+
+    validation_errs: Vec<String>,
+    history: MonitoringHistoryRef,
+    running_monitoring_should_stop: Option<Arc<AtomicBool>>,
+}
+
+impl Default for MonitoringWindowState {
+    fn default() -> Self {
+        Self {
+            open: false,
+
+            targets: "".to_owned(),
+            include_project_nodes: true,
+            ports: DEFAULT_MONITORING_PORTS.to_owned(),
+            interval_min: DEFAULT_MONITORING_INTERVAL_MIN.to_string(),
+            ping_timeout_ms: "1000".to_owned(),
+            connection_timeout_ms: "250".to_owned(),
+            should_notify: true,
+
+            validation_errs: vec![],
+            history: MonitoringHistoryRef::default(),
+            running_monitoring_should_stop: None,
+        }
+    }
+}
+
+impl MonitoringWindowState {
+    fn is_running(&self) -> bool {
+        self.running_monitoring_should_stop
+            .as_ref()
+            .is_some_and(|s| !s.load(Ordering::Relaxed))
+    }
+
+    pub fn unseen_alerts(&self) -> usize {
+        self.history.lock().unwrap().unseen_alerts
+    }
+
+    fn read_targets(&mut self) -> Option<TargetExpressions> {
+        if self.targets.trim().is_empty() {
+            if !self.include_project_nodes {
+                self.validation_errs.push(
+                    "There's nothing to monitor, add targets or include the project's nodes."
+                        .to_owned(),
+                );
+                return None;
+            }
+            return Some(TargetExpressions::default());
+        }
+
+        match parse_target_expressions(&self.targets) {
+            Ok(s) => Some(s),
+            Err(errs) => {
+                self.validation_errs
+                    .extend(errs.iter().map(|s| s.to_string()));
+                None
+            }
+        }
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.monitoring_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("Monitoring")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .default_size(Vec2::new(400.0, 400.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.monitoring_window_state;
+                let is_running = window_state.is_running();
+                let is_proxy_active = app_context.app_state.project_settings.proxy.is_some();
+
+                ui.label("Re-runs the discovery and port scan on schedule, every run gets compared with the previous one.");
+                ui.add_space(DEFAULT_SPACER);
+                ui.add_enabled_ui(!is_running, |ui| {
+                    ui.label("Targets (IPs, ranges, CIDRs or hostnames)");
+                    ui.text_edit_multiline(&mut window_state.targets);
+                    ui.checkbox(
+                        &mut window_state.include_project_nodes,
+                        "Include every node of the project",
+                    );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Ports");
+                        ui.text_edit_singleline(&mut window_state.ports)
+                            .on_hover_text("e.g. 22, 80, 8000-8100. Leave empty to only watch hosts come and go (IPv4 only, IPv6 hosts are up when one of the ports is open).");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Every (min)");
+                        render_numeric_textbox(ui, &mut window_state.interval_min);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Ping timeout (ms)");
+                        render_numeric_textbox(ui, &mut window_state.ping_timeout_ms);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Connection timeout (ms)");
+                        render_numeric_textbox(ui, &mut window_state.connection_timeout_ms);
+                    });
+                    ui.checkbox(&mut window_state.should_notify, "Desktop notification on changes");
+                    if is_proxy_active {
+                        ui.label("Proxy is set, so hosts count as up when one of the ports is open (pings need raw sockets).");
+                    }
+                });
+                for err in window_state.validation_errs.iter() {
+                    render_validation_err(ui, true, err);
+                }
+
+                ui.add_space(ACTION_SPACER);
+                if is_running {
+                    ui.horizontal(|ui| {
+                        match window_state.history.lock().unwrap().next_run_at {
+                            Some(s) => ui.label(format!("Next run at {}", s.format("%H:%M:%S"))),
+                            None => ui.label("Running..."),
+                        };
+                        if ui.button("Stop").clicked() {
+                            if let Some(should_stop) = window_state.running_monitoring_should_stop.take() {
+                                should_stop.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    });
+                } else if ui.button("Start monitoring").clicked() {
+                    window_state.validation_errs = vec![];
+                    let targets = window_state.read_targets();
+                    let ports = parse_port_list(&window_state.ports).map_err(|invalid_entries| {
+                        window_state.validation_errs.extend(
+                            invalid_entries
+                                .iter()
+                                .map(|s| format!("{} is not valid port or port range.", s)),
+                        )
+                    });
+                    let max_targets = app_context.app_state.project_settings.max_targets_without_confirmation;
+                    if let Some(targets) = targets.as_ref().filter(|s| s.count() > max_targets as u128) {
+                        window_state.validation_errs.push(format!(
+                            "{} targets is too many to monitor (project limit is {}).",
+                            targets.count(),
+                            max_targets
+                        ));
+                    }
+                    if is_proxy_active && ports.as_ref().is_ok_and(|s| s.is_empty()) {
+                        window_state.validation_errs.push("With a proxy set, at least one port is needed to tell whether a host is up.".to_owned());
+                    }
+
+                    if let (Some(targets), Ok(ports), true) = (targets, ports, window_state.validation_errs.is_empty()) {
+                        let project_settings = &app_context.app_state.project_settings;
+                        window_state.running_monitoring_should_stop = Some(start_monitoring(
+                            Arc::clone(&app_context.app_state.network_topology.graph),
+                            Arc::clone(&app_context.app_state.status_info),
+                            Arc::clone(&app_context.app_state.scan_snapshots),
+                            Arc::clone(&window_state.history),
+                            MonitoringConfig {
+                                targets,
+                                include_project_nodes: window_state.include_project_nodes,
+                                ports,
+                                interval: Duration::from_secs(
+                                    window_state
+                                        .interval_min
+                                        .parse::<u64>()
+                                        .unwrap_or(DEFAULT_MONITORING_INTERVAL_MIN)
+                                        .max(1)
+                                        * 60,
+                                ),
+                                ping_timeout_ms: window_state.ping_timeout_ms.parse().unwrap_or(1000),
+                                connection_timeout_ms: window_state.connection_timeout_ms.parse().unwrap_or(250),
+                                should_notify: window_state.should_notify,
+                                dns_resolver: project_settings.dns_resolver_config(),
+                                exclusions: project_settings.exclusions.clone(),
+                                source: project_settings.source.clone(),
+                                proxy: project_settings.proxy.clone(),
+                            },
+                        ));
+                    }
+                }
+
+                let mut history = window_state.history.lock().unwrap();
+                history.unseen_alerts = 0;
+
+                ui.add_space(DEFAULT_SPACER);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.strong(format!("Alerts ({})", history.alerts.len()));
+                    if !history.alerts.is_empty() && ui.button("Clear").clicked() {
+                        history.alerts.clear();
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .id_source("monitoring_alerts")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        for alert in history.alerts.iter().rev() {
+                            ui.label(format!(
+                                "{}: {}",
+                                alert.raised_at.format("%Y-%m-%d %H:%M:%S"),
+                                alert.change
+                            ));
+                        }
+                    });
+
+                ui.add_space(DEFAULT_SPACER);
+                ui.separator();
+                ui.strong(format!("Runs ({})", history.runs.len()));
+                egui::ScrollArea::vertical()
+                    .id_source("monitoring_runs")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("monitoring_runs_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Finished");
+                                ui.strong("Hosts up");
+                                ui.strong("Ports open");
+                                ui.strong("Changes");
+                                ui.end_row();
+                                for run in history.runs.iter().rev() {
+                                    ui.label(run.finished_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                                    ui.label(run.hosts_up.to_string());
+                                    ui.label(run.ports_open.to_string());
+                                    ui.label(run.changes.to_string());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        app_context.ui_state.monitoring_window_state.open &= should_show_window;
+    }
+}
@@ -0,0 +1,178 @@
+use eframe::{egui, epaint::Vec2};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        general::render_validation_err,
+        passive::{
+            get_passive_discovery_interfaces, start_passive_discovery, PassiveDiscoveryConfig,
+        },
+    },
+};
+
+pub struct PassiveDiscoveryWindowState {
+    pub open: bool,
+
+    // (interface name, label to render)
+    interfaces: Vec<(String, String)>,
+    selected_interface: String,
+    settings_only_local_subnets: bool,
+
+    start_err: Option<String>,
+    running_discovery_should_stop: Option<Arc<AtomicBool>>,
+}
+
+impl Default for PassiveDiscoveryWindowState {
+    fn default() -> Self {
+        let mut new_state = Self {
+            open: false,
+
+            interfaces: vec![],
+            selected_interface: "".to_owned(),
+            settings_only_local_subnets: true,
+
+            start_err: None,
+            running_discovery_should_stop: None,
+        };
+        new_state.refresh_interfaces();
+        new_state
+    }
+}
+
+impl PassiveDiscoveryWindowState {
+    fn refresh_interfaces(&mut self) {
+        self.interfaces = get_passive_discovery_interfaces()
+            .iter()
+            .map(|s| {
+                (
+                    s.name.clone(),
+                    format!(
+                        "{} ({})",
+                        s.name,
+                        s.ips
+                            .iter()
+                            .map(|s| s.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                )
+            })
+            .collect();
+
+        if !self
+            .interfaces
+            .iter()
+            .any(|s| s.0 == self.selected_interface)
+        {
+            self.selected_interface = self
+                .interfaces
+                .first()
+                .map(|s| s.0.clone())
+                .unwrap_or_default();
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.running_discovery_should_stop
+            .as_ref()
+            .is_some_and(|s| !s.load(Ordering::Relaxed))
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.passive_discovery_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("Passive discovery")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .fixed_size(Vec2::new(275.0, 250.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.passive_discovery_window_state;
+                let is_running = window_state.is_running();
+
+                ui.vertical_centered(|ui| {
+                    ui.label("Only listens to the traffic on the selected interface, no packets are sent.");
+                    ui.add_space(DEFAULT_SPACER);
+
+                    ui.add_enabled_ui(!is_running, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Interface");
+                            let selected_label = window_state
+                                .interfaces
+                                .iter()
+                                .find(|s| s.0 == window_state.selected_interface)
+                                .map(|s| s.1.clone())
+                                .unwrap_or_default();
+                            egui::ComboBox::from_id_source("passive_discovery_interface")
+                                .selected_text(selected_label)
+                                .show_ui(ui, |ui| {
+                                    for (name, label) in window_state.interfaces.iter() {
+                                        ui.selectable_value(
+                                            &mut window_state.selected_interface,
+                                            name.clone(),
+                                            label,
+                                        );
+                                    }
+                                });
+                            if ui.button("⟳").on_hover_text("Refresh interfaces").clicked() {
+                                window_state.refresh_interfaces();
+                            }
+                        });
+
+                        ui.add_space(DEFAULT_SPACER);
+                        ui.checkbox(
+                            &mut window_state.settings_only_local_subnets,
+                            "Only learn hosts from local subnets",
+                        )
+                        .on_hover_text("Otherwise every ip seen in the traffic (including internet hosts) becomes a node.");
+                    });
+
+                    render_validation_err(
+                        ui,
+                        window_state.start_err.is_some(),
+                        window_state.start_err.as_deref().unwrap_or_default(),
+                    );
+
+                    ui.add_space(ACTION_SPACER);
+                    if is_running {
+                        ui.label(format!("Listening on {}...", window_state.selected_interface));
+                        if ui.button("Stop").clicked() {
+                            if let Some(should_stop) = window_state.running_discovery_should_stop.take() {
+                                should_stop.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    } else if ui.button("Start listening").clicked() {
+                        window_state.start_err = None;
+                        match start_passive_discovery(
+                            Arc::clone(&app_context.app_state.network_topology.graph),
+                            Arc::clone(&app_context.app_state.status_info),
+                            PassiveDiscoveryConfig {
+                                interface_name: window_state.selected_interface.clone(),
+                                only_local_subnets: window_state.settings_only_local_subnets,
+                            },
+                        ) {
+                            Ok(should_stop) => {
+                                window_state.running_discovery_should_stop = Some(should_stop)
+                            }
+                            Err(e) => {
+                                window_state.start_err = Some(format!(
+                                    "Unable to start listening (are you running as root/admin?) => {}",
+                                    e
+                                ))
+                            }
+                        }
+                    }
+                });
+            });
+
+        app_context.ui_state.passive_discovery_window_state.open &= should_show_window;
+    }
+}
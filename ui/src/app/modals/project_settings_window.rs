@@ -0,0 +1,421 @@
+use eframe::{egui, epaint::Vec2};
+use pnet::datalink::NetworkInterface;
+use std::{
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    app::{
+        network_topology::NetworkTopology,
+        workspace_models::{ProjectSettings, WorkspaceContext},
+    },
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        dns_resolver::{parse_dns_server, DEFAULT_DNS_TIMEOUT_MS},
+        general::{render_numeric_textbox, render_validation_err},
+        interfaces::{get_source_interfaces, SourceBinding},
+        oui::{get_oui_table_len, load_oui_table_from_file},
+        proxy::{ProxyConfig, ProxyKind},
+        targets::{TargetExclusions, DEFAULT_MAX_TARGETS_WITHOUT_CONFIRMATION},
+    },
+};
+
+// Holds the edited (not yet saved) values, project settings are only touched on save
+#[derive(Default)]
+pub struct ProjectSettingsWindowState {
+    pub open: bool,
+
+    pub dns_servers: String, // one per line
+    pub dns_servers_validation_err: Vec<String>,
+    pub dns_timeout_ms: String,
+    pub dns_force_tcp: bool,
+
+    pub exclusions: String, // one IP, range or CIDR per line
+    pub exclusions_validation_err: Vec<String>,
+    pub max_targets_without_confirmation: String,
+
+    interfaces: Vec<NetworkInterface>,
+    pub source_interface: String, // empty => default route
+    pub source_address: String,   // empty => picked by the OS / from the interface
+
+    pub proxy_kind: Option<ProxyKind>, // None => connect directly
+    pub proxy_address: String,         // host:port
+    pub proxy_address_validation_err: bool,
+    pub proxy_username: String, // empty => no authentication
+    pub proxy_password: String,
+
+    pub oui_table_path: String,
+    pub oui_table_result: Option<Result<String, String>>,
+}
+
+impl ProjectSettingsWindowState {
+    pub fn show(&mut self, project_settings: &ProjectSettings) {
+        *self = Self {
+            open: true,
+
+            dns_servers: project_settings
+                .dns_servers
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            dns_servers_validation_err: vec![],
+            dns_timeout_ms: project_settings.dns_timeout_ms.to_string(),
+            dns_force_tcp: project_settings.dns_force_tcp,
+
+            exclusions: project_settings.exclusions.to_text(),
+            exclusions_validation_err: vec![],
+            max_targets_without_confirmation: project_settings
+                .max_targets_without_confirmation
+                .to_string(),
+
+            interfaces: get_source_interfaces(),
+            source_interface: project_settings
+                .source
+                .interface_name
+                .clone()
+                .unwrap_or_default(),
+            source_address: project_settings
+                .source
+                .address
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+
+            proxy_kind: project_settings.proxy.as_ref().map(|s| s.kind),
+            proxy_address: project_settings
+                .proxy
+                .as_ref()
+                .map(|s| s.address.to_string())
+                .unwrap_or("127.0.0.1:1080".to_owned()),
+            proxy_address_validation_err: false,
+            proxy_username: project_settings
+                .proxy
+                .as_ref()
+                .and_then(|s| s.credentials.as_ref())
+                .map(|s| s.0.clone())
+                .unwrap_or_default(),
+            proxy_password: project_settings
+                .proxy
+                .as_ref()
+                .and_then(|s| s.credentials.as_ref())
+                .map(|s| s.1.clone())
+                .unwrap_or_default(),
+
+            oui_table_path: self.oui_table_path.clone(),
+            oui_table_result: None,
+        };
+    }
+
+    // Err => address doesn't resolve
+    fn read_proxy(&self) -> Result<Option<ProxyConfig>, ()> {
+        let Some(kind) = self.proxy_kind else {
+            return Ok(None);
+        };
+        let address = self
+            .proxy_address
+            .trim()
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut s| s.next())
+            .ok_or(())?;
+        Ok(Some(ProxyConfig {
+            kind,
+            address,
+            credentials: (!self.proxy_username.is_empty())
+                .then(|| (self.proxy_username.clone(), self.proxy_password.clone())),
+        }))
+    }
+
+    fn render_proxy_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Proxy");
+            ui.selectable_value(&mut self.proxy_kind, None, "None");
+            ui.selectable_value(&mut self.proxy_kind, Some(ProxyKind::Socks5), "SOCKS5");
+            ui.selectable_value(
+                &mut self.proxy_kind,
+                Some(ProxyKind::HttpConnect),
+                "HTTP CONNECT",
+            );
+        });
+        ui.add_enabled_ui(self.proxy_kind.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Address");
+                ui.text_edit_singleline(&mut self.proxy_address)
+                    .on_hover_text("host:port, e.g. 127.0.0.1:1080 for `ssh -D 1080`");
+            });
+            render_validation_err(
+                ui,
+                self.proxy_address_validation_err,
+                "Proxy address is not valid host:port.",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Username");
+                ui.text_edit_singleline(&mut self.proxy_username)
+                    .on_hover_text("Leave empty if the proxy doesn't require authentication.");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Password");
+                ui.add(egui::TextEdit::singleline(&mut self.proxy_password).password(true));
+            });
+        });
+    }
+
+    // (address, label) of the selected interface, or of every interface when none is selected
+    fn source_addresses(&self) -> Vec<(String, String)> {
+        self.interfaces
+            .iter()
+            .filter(|s| self.source_interface.is_empty() || s.name == self.source_interface)
+            .flat_map(|interface| {
+                interface.ips.iter().map(|s| {
+                    (
+                        s.ip().to_string(),
+                        format!(
+                            "{} (subnet {}/{} on {})",
+                            s.ip(),
+                            s.network(),
+                            s.prefix(),
+                            interface.name
+                        ),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn render_source_selection(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Interface");
+            let selected_label = self
+                .interfaces
+                .iter()
+                .find(|s| s.name == self.source_interface)
+                .map(|s| s.name.clone())
+                .unwrap_or("Default route".to_owned());
+            egui::ComboBox::from_id_source("project_settings_source_interface")
+                .selected_text(selected_label)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.source_interface, "".to_owned(), "Default route");
+                    for interface in self.interfaces.iter() {
+                        ui.selectable_value(
+                            &mut self.source_interface,
+                            interface.name.clone(),
+                            format!(
+                                "{} ({})",
+                                interface.name,
+                                interface
+                                    .ips
+                                    .iter()
+                                    .map(|s| s.to_string())
+                                    .collect::<Vec<String>>()
+                                    .join(", ")
+                            ),
+                        );
+                    }
+                });
+            if ui.button("⟳").on_hover_text("Refresh interfaces").clicked() {
+                self.interfaces = get_source_interfaces();
+            }
+        });
+        if !self
+            .interfaces
+            .iter()
+            .any(|s| s.name == self.source_interface)
+        {
+            self.source_interface = "".to_owned();
+        }
+
+        let addresses = self.source_addresses();
+        if !addresses.iter().any(|s| s.0 == self.source_address) {
+            self.source_address = "".to_owned();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Address");
+            let selected_label = addresses
+                .iter()
+                .find(|s| s.0 == self.source_address)
+                .map(|s| s.1.clone())
+                .unwrap_or("Automatic".to_owned());
+            egui::ComboBox::from_id_source("project_settings_source_address")
+                .selected_text(selected_label)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.source_address, "".to_owned(), "Automatic");
+                    for (address, label) in addresses.iter() {
+                        ui.selectable_value(&mut self.source_address, address.clone(), label);
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Automatic => the interface's address matching the target's IP version.");
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.project_settings_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("Project settings")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .fixed_size(Vec2::new(275.0, 250.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.project_settings_window_state;
+
+                ui.vertical_centered(|ui| {
+                    ui.strong("DNS");
+                    ui.label("DNS servers (one per line, ip or ip:port)")
+                        .on_hover_text("Leave empty to use the DNS servers configured in your OS.");
+                    ui.text_edit_multiline(&mut window_state.dns_servers);
+                    for err in window_state.dns_servers_validation_err.iter() {
+                        render_validation_err(
+                            ui,
+                            true,
+                            &format!("{} is not valid DNS server.", err),
+                        );
+                    }
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("DNS timeout (ms)");
+                        render_numeric_textbox(ui, &mut window_state.dns_timeout_ms);
+                    });
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.checkbox(&mut window_state.dns_force_tcp, "Always query over TCP")
+                        .on_hover_text(
+                            "Otherwise UDP is used and TCP only when the answer gets truncated.",
+                        );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+                    ui.strong("Scope");
+                    ui.label("Never probe (IPs, ranges or CIDRs, one per line)")
+                        .on_hover_text(
+                            "Applies to every ping sweep and port scan in this project.",
+                        );
+                    ui.text_edit_multiline(&mut window_state.exclusions);
+                    for err in window_state.exclusions_validation_err.iter() {
+                        render_validation_err(
+                            ui,
+                            true,
+                            &format!("{} is not valid IP, range or CIDR.", err),
+                        );
+                    }
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Confirm scans above (targets)");
+                        render_numeric_textbox(
+                            ui,
+                            &mut window_state.max_targets_without_confirmation,
+                        );
+                    });
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+                    ui.strong("Source");
+                    ui.label("Pings, TCP connects and raw probes go out of");
+                    window_state.render_source_selection(ui);
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+                    ui.strong("Proxy");
+                    ui.label("TCP port scans and banner grabs go through")
+                        .on_hover_text("Ping sweeps, passive and IPv6 neighbor discovery need raw sockets, so they get disabled while a proxy is set.");
+                    window_state.render_proxy_settings(ui);
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+                    ui.strong("MAC vendors");
+                    ui.label(format!("{} known vendor prefixes (OUI)", get_oui_table_len()))
+                        .on_hover_text("Shared by all projects. A loaded table is applied right away and kept for the next start.");
+                    ui.horizontal(|ui| {
+                        ui.label("File");
+                        ui.text_edit_singleline(&mut window_state.oui_table_path)
+                            .on_hover_text("IEEE oui.txt / oui.csv, wireshark's manuf or nmap-mac-prefixes");
+                        if ui.button("Load").clicked() {
+                            let result = load_oui_table_from_file(Path::new(window_state.oui_table_path.trim()));
+                            // Failed load keeps the old table, nothing to relabel
+                            if result.is_ok() {
+                                NetworkTopology::refresh_node_labels(
+                                    &mut app_context.app_state.network_topology.graph,
+                                );
+                            }
+                            window_state.oui_table_result = Some(
+                                result
+                                    .map(|s| format!("Loaded {} vendor prefixes.", s))
+                                    .map_err(|e| format!("Unable to load the OUI table => {}", e)),
+                            );
+                        }
+                    });
+                    match &window_state.oui_table_result {
+                        Some(Ok(s)) => {
+                            ui.label(s);
+                        }
+                        Some(Err(e)) => render_validation_err(ui, true, e),
+                        None => {}
+                    }
+
+                    ui.add_space(ACTION_SPACER);
+                    if ui.button("Save").clicked() {
+                        window_state.dns_servers_validation_err = vec![];
+                        let mut dns_servers: Vec<SocketAddr> = vec![];
+                        for line in window_state
+                            .dns_servers
+                            .lines()
+                            .map(|s| s.trim())
+                            .filter(|s| !s.is_empty())
+                        {
+                            match parse_dns_server(line) {
+                                Some(s) => dns_servers.push(s),
+                                None => window_state
+                                    .dns_servers_validation_err
+                                    .push(line.to_owned()),
+                            }
+                        }
+
+                        window_state.exclusions_validation_err = vec![];
+                        let exclusions = TargetExclusions::parse(&window_state.exclusions)
+                            .unwrap_or_else(|invalid_entries| {
+                                window_state.exclusions_validation_err = invalid_entries;
+                                TargetExclusions::default()
+                            });
+
+                        let proxy = window_state.read_proxy();
+                        window_state.proxy_address_validation_err = proxy.is_err();
+
+                        if window_state.dns_servers_validation_err.is_empty()
+                            && window_state.exclusions_validation_err.is_empty()
+                            && !window_state.proxy_address_validation_err
+                        {
+                            let project_settings = &mut app_context.app_state.project_settings;
+                            project_settings.dns_servers = dns_servers;
+                            project_settings.dns_timeout_ms = window_state
+                                .dns_timeout_ms
+                                .parse()
+                                .unwrap_or(DEFAULT_DNS_TIMEOUT_MS);
+                            project_settings.dns_force_tcp = window_state.dns_force_tcp;
+                            project_settings.exclusions = exclusions;
+                            project_settings.max_targets_without_confirmation = window_state
+                                .max_targets_without_confirmation
+                                .parse()
+                                .unwrap_or(DEFAULT_MAX_TARGETS_WITHOUT_CONFIRMATION);
+                            project_settings.source = SourceBinding {
+                                interface_name: (!window_state.source_interface.is_empty())
+                                    .then(|| window_state.source_interface.clone()),
+                                address: IpAddr::from_str(&window_state.source_address).ok(),
+                                interface_ips: None,
+                            };
+                            project_settings.proxy = proxy.unwrap_or_default();
+                            window_state.open = false;
+                        }
+                    }
+                });
+            });
+
+        app_context.ui_state.project_settings_window_state.open &= should_show_window;
+    }
+}
@@ -0,0 +1,112 @@
+use eframe::{
+    egui::{self, ScrollArea, TextStyle},
+    epaint::Vec2,
+};
+
+use crate::utils::{
+    bytes::{bytes_to_string, BytesEncoding},
+    constants::{DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS, LINE_ENDING},
+};
+
+pub struct RawDataViewerSection {
+    pub title: String,
+    pub data: Vec<u8>,
+}
+
+pub struct RawDataViewerWindowState {
+    open: bool,
+    title: String,
+    sections: Vec<RawDataViewerSection>,
+    encoding: BytesEncoding,
+}
+impl Default for RawDataViewerWindowState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            title: "".to_owned(),
+            sections: vec![],
+            encoding: BytesEncoding::Utf8,
+        }
+    }
+}
+impl RawDataViewerWindowState {
+    pub fn show(&mut self, title: String, sections: Vec<RawDataViewerSection>) {
+        self.open = true;
+        self.title = title;
+        self.sections = sections;
+    }
+
+    fn stringify_section(&self, section: &RawDataViewerSection) -> String {
+        format!(
+            "{} ({} bytes){}{}",
+            section.title,
+            section.data.len(),
+            LINE_ENDING,
+            bytes_to_string(&section.data, self.encoding)
+        )
+    }
+
+    fn stringify_all_sections(&self) -> String {
+        self.sections
+            .iter()
+            .map(|s| self.stringify_section(s))
+            .collect::<Vec<String>>()
+            .join(&format!("{}{}", LINE_ENDING, LINE_ENDING))
+    }
+}
+
+impl RawDataViewerWindowState {
+    pub fn render(egui_context: &egui::Context, viewer_state: &mut RawDataViewerWindowState) {
+        let mut should_show_window_internal = viewer_state.open;
+        if !should_show_window_internal {
+            return;
+        }
+
+        egui::Window::new(format!("Raw data - {}", viewer_state.title))
+            .collapsible(true)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .default_size(Vec2::new(600.0, 400.0))
+            .resizable(true)
+            .open(&mut should_show_window_internal)
+            .show(egui_context, |ui| {
+                ui.horizontal(|ui| {
+                    for encoding in [
+                        BytesEncoding::Utf8,
+                        BytesEncoding::Latin1,
+                        BytesEncoding::HexDump,
+                    ] {
+                        ui.selectable_value(&mut viewer_state.encoding, encoding, encoding.label());
+                    }
+                    ui.separator();
+                    if ui.button("Copy to clipboard").clicked() {
+                        let text_to_copy = viewer_state.stringify_all_sections();
+                        ui.output_mut(|s| s.copied_text = text_to_copy);
+                    }
+                });
+                ui.separator();
+
+                ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
+                    for section in viewer_state.sections.iter() {
+                        ui.horizontal(|ui| {
+                            ui.strong(format!("{} ({} bytes)", section.title, section.data.len()));
+                            if ui.small_button("copy").clicked() {
+                                let text_to_copy =
+                                    bytes_to_string(&section.data, viewer_state.encoding);
+                                ui.output_mut(|s| s.copied_text = text_to_copy);
+                            }
+                        });
+                        ui.add(
+                            egui::TextEdit::multiline(
+                                &mut bytes_to_string(&section.data, viewer_state.encoding).as_str(),
+                            )
+                            .font(TextStyle::Monospace)
+                            .desired_width(f32::INFINITY),
+                        );
+                        ui.add_space(DEFAULT_SPACER);
+                    }
+                });
+            });
+
+        viewer_state.open &= should_show_window_internal;
+    }
+}
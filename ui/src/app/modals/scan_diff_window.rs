@@ -0,0 +1,138 @@
+use eframe::{egui, epaint::Vec2};
+
+use crate::{
+    app::workspace_models::WorkspaceContext,
+    utils::{
+        constants::{DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        snapshots::{diff_scan_snapshots, ScanDiffEntry, ScanSnapshot, ScanSnapshots},
+    },
+};
+
+#[derive(Default)]
+pub struct ScanDiffWindowState {
+    pub open: bool,
+
+    older_id: Option<usize>,
+    newer_id: Option<usize>,
+}
+
+impl ScanDiffWindowState {
+    // Starts off comparing the two latest scans
+    pub fn show(&mut self, snapshots: &ScanSnapshots) {
+        self.open = true;
+        let mut latest = snapshots.snapshots.iter().rev().map(|s| s.id);
+        self.newer_id = latest.next();
+        self.older_id = latest.next();
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.scan_diff_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("Scan history")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .default_size(Vec2::new(500.0, 400.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.scan_diff_window_state;
+                let mut snapshots = app_context.app_state.scan_snapshots.lock().unwrap();
+
+                ui.label("Every finished scan is kept, pick two of them to see what changed in between.");
+                ui.add_space(DEFAULT_SPACER);
+                render_snapshot_picker(ui, "From", &snapshots.snapshots, &mut window_state.older_id);
+                render_snapshot_picker(ui, "To", &snapshots.snapshots, &mut window_state.newer_id);
+                ui.horizontal(|ui| {
+                    if ui.button("Swap").clicked() {
+                        std::mem::swap(&mut window_state.older_id, &mut window_state.newer_id);
+                    }
+                    if !snapshots.snapshots.is_empty() && ui.button("Clear history").clicked() {
+                        snapshots.snapshots.clear();
+                        window_state.older_id = None;
+                        window_state.newer_id = None;
+                    }
+                });
+
+                ui.add_space(DEFAULT_SPACER);
+                ui.separator();
+                let find_snapshot = |id: Option<usize>| snapshots.snapshots.iter().find(|s| Some(s.id) == id);
+                match (find_snapshot(window_state.older_id), find_snapshot(window_state.newer_id)) {
+                    (Some(older), Some(newer)) => {
+                        if older.scanned_targets != newer.scanned_targets {
+                            ui.weak("Scans covered different targets, only hosts both covered are compared.");
+                        }
+                        if older.scanned_ports != newer.scanned_ports {
+                            ui.weak("Scans probed different ports, only the ones both probed are compared.");
+                        }
+                        render_diff(ui, &diff_scan_snapshots(older, newer));
+                    }
+                    _ if snapshots.snapshots.len() < 2 => {
+                        ui.label("At least two finished scans are needed.");
+                    }
+                    _ => {
+                        ui.label("Pick both scans to compare.");
+                    }
+                }
+            });
+
+        app_context.ui_state.scan_diff_window_state.open &= should_show_window;
+    }
+}
+
+fn render_snapshot_picker(
+    ui: &mut egui::Ui,
+    label: &str,
+    snapshots: &[ScanSnapshot],
+    selected_id: &mut Option<usize>,
+) {
+    let selected_text = snapshots
+        .iter()
+        .find(|s| Some(s.id) == *selected_id)
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.label(label);
+        egui::ComboBox::from_id_source(format!("scan_diff_{}", label))
+            .width(400.0)
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for snapshot in snapshots.iter().rev() {
+                    ui.selectable_value(
+                        selected_id,
+                        Some(snapshot.id),
+                        format!("{} ({} hosts)", snapshot, snapshot.hosts.len()),
+                    );
+                }
+            });
+    });
+}
+
+fn render_diff(ui: &mut egui::Ui, entries: &[ScanDiffEntry]) {
+    ui.strong(format!("Changes ({})", entries.len()));
+    if entries.is_empty() {
+        ui.label("Nothing changed.");
+        return;
+    }
+
+    egui::ScrollArea::vertical()
+        .id_source("scan_diff_entries")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            for entry in entries {
+                let response = ui.colored_label(get_entry_color(entry), entry.to_string());
+                if let ScanDiffEntry::BannerChanged(_, _, old, new) = entry {
+                    response.on_hover_text(format!("Before:\n{}\n\nAfter:\n{}", old, new));
+                }
+            }
+        });
+}
+
+fn get_entry_color(entry: &ScanDiffEntry) -> egui::Color32 {
+    match entry {
+        ScanDiffEntry::HostAdded(..) | ScanDiffEntry::PortOpened(..) => egui::Color32::LIGHT_GREEN,
+        ScanDiffEntry::HostRemoved(..) | ScanDiffEntry::PortClosed(..) => egui::Color32::LIGHT_RED,
+        _ => egui::Color32::LIGHT_YELLOW,
+    }
+}
@@ -1,55 +1,101 @@
-use eframe::{egui, epaint::Vec2};
+use eframe::{
+    egui,
+    epaint::{Color32, Vec2},
+};
 use ipnet::IpNet;
-use local_ip_address::local_ip;
 use std::{
-    net::{IpAddr, Ipv6Addr},
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    net::IpAddr,
     str::FromStr,
     sync::Arc,
 };
 
 use crate::{
-    app::workspace_models::WorkspaceContext,
+    app::workspace_models::{ProjectSettings, WorkspaceContext},
     utils::{
         constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS, TRASH_ICON},
         general::{render_numeric_textbox, render_validation_err},
-        ip::ping_ip_list,
+        ip::{ping_target_expressions, PingIpListConfig},
+        routes::get_local_subnets,
+        targets::{
+            parse_target_expressions, parse_target_file, TargetExclusions, TargetExpressions,
+            TargetList, TargetRange,
+        },
     },
 };
 
+// Anything bigger (a /64 especially) should go through IPv6 neighbor discovery instead
+const MAX_ENUMERATED_IPV6_ADDRESSES: u128 = 1 << 16;
+
 pub struct ScanIpRangeWindowState {
     pub open: bool,
     pub input_type: IpInputType,
 
+    pub target_list: String, // mixed IPs, ranges, CIDRs and hostnames
+    pub target_list_validation_err: Vec<String>,
+
     pub range_ip_from: String,
     pub range_ip_from_validation_err: bool,
     pub range_ip_to: String,
     pub range_ip_to_validation_err: bool,
     pub cidr_notation: String,
     pub cidr_notation_validation_err: bool,
+    pub ipv6_range_too_large_err: bool,
 
     pub manual_ips: Vec<String>,
     pub manual_ips_validation_err: Vec<String>,
 
+    pub import_path: String, // text / CSV file with targets
+    pub import_result: Option<String>,
+    pub import_errs: Vec<String>,
+
+    pub exclusions: String, // one IP, range or CIDR per line
+    pub exclusions_validation_err: Vec<String>,
+
     pub settings_ping_timeout_ms: String,
     pub settings_ping_checkup_ms: String,
     pub settings_exlude_localhost: bool,
     pub settings_remove_connectivity_status_when_unreachable: bool,
     pub settings_reset_connectivity_status: bool,
+    pub settings_resolve_hostnames: bool,
+
+    pub large_scan_confirmation: Option<u128>, // Some => waiting for the user to confirm scanning this many targets
+
+    targets_count: Option<u128>, // None => some input is not valid
+    targets_count_inputs_hash: Option<u64>, // recounted only when the inputs change
+    local_subnets: Vec<(String, IpNet)>, // (interface name, subnet), refreshed whenever the window opens
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum IpInputType {
+    TargetList,
     Range,
     CIDRNotation,
     Manual,
 }
 
+// Which inputs are not valid
+#[derive(Default)]
+struct TargetInputErrors {
+    target_list: Vec<String>,
+    range_ip_from: bool,
+    range_ip_to: bool,
+    cidr_notation: bool,
+    manual_ips: Vec<String>,
+    ipv6_range_too_large: bool,
+}
+
 impl Default for ScanIpRangeWindowState {
     fn default() -> Self {
         Self {
             open: false,
 
-            input_type: IpInputType::Range,
+            input_type: IpInputType::TargetList,
+            target_list: "# IPs, ranges (10.0.0.5-40), CIDRs or hostnames\n192.168.0.0/24"
+                .to_owned(),
+            target_list_validation_err: vec![],
+
             range_ip_from: "192.168.0.0".to_owned(),
             range_ip_from_validation_err: false,
             range_ip_to: "192.168.0.255".to_owned(),
@@ -57,20 +103,209 @@ impl Default for ScanIpRangeWindowState {
 
             cidr_notation: "192.168.0.0/24".to_owned(),
             cidr_notation_validation_err: false,
+            ipv6_range_too_large_err: false,
 
             manual_ips: vec!["192.168.0.1".to_owned()],
             manual_ips_validation_err: vec![],
 
+            import_path: "".to_owned(),
+            import_result: None,
+            import_errs: vec![],
+
+            exclusions: "".to_owned(),
+            exclusions_validation_err: vec![],
+
             settings_ping_timeout_ms: "500".to_owned(),
             settings_ping_checkup_ms: "10".to_owned(),
             settings_exlude_localhost: true,
             settings_remove_connectivity_status_when_unreachable: true,
             settings_reset_connectivity_status: false,
+            settings_resolve_hostnames: true,
+
+            large_scan_confirmation: None,
+
+            targets_count: None,
+            targets_count_inputs_hash: None,
+            local_subnets: vec![],
         }
     }
 }
 
 impl ScanIpRangeWindowState {
+    pub fn show(&mut self) {
+        self.open = true;
+        self.local_subnets = get_local_subnets();
+    }
+
+    pub fn show_with_subnet(&mut self, subnet: IpNet) {
+        self.show();
+        self.input_type = IpInputType::CIDRNotation;
+        self.cidr_notation = subnet.to_string();
+        self.cidr_notation_validation_err = false;
+    }
+
+    // Doesn't touch the validation errors, so it can run for the target count
+    fn read_targets(&self) -> Result<TargetExpressions, TargetInputErrors> {
+        let mut errors = TargetInputErrors::default();
+        let mut hostnames = vec![];
+        let entries = match self.input_type {
+            IpInputType::TargetList => match parse_target_expressions(&self.target_list) {
+                Ok(s) => {
+                    hostnames = s.hostnames;
+                    s.targets.entries
+                }
+                Err(e) => {
+                    errors.target_list = e.iter().map(|s| s.to_string()).collect();
+                    vec![]
+                }
+            },
+            IpInputType::Range => {
+                let from_ip = IpAddr::from_str(self.range_ip_from.trim());
+                let to_ip = IpAddr::from_str(self.range_ip_to.trim());
+                errors.range_ip_from = from_ip.is_err();
+                errors.range_ip_to = to_ip.is_err();
+                match (from_ip, to_ip) {
+                    (Ok(from_ip), Ok(to_ip)) => {
+                        match TargetRange::parse(&format!("{}-{}", from_ip, to_ip)) {
+                            Some(s) => vec![s],
+                            // Different families or "to" being lower than "from"
+                            None => {
+                                errors.range_ip_to = true;
+                                vec![]
+                            }
+                        }
+                    }
+                    _ => vec![],
+                }
+            }
+            IpInputType::CIDRNotation => match IpNet::from_str(self.cidr_notation.trim()) {
+                Ok(s) => vec![TargetRange::Network(s)],
+                Err(_) => {
+                    errors.cidr_notation = true;
+                    vec![]
+                }
+            },
+            IpInputType::Manual => {
+                let mut entries = vec![];
+                for ip in self.manual_ips.iter() {
+                    match parse_target_expressions(ip) {
+                        Ok(s) if s.targets.entries.len() + s.hostnames.len() == 1 => {
+                            entries.extend(s.targets.entries);
+                            hostnames.extend(s.hostnames);
+                        }
+                        _ => errors.manual_ips.push(ip.to_string()),
+                    }
+                }
+                entries
+            }
+        };
+
+        let targets = TargetList { entries };
+        errors.ipv6_range_too_large = targets.count() > MAX_ENUMERATED_IPV6_ADDRESSES
+            && targets.entries.iter().any(|s| {
+                matches!(
+                    s,
+                    TargetRange::Range(IpAddr::V6(_), _) | TargetRange::Network(IpNet::V6(_))
+                )
+            });
+
+        let is_valid = errors.target_list.is_empty()
+            && !errors.range_ip_from
+            && !errors.range_ip_to
+            && !errors.cidr_notation
+            && errors.manual_ips.is_empty()
+            && !errors.ipv6_range_too_large;
+        if is_valid {
+            Ok(TargetExpressions { targets, hostnames })
+        } else {
+            Err(errors)
+        }
+    }
+
+    // The window's exclusions, the project's and this pc's own addresses (when excluding localhost)
+    fn read_exclusions(
+        &self,
+        project_settings: &ProjectSettings,
+    ) -> Result<TargetExclusions, Vec<String>> {
+        let mut exclusions = TargetExclusions::parse(&self.exclusions)?;
+        if self.settings_exlude_localhost {
+            exclusions.entries.extend(
+                project_settings
+                    .source
+                    .own_addresses()
+                    .into_iter()
+                    .map(TargetRange::Single),
+            );
+        }
+        Ok(exclusions.merged_with(&project_settings.exclusions))
+    }
+
+    fn refresh_targets_count(&mut self, project_settings: &ProjectSettings) {
+        let mut hasher = DefaultHasher::new();
+        self.input_type.hash(&mut hasher);
+        self.target_list.hash(&mut hasher);
+        self.range_ip_from.hash(&mut hasher);
+        self.range_ip_to.hash(&mut hasher);
+        self.cidr_notation.hash(&mut hasher);
+        self.manual_ips.hash(&mut hasher);
+        self.exclusions.hash(&mut hasher);
+        self.settings_exlude_localhost.hash(&mut hasher);
+        project_settings.exclusions.hash(&mut hasher);
+        project_settings.source.interface_name.hash(&mut hasher);
+        project_settings.source.address.hash(&mut hasher);
+        let inputs_hash = hasher.finish();
+        if self.targets_count_inputs_hash == Some(inputs_hash) {
+            return;
+        }
+
+        self.targets_count_inputs_hash = Some(inputs_hash);
+        self.targets_count = match (self.read_targets(), self.read_exclusions(project_settings)) {
+            (Ok(targets), Ok(exclusions)) => Some(targets.count_without(&exclusions)),
+            _ => None,
+        };
+    }
+
+    // Appends to the manual list, so a scope can be put together from several files
+    fn import_targets(&mut self) {
+        self.import_result = None;
+        self.import_errs = vec![];
+
+        let content = match std::fs::read_to_string(self.import_path.trim()) {
+            Ok(s) => s,
+            Err(e) => {
+                self.import_errs = vec![format!("Unable to read file => {}", e)];
+                return;
+            }
+        };
+        let (entries, errors) = parse_target_file(&content);
+        self.import_errs = errors.iter().map(|s| s.to_string()).collect();
+
+        self.manual_ips.retain(|s| !s.trim().is_empty());
+        let mut imported_count = 0;
+        for entry in entries {
+            if !self.manual_ips.contains(&entry) {
+                self.manual_ips.push(entry);
+                imported_count += 1;
+            }
+        }
+        self.manual_ips_validation_err = vec![];
+        self.input_type = IpInputType::Manual;
+        self.import_result = Some(format!(
+            "Imported {} targets, skipped {} lines.",
+            imported_count,
+            errors.len()
+        ));
+    }
+
+    fn set_validation_errs(&mut self, errors: TargetInputErrors) {
+        self.target_list_validation_err = errors.target_list;
+        self.range_ip_from_validation_err = errors.range_ip_from;
+        self.range_ip_to_validation_err = errors.range_ip_to;
+        self.cidr_notation_validation_err = errors.cidr_notation;
+        self.manual_ips_validation_err = errors.manual_ips;
+        self.ipv6_range_too_large_err = errors.ipv6_range_too_large;
+    }
+
     pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
         let mut should_show_window = app_context.ui_state.scan_ip_range_window_state.open;
         if !should_show_window {
@@ -83,166 +318,194 @@ impl ScanIpRangeWindowState {
             .fixed_size(Vec2::new(275.0, 250.0))
             .open(&mut should_show_window)
             .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.scan_ip_range_window_state;
+
                 ui.horizontal(|ui| {
                     ui.selectable_value(
-                        &mut app_context.ui_state.scan_ip_range_window_state.input_type,
+                        &mut window_state.input_type,
+                        IpInputType::TargetList,
+                        "Target list",
+                    );
+                    ui.selectable_value(
+                        &mut window_state.input_type,
                         IpInputType::Range,
                         "Range (inclusive)",
                     );
                     ui.selectable_value(
-                        &mut app_context.ui_state.scan_ip_range_window_state.input_type,
+                        &mut window_state.input_type,
                         IpInputType::CIDRNotation,
                         "CIDR Notation",
                     );
                     ui.selectable_value(
-                        &mut app_context.ui_state.scan_ip_range_window_state.input_type,
+                        &mut window_state.input_type,
                         IpInputType::Manual,
                         "Manually input IPs",
                     );
                 });
+                if !window_state.local_subnets.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("Scan my subnet:");
+                        for (interface_name, subnet) in window_state.local_subnets.iter() {
+                            if ui
+                                .button(subnet.to_string())
+                                .on_hover_text(interface_name)
+                                .clicked()
+                            {
+                                window_state.input_type = IpInputType::CIDRNotation;
+                                window_state.cidr_notation = subnet.to_string();
+                                window_state.cidr_notation_validation_err = false;
+                            }
+                        }
+                        if window_state.local_subnets.len() > 1 && ui.button("All").clicked() {
+                            window_state.input_type = IpInputType::TargetList;
+                            window_state.target_list = window_state
+                                .local_subnets
+                                .iter()
+                                .map(|s| format!("{} # {}", s.1, s.0))
+                                .collect::<Vec<String>>()
+                                .join("\n");
+                            window_state.target_list_validation_err = vec![];
+                        }
+                    });
+                }
                 ui.separator();
 
                 ui.vertical_centered(|ui| {
-                    match app_context.ui_state.scan_ip_range_window_state.input_type {
+                    match window_state.input_type {
+                        IpInputType::TargetList => {
+                            ui.text_edit_multiline(&mut window_state.target_list)
+                                .on_hover_text("Separated by new lines, spaces or commas. Everything after \"#\" is a comment. Hostnames get resolved right before scanning.");
+                            for err in window_state.target_list_validation_err.iter() {
+                                render_validation_err(ui, true, err);
+                            }
+                        }
                         IpInputType::Range => {
                             ui.horizontal(|ui| {
                                 ui.label("From IP Address");
-                                ui.text_edit_singleline(
-                                    &mut app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .range_ip_from,
-                                );
+                                ui.text_edit_singleline(&mut window_state.range_ip_from);
                             });
                             render_validation_err(
                                 ui,
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .range_ip_from_validation_err,
+                                window_state.range_ip_from_validation_err,
                                 "IP is not valid.",
                             );
 
                             ui.add_space(DEFAULT_SPACER);
                             ui.horizontal(|ui| {
                                 ui.label("To IP Address     ");
-                                ui.text_edit_singleline(
-                                    &mut app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .range_ip_to,
-                                );
+                                ui.text_edit_singleline(&mut window_state.range_ip_to);
                             });
                             render_validation_err(
                                 ui,
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .range_ip_to_validation_err,
+                                window_state.range_ip_to_validation_err,
                                 "IP is not valid.",
                             );
                         }
                         IpInputType::CIDRNotation => {
                             ui.horizontal(|ui| {
                                 ui.label("CIDR Notation");
-                                ui.text_edit_singleline(
-                                    &mut app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .cidr_notation,
-                                );
+                                ui.text_edit_singleline(&mut window_state.cidr_notation);
                             });
                             render_validation_err(
                                 ui,
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .cidr_notation_validation_err,
+                                window_state.cidr_notation_validation_err,
                                 "CIDR notation is not valid.",
                             );
                         }
                         IpInputType::Manual => {
                             let mut index_to_delete = None;
-                            for (i, ip) in app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .manual_ips
-                                .iter_mut()
-                                .enumerate()
-                            {
-                                ui.horizontal(|ui| {
-                                    ui.set_width(ui.available_width() * 0.85);
-                                    ui.text_edit_singleline(ip);
-                                    if ui.button(TRASH_ICON).clicked() {
-                                        index_to_delete = Some(i);
-                                    }
-                                });
-                            }
+                            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                for (i, ip) in window_state.manual_ips.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.set_width(ui.available_width() * 0.85);
+                                        ui.text_edit_singleline(ip);
+                                        if ui.button(TRASH_ICON).clicked() {
+                                            index_to_delete = Some(i);
+                                        }
+                                    });
+                                }
+                            });
                             if let Some(index_to_delete) = index_to_delete {
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .manual_ips
-                                    .remove(index_to_delete);
+                                window_state.manual_ips.remove(index_to_delete);
                             }
 
-                            for err in app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .manual_ips_validation_err
-                                .iter()
-                            {
+                            for err in window_state.manual_ips_validation_err.iter() {
                                 render_validation_err(
                                     ui,
                                     true,
-                                    &format!("{} is not valid IP.", err),
+                                    &format!("{} is not valid IP, range, CIDR or hostname.", err),
                                 );
                             }
 
                             ui.add_space(DEFAULT_SPACER);
                             if ui.button("+").clicked() {
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .manual_ips
-                                    .push("".to_owned());
+                                window_state.manual_ips.push("".to_owned());
                             }
                         }
                     }
+                    render_validation_err(
+                        ui,
+                        window_state.ipv6_range_too_large_err,
+                        "IPv6 range is too large to scan address by address, use IPv6 neighbor discovery instead.",
+                    );
                     ui.add_space(DEFAULT_SPACER);
                     ui.separator();
 
                     ui.add_space(DEFAULT_SPACER);
                     ui.horizontal(|ui| {
-                        ui.label("Ping timeout (ms)");
-                        render_numeric_textbox(
+                        ui.label("File");
+                        ui.text_edit_singleline(&mut window_state.import_path)
+                            .on_hover_text("Text or CSV file with IPs, ranges, CIDRs or hostnames. Imported targets get added to the manual list.");
+                        if ui.button("Import targets…").clicked() {
+                            window_state.import_targets();
+                        }
+                    });
+                    if let Some(import_result) = window_state.import_result.as_ref() {
+                        ui.label(import_result);
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_source("scan_ip_range_import_errs")
+                        .max_height(100.0)
+                        .show(ui, |ui| {
+                            for err in window_state.import_errs.iter() {
+                                render_validation_err(ui, true, err);
+                            }
+                        });
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.label("Exclusions (IPs, ranges or CIDRs, one per line)")
+                        .on_hover_text(
+                            "Exclusions from the project settings are always applied as well.",
+                        );
+                    ui.text_edit_multiline(&mut window_state.exclusions);
+                    for err in window_state.exclusions_validation_err.iter() {
+                        render_validation_err(
                             ui,
-                            &mut app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_ping_timeout_ms,
+                            true,
+                            &format!("{} is not valid IP, range or CIDR.", err),
                         );
+                    }
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.separator();
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Ping timeout (ms)");
+                        render_numeric_textbox(ui, &mut window_state.settings_ping_timeout_ms);
                     });
 
                     ui.add_space(DEFAULT_SPACER);
                     ui.horizontal(|ui| {
                         ui.label("Ping checkup (ms)");
-                        render_numeric_textbox(
-                            ui,
-                            &mut app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_ping_checkup_ms,
-                        );
+                        render_numeric_textbox(ui, &mut window_state.settings_ping_checkup_ms);
                     });
 
                     ui.add_space(DEFAULT_SPACER);
                     ui.horizontal(|ui| {
                         ui.checkbox(
-                            &mut app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_exlude_localhost,
+                            &mut window_state.settings_exlude_localhost,
                             "Exclude localhost (my ip)",
                         );
                     });
@@ -250,10 +513,7 @@ impl ScanIpRangeWindowState {
                     ui.add_space(DEFAULT_SPACER);
                     ui.horizontal(|ui| {
                         ui.checkbox(
-                            &mut app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_remove_connectivity_status_when_unreachable,
+                            &mut window_state.settings_remove_connectivity_status_when_unreachable,
                             "Remove connectivity status when unreachable",
                         );
                     });
@@ -261,201 +521,117 @@ impl ScanIpRangeWindowState {
                     ui.add_space(DEFAULT_SPACER);
                     ui.horizontal(|ui| {
                         ui.checkbox(
-                            &mut app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_reset_connectivity_status,
+                            &mut window_state.settings_reset_connectivity_status,
                             "Reset connectivity status (for each node)",
                         );
                     });
 
-                    ui.add_space(ACTION_SPACER);
-                    if ui.button("Start scan").clicked() {
-                        let ips_to_ping: Option<Vec<IpAddr>> = match app_context
-                            .ui_state
-                            .scan_ip_range_window_state
-                            .input_type
-                        {
-                            IpInputType::Range => {
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .range_ip_from_validation_err = false;
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .range_ip_to_validation_err = false;
-
-                                let from_ip_res = IpAddr::from_str(
-                                    &app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .range_ip_from,
-                                );
-                                let to_ip_res = IpAddr::from_str(
-                                    &app_context.ui_state.scan_ip_range_window_state.range_ip_to,
-                                );
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.checkbox(
+                            &mut window_state.settings_resolve_hostnames,
+                            "Resolve hostnames of reachable hosts",
+                        );
+                    });
 
-                                match (from_ip_res, to_ip_res) {
-                                    (Ok(from_ip), Ok(to_ip)) => {
-                                        let mut ips_to_ping = vec![];
-                                        let mut from = u128::from_be_bytes(
-                                            match from_ip {
-                                                IpAddr::V4(s) => s.to_ipv6_mapped(),
-                                                IpAddr::V6(s) => s,
-                                            }
-                                            .octets(),
-                                        );
-                                        let to = u128::from_be_bytes(
-                                            match to_ip {
-                                                IpAddr::V4(s) => s.to_ipv6_mapped(),
-                                                IpAddr::V6(s) => s,
-                                            }
-                                            .octets(),
-                                        );
-
-                                        while from <= to {
-                                            ips_to_ping.push(Ipv6Addr::from(from));
-                                            from += 1;
-                                        }
-                                        Some(
-                                            ips_to_ping
-                                                .iter()
-                                                .map(|s| {
-                                                    if let Some(ipv4) = s.to_ipv4() {
-                                                        IpAddr::V4(ipv4)
-                                                    } else {
-                                                        IpAddr::V6(*s)
-                                                    }
-                                                })
-                                                .collect(),
-                                        )
-                                    }
-                                    (Err(_), Ok(_)) => {
-                                        app_context
-                                            .ui_state
-                                            .scan_ip_range_window_state
-                                            .range_ip_from_validation_err = true;
-                                        None
-                                    }
-                                    (Ok(_), Err(_)) => {
-                                        app_context
-                                            .ui_state
-                                            .scan_ip_range_window_state
-                                            .range_ip_to_validation_err = true;
-                                        None
-                                    }
-                                    (Err(_), Err(_)) => {
-                                        app_context
-                                            .ui_state
-                                            .scan_ip_range_window_state
-                                            .range_ip_from_validation_err = true;
-                                        app_context
-                                            .ui_state
-                                            .scan_ip_range_window_state
-                                            .range_ip_to_validation_err = true;
-                                        None
-                                    }
-                                }
-                            }
-                            IpInputType::CIDRNotation => {
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .cidr_notation_validation_err = false;
-                                if let Ok(ips_to_ping) = IpNet::from_str(
-                                    &app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .cidr_notation,
-                                ) {
-                                    Some(ips_to_ping.hosts().collect::<Vec<IpAddr>>())
-                                } else {
-                                    app_context
-                                        .ui_state
-                                        .scan_ip_range_window_state
-                                        .cidr_notation_validation_err = true;
-                                    None
-                                }
-                            }
-                            IpInputType::Manual => {
-                                let mut ips_to_ping = vec![];
-                                let mut are_all_valid = true;
-
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .manual_ips_validation_err = vec![];
-                                for ip in app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .manual_ips
-                                    .iter()
-                                {
-                                    if let Ok(ip_to_add) = IpAddr::from_str(ip) {
-                                        ips_to_ping.push(ip_to_add);
-                                    } else {
-                                        are_all_valid = false;
-                                        app_context
-                                            .ui_state
-                                            .scan_ip_range_window_state
-                                            .manual_ips_validation_err
-                                            .push(ip.to_string());
-                                    }
-                                }
+                    ui.add_space(ACTION_SPACER);
+                    let project_settings = &app_context.app_state.project_settings;
+                    window_state.refresh_targets_count(project_settings);
+                    match window_state.targets_count {
+                        Some(s) => ui.label(format!("Targets: {}", s)),
+                        None => ui.label("Targets: -"),
+                    };
 
-                                if are_all_valid {
-                                    Some(ips_to_ping)
-                                } else {
-                                    None
-                                }
+                    let mut should_start = false;
+                    let mut is_confirmed = false;
+                    if let Some(targets_count) = window_state.large_scan_confirmation {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            format!(
+                                "This will scan {} targets. Are you sure?",
+                                targets_count
+                            ),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Start anyway").clicked() {
+                                should_start = true;
+                                is_confirmed = true;
                             }
-                        };
-
-                        if let Some(mut ips_to_ping) = ips_to_ping {
-                            // Exclude localhost. This code is ugly. I hope if let chains get implemented soon.
-                            if app_context
-                                .ui_state
-                                .scan_ip_range_window_state
-                                .settings_exlude_localhost
-                            {
-                                if let Ok(my_ip) = local_ip() {
-                                    if let Some(position_of_my_ip) =
-                                        ips_to_ping.iter().position(|s| *s == my_ip)
-                                    {
-                                        ips_to_ping.remove(position_of_my_ip);
-                                    }
-                                }
+                            if ui.button("Cancel").clicked() {
+                                window_state.large_scan_confirmation = None;
                             }
+                        });
+                    } else {
+                        should_start = ui.button("Start scan").clicked();
+                    }
+                    if !should_start {
+                        return;
+                    }
 
-                            ping_ip_list(
-                                Arc::clone(&app_context.app_state.network_topology.graph),
-                                Arc::clone(&app_context.app_state.status_info),
-                                ips_to_ping,
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .settings_ping_timeout_ms
-                                    .parse()
-                                    .unwrap_or(1),
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .settings_ping_checkup_ms
-                                    .parse()
-                                    .unwrap_or(1),
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .settings_remove_connectivity_status_when_unreachable,
-                                app_context
-                                    .ui_state
-                                    .scan_ip_range_window_state
-                                    .settings_reset_connectivity_status,
-                            );
-                            app_context.ui_state.scan_ip_range_window_state.open = false;
+                    let targets = match window_state.read_targets() {
+                        Ok(s) => {
+                            window_state.set_validation_errs(TargetInputErrors::default());
+                            Some(s)
+                        }
+                        Err(errors) => {
+                            window_state.set_validation_errs(errors);
+                            None
                         }
+                    };
+                    let exclusions = match window_state.read_exclusions(project_settings) {
+                        Ok(s) => {
+                            window_state.exclusions_validation_err = vec![];
+                            Some(s)
+                        }
+                        Err(invalid_entries) => {
+                            window_state.exclusions_validation_err = invalid_entries;
+                            None
+                        }
+                    };
+                    let (Some(targets), Some(exclusions)) = (targets, exclusions) else {
+                        window_state.large_scan_confirmation = None;
+                        return;
+                    };
+
+                    // Inputs might have changed since the confirmation was asked for
+                    let targets_count = targets.count_without(&exclusions);
+                    let is_confirmed =
+                        is_confirmed && window_state.large_scan_confirmation == Some(targets_count);
+                    if targets_count > project_settings.max_targets_without_confirmation as u128
+                        && !is_confirmed
+                    {
+                        window_state.large_scan_confirmation = Some(targets_count);
+                        return;
                     }
+                    window_state.large_scan_confirmation = None;
+
+                    ping_target_expressions(
+                        Arc::clone(&app_context.app_state.network_topology.graph),
+                        Arc::clone(&app_context.app_state.status_info),
+                        Arc::clone(&app_context.app_state.scan_snapshots),
+                        targets,
+                        project_settings.dns_resolver_config(),
+                        PingIpListConfig {
+                            ping_timeout_ms: window_state
+                                .settings_ping_timeout_ms
+                                .parse()
+                                .unwrap_or(1),
+                            ping_checkup_ms: window_state
+                                .settings_ping_checkup_ms
+                                .parse()
+                                .unwrap_or(1),
+                            remove_connectivity_status_when_unreachable: window_state
+                                .settings_remove_connectivity_status_when_unreachable,
+                            reset_connectivity_status: window_state
+                                .settings_reset_connectivity_status,
+                            resolve_hostnames_with: window_state
+                                .settings_resolve_hostnames
+                                .then(|| project_settings.dns_resolver_config()),
+                            exclusions,
+                            source: project_settings.source.clone(),
+                        },
+                    );
+                    window_state.open = false;
                 });
             });
 
@@ -0,0 +1,147 @@
+use eframe::{egui, epaint::Vec2};
+use std::sync::Arc;
+
+use crate::{
+    app::{
+        network_topology::NetworkTopology,
+        workspace_models::{ProjectSettings, WorkspaceContext},
+    },
+    utils::{
+        constants::{ACTION_SPACER, DEFAULT_SPACER, DEFAULT_WINDOW_STARTING_POS},
+        general::{render_numeric_textbox, render_validation_err},
+        snmp::{
+            probe_snmp_list, SnmpProbeConfig, SnmpVersion, DEFAULT_SNMP_COMMUNITIES,
+            DEFAULT_SNMP_TIMEOUT_MS,
+        },
+    },
+};
+
+pub struct SnmpProbeWindowState {
+    pub open: bool,
+
+    pub communities: String, // one per line
+    pub communities_validation_err: bool,
+
+    pub settings_version: SnmpVersion,
+    pub settings_walk_tables: bool,
+    pub settings_timeout_ms: String,
+}
+
+impl Default for SnmpProbeWindowState {
+    fn default() -> Self {
+        Self {
+            open: false,
+
+            communities: DEFAULT_SNMP_COMMUNITIES.to_owned(),
+            communities_validation_err: false,
+
+            settings_version: SnmpVersion::V2c,
+            settings_walk_tables: false,
+            settings_timeout_ms: DEFAULT_SNMP_TIMEOUT_MS.to_string(),
+        }
+    }
+}
+
+impl SnmpProbeWindowState {
+    // None => no community to try
+    pub fn get_probe_config(&self, project_settings: &ProjectSettings) -> Option<SnmpProbeConfig> {
+        let communities = self
+            .communities
+            .lines()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned())
+            .collect::<Vec<String>>();
+        if communities.is_empty() {
+            return None;
+        }
+
+        Some(SnmpProbeConfig {
+            communities,
+            version: self.settings_version,
+            walk_tables: self.settings_walk_tables,
+            timeout_ms: self
+                .settings_timeout_ms
+                .parse()
+                .unwrap_or(DEFAULT_SNMP_TIMEOUT_MS),
+            exclusions: project_settings.exclusions.clone(),
+            source: project_settings.source.clone(),
+        })
+    }
+
+    pub fn render(egui_context: &egui::Context, app_context: &mut WorkspaceContext) {
+        let mut should_show_window = app_context.ui_state.snmp_probe_window_state.open;
+        if !should_show_window {
+            return;
+        }
+
+        egui::Window::new("SNMP probe")
+            .collapsible(false)
+            .default_pos(DEFAULT_WINDOW_STARTING_POS)
+            .fixed_size(Vec2::new(275.0, 250.0))
+            .open(&mut should_show_window)
+            .show(egui_context, |ui| {
+                let window_state = &mut app_context.ui_state.snmp_probe_window_state;
+
+                ui.vertical_centered(|ui| {
+                    ui.label("Community strings (one per line)");
+                    ui.text_edit_multiline(&mut window_state.communities);
+                    render_validation_err(
+                        ui,
+                        window_state.communities_validation_err,
+                        "At least one community is required.",
+                    );
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Version");
+                        ui.selectable_value(
+                            &mut window_state.settings_version,
+                            SnmpVersion::V1,
+                            "v1",
+                        );
+                        ui.selectable_value(
+                            &mut window_state.settings_version,
+                            SnmpVersion::V2c,
+                            "v2c",
+                        );
+                    });
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.horizontal(|ui| {
+                        ui.label("Timeout (ms)");
+                        render_numeric_textbox(ui, &mut window_state.settings_timeout_ms);
+                    });
+
+                    ui.add_space(DEFAULT_SPACER);
+                    ui.checkbox(
+                        &mut window_state.settings_walk_tables,
+                        "Also walk interface and ARP tables",
+                    )
+                    .on_hover_text(
+                        "Takes a lot longer on switches and routers with many ports / neighbours.",
+                    );
+
+                    ui.add_space(ACTION_SPACER);
+                    if ui.button("Probe all devices").clicked() {
+                        let probe_config =
+                            window_state.get_probe_config(&app_context.app_state.project_settings);
+                        window_state.communities_validation_err = probe_config.is_none();
+                        if let Some(probe_config) = probe_config {
+                            probe_snmp_list(
+                                Arc::clone(&app_context.app_state.network_topology.graph),
+                                Arc::clone(&app_context.app_state.status_info),
+                                NetworkTopology::get_all_ips_except_localhost(
+                                    &mut app_context.app_state.network_topology.graph,
+                                ),
+                                probe_config,
+                            );
+                            window_state.open = false;
+                        }
+                    }
+                });
+            });
+
+        app_context.ui_state.snmp_probe_window_state.open &= should_show_window;
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Local};
 use crossbeam::channel::{unbounded, Receiver, Sender};
 use dns_lookup::get_hostname;
 use eframe::epaint::{Color32, Vec2};
@@ -8,18 +9,28 @@ use local_ip_address::local_ip;
 use log::{debug, warn};
 use petgraph::{
     stable_graph::{EdgeIndex, NodeIndex, StableGraph},
-    visit::{EdgeRef, IntoNodeReferences},
+    visit::{EdgeRef, IntoEdgeReferences, IntoNodeReferences},
     Directed,
 };
+use pnet::util::MacAddr;
 use rand::Rng;
 use std::{
-    net::IpAddr,
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use crate::utils::{
+    access_checks::AccessFinding,
     constants::LINE_ENDING,
+    interfaces::SourceBinding,
     ip::{BannerGrabResult, FuzzingResults, Port},
+    mdns::MdnsService,
+    netbios::NetBiosInfo,
+    oui::lookup_mac_vendor,
+    snmp::SnmpInfo,
+    ssdp::UpnpDeviceInfo,
 };
 
 lazy_static! {
@@ -45,6 +56,7 @@ pub struct PortInfo {
     pub fuzzing_results: FuzzingResults,
     pub possible_service_name: String,
     pub possible_service_usefull_info: Option<String>,
+    pub access_findings: Vec<AccessFinding>, // filled by the optional access checks
 }
 impl PortInfo {
     pub fn new(
@@ -60,25 +72,81 @@ impl PortInfo {
             fuzzing_results,
             possible_service_name,
             possible_service_usefull_info,
+            access_findings: vec![],
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HostnameSource {
+    Dns,
+    NetBios,
+    Llmnr,
+    Mdns,
+    Dhcp,
+    Snmp,
+}
+impl Display for HostnameSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostnameSource::Dns => write!(f, "DNS"),
+            HostnameSource::NetBios => write!(f, "NetBIOS"),
+            HostnameSource::Llmnr => write!(f, "LLMNR"),
+            HostnameSource::Mdns => write!(f, "mDNS"),
+            HostnameSource::Dhcp => write!(f, "DHCP"),
+            HostnameSource::Snmp => write!(f, "SNMP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostnameRecord {
+    pub name: String,
+    pub source: HostnameSource,
+}
+
+// Oldest pings get dropped past this
+const MAX_PING_HISTORY_LEN: usize = 1000;
+pub const LOW_LATENCY_MS: u128 = 20;
+pub const HIGH_LATENCY_MS: u128 = 100;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PingRecord {
+    pub at: DateTime<Local>,
+    pub rtt: Option<Duration>, // None => timed out / unreachable
+}
+
 #[derive(Debug, Clone)]
 pub struct NetworkTopologyNode {
     pub ip: IpAddr, // ip == id ; has to be unique
     pub notes: String,
     pub is_localhost: bool, // True => node is a machine that's running this program
     pub hostname: String,
+    pub hostnames: Vec<HostnameRecord>, // every name found for the node, `hostname` is the one that gets rendered
     pub opened_ports: Vec<PortInfo>,
+    pub mac: Option<MacAddr>,
+    pub first_seen: Option<DateTime<Local>>,
+    pub last_seen: Option<DateTime<Local>>,
+    pub mdns_services: Vec<MdnsService>,
+    pub upnp_devices: Vec<UpnpDeviceInfo>,
+    pub netbios_info: Option<NetBiosInfo>,
+    pub snmp_info: Option<SnmpInfo>,
+    pub ping_history: Vec<PingRecord>, // oldest first
 }
 impl NetworkTopologyNode {
     pub fn new(ip: IpAddr, notes: String, hostname: Option<String>) -> Self {
         Self::new_internal(ip, notes, false, hostname)
     }
 
-    pub fn new_my_pc() -> anyhow::Result<Self> {
-        let my_local_ip = local_ip()?;
+    // Prefers the address scans go out of, so edges from localhost reflect reality
+    pub fn new_my_pc(source: &SourceBinding) -> anyhow::Result<Self> {
+        let my_local_ip = match source
+            .address
+            .or_else(|| source.address_for(IpAddr::V4(Ipv4Addr::UNSPECIFIED)))
+        {
+            Some(s) => s,
+            None => local_ip()?,
+        };
         let my_hostname = get_hostname().ok();
         Ok(Self::new_internal(
             my_local_ip,
@@ -99,7 +167,57 @@ impl NetworkTopologyNode {
             notes,
             is_localhost,
             hostname: hostname.unwrap_or_default(),
+            hostnames: vec![],
             opened_ports: vec![],
+            mac: None,
+            first_seen: None,
+            last_seen: None,
+            mdns_services: vec![],
+            upnp_devices: vec![],
+            netbios_info: None,
+            snmp_info: None,
+            ping_history: vec![],
+        }
+    }
+
+    pub fn record_ping(&mut self, rtt: Option<Duration>) {
+        self.ping_history.push(PingRecord {
+            at: Local::now(),
+            rtt,
+        });
+        let overflow = self
+            .ping_history
+            .len()
+            .saturating_sub(MAX_PING_HISTORY_LEN);
+        self.ping_history.drain(..overflow);
+    }
+
+    // None => never pinged
+    pub fn uptime_percentage(&self) -> Option<f32> {
+        if self.ping_history.is_empty() {
+            return None;
+        }
+        let replies = self.ping_history.iter().filter(|s| s.rtt.is_some()).count();
+        Some(replies as f32 * 100.0 / self.ping_history.len() as f32)
+    }
+
+    // Doesn't override already set hostname, since it could've been typed in by the user
+    pub fn add_hostname(&mut self, name: &str, source: HostnameSource) {
+        if name.is_empty() {
+            return;
+        }
+        if !self
+            .hostnames
+            .iter()
+            .any(|s| s.source == source && s.name.eq_ignore_ascii_case(name))
+        {
+            self.hostnames.push(HostnameRecord {
+                name: name.to_owned(),
+                source,
+            });
+        }
+        if self.hostname.is_empty() {
+            self.hostname = name.to_owned();
         }
     }
 }
@@ -128,7 +246,7 @@ impl Default for NetworkTopology {
             graph_changes_receiver,
         };
 
-        if let Ok(new_my_pc_node) = NetworkTopologyNode::new_my_pc() {
+        if let Ok(new_my_pc_node) = NetworkTopologyNode::new_my_pc(&SourceBinding::default()) {
             NetworkTopology::add_node(
                 &mut new_topology.graph,
                 new_my_pc_node,
@@ -279,6 +397,26 @@ impl NetworkTopology {
             .collect()
     }
 
+    // bool => whether the node was newly created
+    pub fn get_or_add_node_by_ip(
+        graph: &mut NetworkTopologyGraph,
+        ip: IpAddr,
+    ) -> (NodeIndex, bool) {
+        // Other workers can add the same ip between the lookup and the insert, then it gets found on the next try
+        loop {
+            if let Some((node_index, _)) = Self::get_node_by_ip(graph, ip) {
+                return (node_index, false);
+            }
+            if let Some(new_node_index) = Self::add_node(
+                graph,
+                NetworkTopologyNode::new(ip, "".to_string(), None),
+                None,
+            ) {
+                return (new_node_index, true);
+            }
+        }
+    }
+
     // TODO: Spanwing nodes like this is messy, but looks / works well enough atm. Could be improved.
     pub fn add_node(
         graph: &mut NetworkTopologyGraph,
@@ -311,6 +449,52 @@ impl NetworkTopology {
         // TODO: Graph should re-zoom to fit all
     }
 
+    // Label gets regenerated afterwards, so the closure can change anything (hostname, ...)
+    pub fn update_node_data(
+        graph: &mut NetworkTopologyGraph,
+        node: NodeIndex,
+        update: impl FnOnce(&mut NetworkTopologyNode),
+    ) {
+        let mut graph_lock = graph.lock().unwrap();
+        let Some(node_to_update) = graph_lock.node_weight_mut(node) else {
+            return;
+        };
+        let mut new_data = node_to_update.data().unwrap().clone();
+        update(&mut new_data);
+        *node_to_update = node_to_update.with_label(Self::generate_node_label(&new_data));
+        node_to_update.set_data(Some(new_data));
+    }
+
+    // After something the labels are made of changed for every node (e.g. a new OUI table)
+    pub fn refresh_node_labels(graph: &mut NetworkTopologyGraph) {
+        let mut graph_lock = graph.lock().unwrap();
+        for node in graph_lock.node_weights_mut() {
+            let new_label = Self::generate_node_label(node.data().unwrap());
+            *node = node.with_label(new_label);
+        }
+    }
+
+    pub fn ensure_edge_from_localhost(graph: &mut NetworkTopologyGraph, to: NodeIndex) {
+        let Some((localhost, _)) = Self::get_localhosts_node(graph) else {
+            return;
+        };
+        if localhost == to || graph.lock().unwrap().contains_edge(localhost, to) {
+            return;
+        }
+        Self::add_edge(graph, localhost, to, NetworkTopologyEdge::default());
+    }
+
+    // Node is no longer reachable, but stays in the topology
+    pub fn remove_edge_from_localhost(graph: &mut NetworkTopologyGraph, to: NodeIndex) {
+        let Some((localhost, _)) = Self::get_localhosts_node(graph) else {
+            return;
+        };
+        let mut graph_lock = graph.lock().unwrap();
+        if let Some(edge) = graph_lock.find_edge(localhost, to) {
+            graph_lock.remove_edge(edge);
+        }
+    }
+
     pub fn remove_node(graph: &mut NetworkTopologyGraph, node: NodeIndex) {
         graph.lock().unwrap().remove_node(node);
     }
@@ -321,7 +505,7 @@ impl NetworkTopology {
         to: NodeIndex,
         weight: NetworkTopologyEdge,
     ) -> EdgeIndex {
-        let new_edge = egui_graphs::Edge::new(weight).with_width(0.2);
+        let new_edge = Self::create_edge(weight);
 
         graph.lock().unwrap().add_edge(from, to, new_edge)
     }
@@ -337,13 +521,45 @@ impl NetworkTopology {
         }
     }
 
-    pub fn update_node_label(graph: &mut NetworkTopologyGraph, node: NodeIndex) {
-        let mut graph_lock = graph.lock().unwrap();
-        let Some(node_to_update) = graph_lock.node_weight_mut(node) else {
-            return;
+    // Timeouts only get recorded for existing nodes, otherwise every silent address of a range would become one
+    pub fn record_ping(graph: &mut NetworkTopologyGraph, ip: IpAddr, rtt: Option<Duration>) {
+        let node_index = match rtt {
+            Some(_) => Self::get_or_add_node_by_ip(graph, ip).0,
+            None => match Self::get_node_by_ip(graph, ip) {
+                Some((node_index, _)) => node_index,
+                None => return,
+            },
         };
-        *node_to_update =
-            node_to_update.with_label(Self::generate_node_label(node_to_update.data().unwrap()));
+        Self::update_node_data(graph, node_index, |node| node.record_ping(rtt));
+    }
+
+    // Colors the edge by the latest ping of the node it leads to, false => back to the default color
+    pub fn color_edges_by_latency(graph: &mut NetworkTopologyGraph, should_color: bool) {
+        let mut graph_lock = graph.lock().unwrap();
+        let edges = graph_lock
+            .edge_references()
+            .map(|s| (s.id(), s.target()))
+            .collect::<Vec<(EdgeIndex, NodeIndex)>>();
+        for (edge_index, target) in edges {
+            let color = graph_lock
+                .node_weight(target)
+                .and_then(|s| s.data().unwrap().ping_history.last().copied())
+                .filter(|_| should_color)
+                .map(|s| get_latency_color(s.rtt));
+            let edge = graph_lock.edge_weight_mut(edge_index).unwrap(); // safe to unwrap, index comes from the graph itself
+            if edge.color() == color {
+                continue;
+            }
+            let data = edge.data().cloned().unwrap_or_default();
+            *edge = match color {
+                Some(s) => Self::create_edge(data).with_color(s),
+                None => Self::create_edge(data),
+            };
+        }
+    }
+
+    fn create_edge(weight: NetworkTopologyEdge) -> egui_graphs::Edge<NetworkTopologyEdge> {
+        egui_graphs::Edge::new(weight).with_width(0.2)
     }
 
     fn generate_node_label(node_data: &NetworkTopologyNode) -> String {
@@ -352,7 +568,20 @@ impl NetworkTopology {
             new_label.push_str(LINE_ENDING);
             new_label.push_str(&node_data.hostname);
         }
+        if let Some(vendor) = node_data.mac.and_then(lookup_mac_vendor) {
+            new_label.push_str(LINE_ENDING);
+            new_label.push_str(&vendor);
+        }
 
         new_label
     }
 }
+
+pub fn get_latency_color(rtt: Option<Duration>) -> Color32 {
+    match rtt.map(|s| s.as_millis()) {
+        None => Color32::from_rgb(220, 60, 60),
+        Some(s) if s < LOW_LATENCY_MS => Color32::from_rgb(90, 200, 90),
+        Some(s) if s < HIGH_LATENCY_MS => Color32::from_rgb(230, 200, 60),
+        Some(_) => Color32::from_rgb(240, 140, 50),
+    }
+}
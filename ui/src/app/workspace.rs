@@ -5,12 +5,17 @@ use uuid::Uuid;
 use crate::{
     app::modals::device_window_state::DeviceWindowState,
     utils::{
+        constants::RAW_SOCKETS_WITH_PROXY_HINT,
         general::add_localhost_pc,
         icmp::{
             DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
             DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
         },
-        ip::{ping_ip_list, update_hostname_list},
+        ip::{ping_ip_list, update_hostname_list, PingIpListConfig},
+        mdns::{browse_mdns_services, DEFAULT_MDNS_RESPONSE_WAIT_MS},
+        neighbors::seed_from_neighbor_cache,
+        snapshots::ScanSnapshotsRef,
+        ssdp::{discover_ssdp_devices, DEFAULT_SSDP_RESPONSE_WAIT_MS},
     },
 };
 
@@ -18,14 +23,23 @@ use super::{
     menu_bar::{file_menu_button::FileMenuButton, view_menu_button::ViewMenuButton},
     modals::{
         add_new_device_window::AddNewDeviceWindowState,
-        generic_info_window::GenericInfoWindowState, scan_ip_range_window::ScanIpRangeWindowState,
+        domain_recon_window::DomainReconWindowState, generic_info_window::GenericInfoWindowState,
+        ipv6_discovery_window::Ipv6DiscoveryWindowState,
+        local_network_window::LocalNetworkWindowState,
+        monitoring_window::MonitoringWindowState,
+        passive_discovery_window::PassiveDiscoveryWindowState,
+        project_settings_window::ProjectSettingsWindowState,
+        raw_data_viewer_window::RawDataViewerWindowState,
+        scan_diff_window::ScanDiffWindowState, scan_ip_range_window::ScanIpRangeWindowState,
+        snmp_probe_window::SnmpProbeWindowState,
     },
     network_topology::{
         NetworkTopology, EGUI_GRAPH_SETTINGS_INTERACTIONS, EGUI_GRAPH_SETTINGS_NAVIGATION,
-        EGUI_GRAPH_SETTINGS_STYLE,
+        EGUI_GRAPH_SETTINGS_STYLE, HIGH_LATENCY_MS, LOW_LATENCY_MS,
     },
     workspace_models::{
-        AppState, StatusInfo, StatusMessage, TabsContext, UIState, WorkspaceContext,
+        AppState, ProjectSettings, StatusInfo, StatusMessage, TabsContext, UIState,
+        WorkspaceContext,
     },
     workspace_tab::{default_tabs, WorkspaceTab},
 };
@@ -45,15 +59,27 @@ impl Workspace {
             app_state: AppState {
                 network_topology: NetworkTopology::default(),
                 status_info: Arc::new(Mutex::new(StatusInfo::default())),
+                project_settings: ProjectSettings::default(),
+                scan_snapshots: ScanSnapshotsRef::default(),
             },
             ui_state: UIState {
                 open_tabs: tabs_context.default_tabs.clone(),
+                color_edges_by_latency: false,
                 add_new_device_window_state: AddNewDeviceWindowState::default(),
                 add_this_computer_window_state: GenericInfoWindowState::new(
                     "Cannot add this computer",
                 ),
                 scan_ip_range_window_state: ScanIpRangeWindowState::default(),
                 device_window_states: vec![],
+                raw_data_viewer_window_state: RawDataViewerWindowState::default(),
+                passive_discovery_window_state: PassiveDiscoveryWindowState::default(),
+                ipv6_discovery_window_state: Ipv6DiscoveryWindowState::default(),
+                snmp_probe_window_state: SnmpProbeWindowState::default(),
+                project_settings_window_state: ProjectSettingsWindowState::default(),
+                domain_recon_window_state: DomainReconWindowState::default(),
+                local_network_window_state: LocalNetworkWindowState::default(),
+                monitoring_window_state: MonitoringWindowState::default(),
+                scan_diff_window_state: ScanDiffWindowState::default(),
             },
         };
 
@@ -103,9 +129,21 @@ impl eframe::App for Workspace {
                     &mut self.context.ui_state.add_this_computer_window_state,
                 );
                 ScanIpRangeWindowState::render(ctx, &mut self.context);
+                PassiveDiscoveryWindowState::render(ctx, &mut self.context);
+                Ipv6DiscoveryWindowState::render(ctx, &mut self.context);
+                SnmpProbeWindowState::render(ctx, &mut self.context);
+                ProjectSettingsWindowState::render(ctx, &mut self.context);
+                DomainReconWindowState::render(ctx, &mut self.context);
+                LocalNetworkWindowState::render(ctx, &mut self.context);
+                MonitoringWindowState::render(ctx, &mut self.context);
+                ScanDiffWindowState::render(ctx, &mut self.context);
                 for i in 0..self.context.ui_state.device_window_states.len() {
                     DeviceWindowState::render(ctx, &mut self.context, i);
                 }
+                RawDataViewerWindowState::render(
+                    ctx,
+                    &mut self.context.ui_state.raw_data_viewer_window_state,
+                );
 
                 let mut dock_style = egui_dock::Style::from_egui(ui.style());
                 dock_style.separator.extra = 50.0;
@@ -132,6 +170,7 @@ impl egui_dock::TabViewer for WorkspaceContext {
             "general_tab" => self.render_general_tab(ui),
             "discovery_shared_tab" => self.render_discovery_shared_tab(ui),
             "discovery_inside_tab" => self.render_discovery_inside_tab(ui),
+            "discovery_outside_tab" => self.render_discovery_outside_tab(ui),
             "topology_overview_tab" => self.render_topology_overview_tab(ui),
             "status_tab" => self.render_status_tab(ui),
             // "Simple Demo" => self.simple_demo(ui),
@@ -166,38 +205,82 @@ impl WorkspaceContext {
             if ui.button("Add a new device").clicked() {
                 self.ui_state.add_new_device_window_state.open = true;
             }
+            if ui.button("Project settings").clicked() {
+                self.ui_state
+                    .project_settings_window_state
+                    .show(&self.app_state.project_settings);
+            }
+            let is_proxy_active = self.app_state.project_settings.proxy.is_some();
             if ui
-                .button("Refresh connection status to all devices (soft)")
+                .add_enabled(
+                    !is_proxy_active,
+                    egui::Button::new("Refresh connection status to all devices (soft)"),
+                )
+                .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
                 .clicked()
             {
                 ping_ip_list(
                     Arc::clone(&self.app_state.network_topology.graph),
                     Arc::clone(&self.app_state.status_info),
+                    Arc::clone(&self.app_state.scan_snapshots),
                     NetworkTopology::get_all_ips_except_localhost(
                         &mut self.app_state.network_topology.graph,
-                    ),
-                    DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
-                    DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
-                    true,
-                    false,
+                    )
+                    .into(),
+                    PingIpListConfig {
+                        ping_timeout_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
+                        ping_checkup_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
+                        remove_connectivity_status_when_unreachable: true,
+                        reset_connectivity_status: false,
+                        resolve_hostnames_with: None,
+                        exclusions: self.app_state.project_settings.exclusions.clone(),
+                        source: self.app_state.project_settings.source.clone(),
+                    },
                 );
             }
             if ui
-                .button("Refresh connection status to all devices (hard)")
+                .add_enabled(
+                    !is_proxy_active,
+                    egui::Button::new("Refresh connection status to all devices (hard)"),
+                )
+                .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
                 .clicked()
             {
                 ping_ip_list(
                     Arc::clone(&self.app_state.network_topology.graph),
                     Arc::clone(&self.app_state.status_info),
+                    Arc::clone(&self.app_state.scan_snapshots),
                     NetworkTopology::get_all_ips_except_localhost(
                         &mut self.app_state.network_topology.graph,
-                    ),
-                    DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
-                    DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
-                    false,
-                    true,
+                    )
+                    .into(),
+                    PingIpListConfig {
+                        ping_timeout_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS,
+                        ping_checkup_ms: DEFAULT_PING_ENSURED_CONNECTIVITY_CHECKUP_MS,
+                        remove_connectivity_status_when_unreachable: false,
+                        reset_connectivity_status: true,
+                        resolve_hostnames_with: None,
+                        exclusions: self.app_state.project_settings.exclusions.clone(),
+                        source: self.app_state.project_settings.source.clone(),
+                    },
                 );
             }
+            let unseen_monitoring_alerts = self.ui_state.monitoring_window_state.unseen_alerts();
+            if ui
+                .button(if unseen_monitoring_alerts > 0 {
+                    format!("Monitoring ({} new alerts)", unseen_monitoring_alerts)
+                } else {
+                    "Monitoring".to_owned()
+                })
+                .clicked()
+            {
+                self.ui_state.monitoring_window_state.open = true;
+            }
+            if ui.button("Scan history").clicked() {
+                self.ui_state
+                    .scan_diff_window_state
+                    .show(&self.app_state.scan_snapshots.lock().unwrap());
+            }
         });
     }
 
@@ -213,20 +296,102 @@ impl WorkspaceContext {
                     .iter()
                     .map(|s| s.1.data().unwrap().ip)
                     .collect(),
+                    self.app_state.project_settings.dns_resolver_config(),
+                    self.app_state.project_settings.exclusions.clone(),
+                    self.app_state.project_settings.source.clone(),
                 );
             }
+            if ui.button("Browse mDNS / DNS-SD services").clicked() {
+                browse_mdns_services(
+                    Arc::clone(&self.app_state.network_topology.graph),
+                    Arc::clone(&self.app_state.status_info),
+                    DEFAULT_MDNS_RESPONSE_WAIT_MS,
+                );
+            }
+            if ui.button("Discover SSDP / UPnP devices").clicked() {
+                discover_ssdp_devices(
+                    Arc::clone(&self.app_state.network_topology.graph),
+                    Arc::clone(&self.app_state.status_info),
+                    DEFAULT_SSDP_RESPONSE_WAIT_MS,
+                    self.app_state.project_settings.exclusions.clone(),
+                    self.app_state.project_settings.source.clone(),
+                );
+            }
+            if ui.button("SNMP probe").clicked() {
+                self.ui_state.snmp_probe_window_state.open = true;
+            }
         });
     }
 
     fn render_discovery_inside_tab(&mut self, ui: &mut egui::Ui) {
+        // Everything here pings or sniffs, none of it can go through a proxy
+        let is_proxy_active = self.app_state.project_settings.proxy.is_some();
         ui.horizontal(|ui| {
-            if ui.button("Scan IP Range").clicked() {
-                self.ui_state.scan_ip_range_window_state.open = true;
+            if ui
+                .add_enabled(!is_proxy_active, egui::Button::new("Scan IP Range"))
+                .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
+                .clicked()
+            {
+                self.ui_state.scan_ip_range_window_state.show();
+            }
+            if ui
+                .add_enabled(!is_proxy_active, egui::Button::new("Passive discovery"))
+                .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
+                .clicked()
+            {
+                self.ui_state.passive_discovery_window_state.open = true;
+            }
+            if ui
+                .add_enabled(
+                    !is_proxy_active,
+                    egui::Button::new("IPv6 neighbor discovery"),
+                )
+                .on_disabled_hover_text(RAW_SOCKETS_WITH_PROXY_HINT)
+                .clicked()
+            {
+                self.ui_state
+                    .ipv6_discovery_window_state
+                    .show(&self.app_state.project_settings.source);
+            }
+            if ui.button("Local interfaces & routes").clicked() {
+                self.ui_state.local_network_window_state.show();
+            }
+            if ui
+                .button("Import ARP / neighbor cache")
+                .on_hover_text(
+                    "Adds every host the OS already knows about, without sending any packets.",
+                )
+                .clicked()
+            {
+                seed_from_neighbor_cache(
+                    Arc::clone(&self.app_state.network_topology.graph),
+                    Arc::clone(&self.app_state.status_info),
+                );
+            }
+        });
+    }
+
+    fn render_discovery_outside_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Domain reconnaissance").clicked() {
+                self.ui_state.domain_recon_window_state.open = true;
             }
         });
     }
 
     fn render_topology_overview_tab(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.ui_state.color_edges_by_latency,
+            "Color connections by latency",
+        )
+        .on_hover_text(format!(
+            "Latest ping of each device: green < {} ms, yellow < {} ms, orange above, red no reply",
+            LOW_LATENCY_MS, HIGH_LATENCY_MS
+        ));
+        NetworkTopology::color_edges_by_latency(
+            &mut self.app_state.network_topology.graph,
+            self.ui_state.color_edges_by_latency,
+        );
         ui.add(
             &mut egui_graphs::GraphView::new(
                 &mut self.app_state.network_topology.graph.lock().unwrap(),
@@ -1,11 +1,30 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use crate::utils::constants::LINE_ENDING;
+use crate::utils::{
+    constants::LINE_ENDING,
+    dns_resolver::{DnsResolverConfig, DEFAULT_DNS_TIMEOUT_MS},
+    interfaces::SourceBinding,
+    proxy::ProxyConfig,
+    snapshots::ScanSnapshotsRef,
+    targets::{TargetExclusions, DEFAULT_MAX_TARGETS_WITHOUT_CONFIRMATION},
+};
 
 use super::{
     modals::{
         add_new_device_window::AddNewDeviceWindowState, device_window_state::DeviceWindowState,
-        generic_info_window::GenericInfoWindowState, scan_ip_range_window::ScanIpRangeWindowState,
+        domain_recon_window::DomainReconWindowState, generic_info_window::GenericInfoWindowState,
+        ipv6_discovery_window::Ipv6DiscoveryWindowState,
+        local_network_window::LocalNetworkWindowState,
+        monitoring_window::MonitoringWindowState,
+        passive_discovery_window::PassiveDiscoveryWindowState,
+        project_settings_window::ProjectSettingsWindowState,
+        raw_data_viewer_window::RawDataViewerWindowState,
+        scan_diff_window::ScanDiffWindowState, scan_ip_range_window::ScanIpRangeWindowState,
+        snmp_probe_window::SnmpProbeWindowState,
     },
     network_topology::NetworkTopology,
     workspace_tab::WorkspaceTab,
@@ -38,9 +57,46 @@ pub enum StatusMessage {
     Err(String),
 }
 
+// Settings that apply to the whole project (every scan / discovery started from it)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSettings {
+    pub dns_servers: Vec<SocketAddr>, // empty => use system resolver
+    pub dns_timeout_ms: u64,
+    pub dns_force_tcp: bool,
+    pub exclusions: TargetExclusions, // never probed, whatever the scan
+    pub max_targets_without_confirmation: u64,
+    pub source: SourceBinding, // where pings, TCP connects and raw probes go out of
+    pub proxy: Option<ProxyConfig>, // Some => TCP connects go through it, raw socket features are disabled
+}
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            dns_servers: vec![],
+            dns_timeout_ms: DEFAULT_DNS_TIMEOUT_MS,
+            dns_force_tcp: false,
+            exclusions: TargetExclusions::default(),
+            max_targets_without_confirmation: DEFAULT_MAX_TARGETS_WITHOUT_CONFIRMATION,
+            source: SourceBinding::default(),
+            proxy: None,
+        }
+    }
+}
+impl ProjectSettings {
+    pub fn dns_resolver_config(&self) -> DnsResolverConfig {
+        DnsResolverConfig {
+            servers: self.dns_servers.clone(),
+            timeout: Duration::from_millis(self.dns_timeout_ms),
+            force_tcp: self.dns_force_tcp,
+            source: self.source.resolved(),
+        }
+    }
+}
+
 pub struct AppState {
     pub network_topology: NetworkTopology,
     pub status_info: StatusInfoRef,
+    pub project_settings: ProjectSettings,
+    pub scan_snapshots: ScanSnapshotsRef,
 }
 impl AppState {
     pub fn log_to_status(&self, info_to_append: StatusMessage) {
@@ -79,8 +135,18 @@ impl AppState {
 
 pub struct UIState {
     pub open_tabs: Vec<WorkspaceTab>,
+    pub color_edges_by_latency: bool,
     pub add_new_device_window_state: AddNewDeviceWindowState,
     pub add_this_computer_window_state: GenericInfoWindowState,
     pub scan_ip_range_window_state: ScanIpRangeWindowState,
     pub device_window_states: Vec<DeviceWindowState>,
+    pub raw_data_viewer_window_state: RawDataViewerWindowState,
+    pub passive_discovery_window_state: PassiveDiscoveryWindowState,
+    pub ipv6_discovery_window_state: Ipv6DiscoveryWindowState,
+    pub snmp_probe_window_state: SnmpProbeWindowState,
+    pub project_settings_window_state: ProjectSettingsWindowState,
+    pub domain_recon_window_state: DomainReconWindowState,
+    pub local_network_window_state: LocalNetworkWindowState,
+    pub monitoring_window_state: MonitoringWindowState,
+    pub scan_diff_window_state: ScanDiffWindowState,
 }
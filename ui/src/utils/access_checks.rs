@@ -0,0 +1,1213 @@
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Local};
+use rand::random;
+
+use crate::app::network_topology::PortInfo;
+
+use super::{bytes::bytes_to_utf8, ip::ScanIpPortsConfig, ports::connect_tcp_stream};
+
+// FTP / SMB servers take their time to answer, the banner grabbing timeout is usually way too small for that
+const MIN_ACCESS_CHECK_READ_TIMEOUT_MS: u64 = 1000;
+const MAX_ACCESS_CHECK_RESPONSE_LEN: usize = 64 * 1024;
+const ANONYMOUS_FTP_PASSWORD: &str = "anonymous@example.com";
+const HTTP_USER_AGENT: &str = "naive-network-scanner";
+// Apache / nginx, python's http.server, IIS, lighttpd
+const HTTP_DIRECTORY_LISTING_MARKERS: [&str; 5] = [
+    "<title>Index of /",
+    "<h1>Index of /",
+    "Directory listing for /",
+    "[To Parent Directory]",
+    "<title>Directory Listing",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccessCheck {
+    AnonymousFtp,
+    RedisWithoutAuth,
+    MongoDbWithoutAuth,
+    ElasticsearchWithoutAuth,
+    MemcachedWithoutAuth,
+    SmbNullSession,
+    HttpDirectoryListing,
+}
+impl Display for AccessCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AnonymousFtp => write!(f, "Anonymous FTP login"),
+            Self::RedisWithoutAuth => write!(f, "Redis without AUTH"),
+            Self::MongoDbWithoutAuth => write!(f, "MongoDB without authentication"),
+            Self::ElasticsearchWithoutAuth => write!(f, "Elasticsearch without authentication"),
+            Self::MemcachedWithoutAuth => write!(f, "Memcached without authentication"),
+            Self::SmbNullSession => write!(f, "SMB null session"),
+            Self::HttpDirectoryListing => write!(f, "HTTP directory listing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessFinding {
+    pub check: AccessCheck,
+    pub found_at: DateTime<Local>,
+    pub summary: String,
+    pub evidence: Vec<u8>, // everything the service answered during the check
+}
+
+impl AccessCheck {
+    // By the identified service, then by what it said to banner grabbing / fuzzing, so services on non-standard ports get checked too
+    pub fn for_port(port: &PortInfo) -> Option<Self> {
+        let by_service = match port.possible_service_name.as_str() {
+            "FTP" => Some(Self::AnonymousFtp),
+            "Redis" => Some(Self::RedisWithoutAuth),
+            "MongoDB" => Some(Self::MongoDbWithoutAuth),
+            "Elasticsearch" => Some(Self::ElasticsearchWithoutAuth),
+            "Memcached" => Some(Self::MemcachedWithoutAuth),
+            "Microsoft DS SMB" => Some(Self::SmbNullSession),
+            "HTTP" | "HTTP Proxy" => Some(Self::HttpDirectoryListing),
+            _ => None,
+        };
+        if by_service.is_some() {
+            return by_service;
+        }
+
+        let answers = port
+            .banner
+            .iter()
+            .map(|s| s.result.as_str())
+            .chain(
+                port.fuzzing_results
+                    .iter()
+                    .flatten()
+                    .map(|s| s.result.as_str()),
+            )
+            .collect::<Vec<&str>>();
+        answers.iter().find_map(|s| {
+            if s.starts_with("220") && s.to_lowercase().contains("ftp") {
+                Some(Self::AnonymousFtp)
+            } else if s.contains("redis_version")
+                || s.starts_with("-NOAUTH")
+                || s.starts_with("-ERR unknown command `")
+            {
+                Some(Self::RedisWithoutAuth)
+            } else if s.starts_with("VERSION ") {
+                Some(Self::MemcachedWithoutAuth)
+            } else if s.starts_with("HTTP/1.") || is_html(s) {
+                Some(Self::HttpDirectoryListing)
+            } else {
+                None
+            }
+        })
+    }
+
+    // Ok(None) => the service wants credentials, Err => couldn't tell (not the expected service, connection issues, ...)
+    pub fn run(
+        &self,
+        target: SocketAddr,
+        config: &ScanIpPortsConfig,
+    ) -> anyhow::Result<Option<AccessFinding>> {
+        let mut conversation = Conversation::new(target, config)?;
+        let summary = match self {
+            Self::AnonymousFtp => check_anonymous_ftp(&mut conversation),
+            Self::RedisWithoutAuth => check_redis(&mut conversation),
+            Self::MongoDbWithoutAuth => check_mongodb(&mut conversation),
+            Self::ElasticsearchWithoutAuth => check_elasticsearch(&mut conversation, target),
+            Self::MemcachedWithoutAuth => check_memcached(&mut conversation),
+            Self::SmbNullSession => check_smb_null_session(&mut conversation, target),
+            Self::HttpDirectoryListing => check_http_directory_listing(&mut conversation, target),
+        }?;
+
+        Ok(summary.map(|summary| AccessFinding {
+            check: *self,
+            found_at: Local::now(),
+            summary,
+            evidence: conversation.received,
+        }))
+    }
+}
+
+// Some servers answer garbage (e.g. the fuzzing commands) with a bare HTML error page, no status line
+fn is_html(text: &str) -> bool {
+    let text = text.trim_start().to_lowercase();
+    text.starts_with("<!doctype html") || text.starts_with("<html")
+}
+
+// Keeps everything the service answered, it's the evidence of a finding
+struct Conversation {
+    stream: TcpStream,
+    received: Vec<u8>,
+}
+impl Conversation {
+    fn new(target: SocketAddr, config: &ScanIpPortsConfig) -> anyhow::Result<Self> {
+        let stream = connect_tcp_stream(target, config)?;
+        let timeout = Duration::from_millis(
+            config
+                .read_write_timeout_ms
+                .max(MIN_ACCESS_CHECK_READ_TIMEOUT_MS),
+        );
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(Self {
+            stream,
+            received: vec![],
+        })
+    }
+
+    fn send(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        self.stream.write_all(data)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    // Reads until the answer is complete, the connection gets closed or the service stops talking
+    fn receive(&mut self, is_complete: impl Fn(&[u8]) -> bool) -> anyhow::Result<Vec<u8>> {
+        let mut answer = vec![];
+        let mut buffer = [0; 4096];
+        while !is_complete(&answer) && answer.len() < MAX_ACCESS_CHECK_RESPONSE_LEN {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(bytes_read) => answer.extend_from_slice(&buffer[..bytes_read]),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.received.extend_from_slice(&answer);
+        if answer.is_empty() {
+            return Err(anyhow!("No answer from the service"));
+        }
+        Ok(answer)
+    }
+
+    fn receive_line(&mut self) -> anyhow::Result<String> {
+        let answer = self.receive(|s| s.ends_with(b"\n"))?;
+        Ok(bytes_to_utf8(&answer))
+    }
+}
+
+fn check_anonymous_ftp(conversation: &mut Conversation) -> anyhow::Result<Option<String>> {
+    let greeting = bytes_to_utf8(&conversation.receive(is_ftp_reply_complete)?);
+    if !greeting.starts_with("220") {
+        return Err(anyhow!(
+            "Unexpected FTP greeting \"{}\"",
+            first_line(&greeting)
+        ));
+    }
+
+    conversation.send(b"USER anonymous\r\n")?;
+    let mut reply = bytes_to_utf8(&conversation.receive(is_ftp_reply_complete)?);
+    if reply.starts_with("331") {
+        conversation.send(format!("PASS {}\r\n", ANONYMOUS_FTP_PASSWORD).as_bytes())?;
+        reply = bytes_to_utf8(&conversation.receive(is_ftp_reply_complete)?);
+    }
+    let is_logged_in = reply.starts_with("230");
+    _ = conversation.send(b"QUIT\r\n");
+
+    Ok(is_logged_in.then(|| {
+        format!(
+            "Logged in as anonymous, server said \"{}\"",
+            first_line(&reply)
+        )
+    }))
+}
+
+// Replies can span multiple lines ("230-..."), the last one has a space after the code
+fn is_ftp_reply_complete(data: &[u8]) -> bool {
+    if !data.ends_with(b"\n") {
+        return false;
+    }
+    let text = String::from_utf8_lossy(data);
+    text.lines().last().is_some_and(|s| {
+        s.len() >= 4 && s.as_bytes()[..3].iter().all(u8::is_ascii_digit) && s.as_bytes()[3] == b' '
+    })
+}
+
+fn check_redis(conversation: &mut Conversation) -> anyhow::Result<Option<String>> {
+    conversation.send(b"PING\r\n")?;
+    let reply = conversation.receive_line()?;
+    if reply.starts_with("+PONG") {
+        return Ok(Some("PING answered with PONG without AUTH".to_owned()));
+    }
+    // -NOAUTH => requirepass, -DENIED => protected mode, -WRONGPASS / -ERR ... auth => ACLs
+    if reply.starts_with("-NOAUTH")
+        || reply.starts_with("-DENIED")
+        || reply.starts_with("-WRONGPASS")
+        || reply.to_lowercase().contains("auth")
+    {
+        return Ok(None);
+    }
+    Err(anyhow!(
+        "Unexpected answer to PING \"{}\"",
+        first_line(&reply)
+    ))
+}
+
+fn check_memcached(conversation: &mut Conversation) -> anyhow::Result<Option<String>> {
+    conversation.send(b"stats\r\n")?;
+    let reply = bytes_to_utf8(
+        &conversation.receive(|s| s.ends_with(b"END\r\n") || s.ends_with(b"ERROR\r\n"))?,
+    );
+    if reply.starts_with("STAT ") {
+        let version = reply
+            .lines()
+            .find_map(|s| s.strip_prefix("STAT version "))
+            .unwrap_or("unknown");
+        return Ok(Some(format!(
+            "stats answered without authentication (version {})",
+            version
+        )));
+    }
+    // SASL enabled memcached refuses everything before authenticating
+    if reply.contains("ERROR") {
+        return Ok(None);
+    }
+    Err(anyhow!(
+        "Unexpected answer to stats \"{}\"",
+        first_line(&reply)
+    ))
+}
+
+fn check_elasticsearch(
+    conversation: &mut Conversation,
+    target: SocketAddr,
+) -> anyhow::Result<Option<String>> {
+    let (status_code, body) = http_get(conversation, target, "/")?;
+    match status_code {
+        200 if body.contains("cluster_name") => Ok(Some(format!(
+            "Cluster info readable without credentials (cluster \"{}\", version {})",
+            find_json_string(&body, "cluster_name").unwrap_or_default(),
+            find_json_string(&body, "number").unwrap_or("unknown".to_owned())
+        ))),
+        401 | 403 => Ok(None),
+        _ => Err(anyhow!(
+            "Unexpected HTTP {} answer, likely not Elasticsearch",
+            status_code
+        )),
+    }
+}
+
+fn check_http_directory_listing(
+    conversation: &mut Conversation,
+    target: SocketAddr,
+) -> anyhow::Result<Option<String>> {
+    let (status_code, body) = http_get(conversation, target, "/")?;
+    if status_code != 200 {
+        return Ok(None);
+    }
+    Ok(HTTP_DIRECTORY_LISTING_MARKERS
+        .iter()
+        .find(|s| body.contains(*s))
+        .map(|s| {
+            format!(
+                "GET / returns a directory listing (\"{}\")",
+                s.trim_start_matches('<')
+            )
+        }))
+}
+
+// => (status code, body), the connection gets closed by the server afterwards
+fn http_get(
+    conversation: &mut Conversation,
+    target: SocketAddr,
+    path: &str,
+) -> anyhow::Result<(u16, String)> {
+    conversation.send(
+        format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nAccept: */*\r\nConnection: close\r\n\r\n",
+            path, target, HTTP_USER_AGENT
+        )
+        .as_bytes(),
+    )?;
+    let response = bytes_to_utf8(&conversation.receive(|_| false)?);
+    let status_code = response
+        .strip_prefix("HTTP/1.")
+        .and_then(|s| s.get(2..5))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| anyhow!("Not an HTTP answer \"{}\"", first_line(&response)))?;
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|s| s.1.to_owned())
+        .unwrap_or_default();
+    Ok((status_code, body))
+}
+
+// Good enough for pulling a value out of a known answer, not a JSON parser
+fn find_json_string(json: &str, key: &str) -> Option<String> {
+    let (_, rest) = json.split_once(&format!("\"{}\"", key))?;
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    Some(rest.split_once('"')?.0.to_owned())
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default().trim()
+}
+
+// MongoDB
+
+const MONGODB_OP_MSG: i32 = 2013;
+
+// { listDatabases: 1, $db: "admin" } as OP_MSG, works on every version since 3.6
+fn check_mongodb(conversation: &mut Conversation) -> anyhow::Result<Option<String>> {
+    let mut document = vec![];
+    document.push(0x10); // int32
+    document.extend_from_slice(b"listDatabases\0");
+    document.extend_from_slice(&1i32.to_le_bytes());
+    document.push(0x02); // string
+    document.extend_from_slice(b"$db\0");
+    document.extend_from_slice(&6i32.to_le_bytes());
+    document.extend_from_slice(b"admin\0");
+    document.push(0x00);
+    let document_len = (document.len() + 4) as i32;
+    document.splice(0..0, document_len.to_le_bytes());
+
+    let mut message = vec![];
+    message.extend_from_slice(&random::<i32>().to_le_bytes()); // requestID
+    message.extend_from_slice(&0i32.to_le_bytes()); // responseTo
+    message.extend_from_slice(&MONGODB_OP_MSG.to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes()); // flagBits
+    message.push(0x00); // section kind: body
+    message.extend_from_slice(&document);
+    let message_len = (message.len() + 4) as i32;
+    message.splice(0..0, message_len.to_le_bytes());
+    conversation.send(&message)?;
+
+    let reply = conversation.receive(|s| read_bson_len(s, 0).is_some_and(|len| s.len() >= len))?;
+    // header (16) + flagBits (4) + section kind (1)
+    if reply.len() < 21
+        || i32::from_le_bytes([reply[12], reply[13], reply[14], reply[15]]) != MONGODB_OP_MSG
+    {
+        return Err(anyhow!("Not a MongoDB answer"));
+    }
+    let fields =
+        read_bson_fields(&reply[21..]).ok_or_else(|| anyhow!("Malformed MongoDB answer"))?;
+    let is_ok = fields.iter().any(|(name, kind, value)| {
+        name == "ok"
+            && match kind {
+                0x01 => {
+                    value.len() == 8 && f64::from_le_bytes(value[..8].try_into().unwrap()) == 1.0
+                }
+                0x10 => value.len() == 4 && i32::from_le_bytes(value[..4].try_into().unwrap()) == 1,
+                _ => false,
+            }
+    });
+    if !is_ok {
+        // Unauthorized (13) => auth is on, anything else is an error we can't tell anything from
+        let errmsg = fields
+            .iter()
+            .find(|s| s.0 == "errmsg" && s.1 == 0x02)
+            .map(|s| bytes_to_utf8(bson_string_value(s.2)))
+            .unwrap_or_default();
+        if errmsg.contains("auth") || errmsg.contains("Unauthorized") {
+            return Ok(None);
+        }
+        return Err(anyhow!("listDatabases failed \"{}\"", errmsg));
+    }
+
+    let database_names = fields
+        .iter()
+        .find(|s| s.0 == "databases" && s.1 == 0x04)
+        .and_then(|s| read_bson_fields(s.2))
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| s.1 == 0x03)
+        .filter_map(|s| read_bson_fields(s.2))
+        .filter_map(|s| {
+            s.iter()
+                .find(|s| s.0 == "name" && s.1 == 0x02)
+                .map(|s| bytes_to_utf8(bson_string_value(s.2)))
+        })
+        .collect::<Vec<String>>();
+    Ok(Some(format!(
+        "listDatabases works without credentials ({})",
+        database_names.join(", ")
+    )))
+}
+
+// Top level elements of a BSON document => (name, type, raw value). None => malformed / unknown type.
+fn read_bson_fields(document: &[u8]) -> Option<Vec<(String, u8, &[u8])>> {
+    let document_len = read_bson_len(document, 0)?;
+    let document = document.get(..document_len)?;
+    let mut fields = vec![];
+    let mut position = 4;
+    while let Some(&kind) = document.get(position).filter(|s| **s != 0x00) {
+        position += 1;
+        let name_len = document[position..].iter().position(|s| *s == 0x00)?;
+        let name = bytes_to_utf8(&document[position..position + name_len]);
+        position += name_len + 1;
+
+        let value_len = match kind {
+            0x01 | 0x09 | 0x11 | 0x12 => 8, // double, datetime, timestamp, int64
+            0x02 => read_bson_len(document, position)?.checked_add(4)?, // string
+            0x03 | 0x04 => read_bson_len(document, position)?, // document, array
+            0x05 => read_bson_len(document, position)?.checked_add(5)?, // binary
+            0x07 => 12,                     // ObjectId
+            0x08 => 1,                      // bool
+            0x0A => 0,                      // null
+            0x10 => 4,                      // int32
+            0x13 => 16,                     // decimal128
+            _ => return None,
+        };
+        let value_end = position.checked_add(value_len)?;
+        fields.push((name, kind, document.get(position..value_end)?));
+        position = value_end;
+    }
+    Some(fields)
+}
+
+// Lengths come from the reply, a negative one => malformed
+fn read_bson_len(data: &[u8], at: usize) -> Option<usize> {
+    let len = i32::from_le_bytes(data.get(at..at.checked_add(4)?)?.try_into().ok()?);
+    usize::try_from(len).ok()
+}
+
+// Length prefixed, null terminated
+fn bson_string_value(value: &[u8]) -> &[u8] {
+    value
+        .get(4..value.len().saturating_sub(1))
+        .unwrap_or_default()
+}
+
+// SMB
+
+const SMB2_PROTOCOL_ID: [u8; 4] = [0xFE, b'S', b'M', b'B'];
+const SMB2_HEADER_LEN: usize = 64;
+const SMB2_NEGOTIATE: u16 = 0x0000;
+const SMB2_SESSION_SETUP: u16 = 0x0001;
+const SMB2_TREE_CONNECT: u16 = 0x0003;
+// No 3.1.1, it would need negotiate contexts
+const SMB2_DIALECTS: [u16; 4] = [0x0202, 0x0210, 0x0300, 0x0302];
+const STATUS_SUCCESS: u32 = 0x0000_0000;
+const STATUS_MORE_PROCESSING_REQUIRED: u32 = 0xC000_0016;
+const SMB2_SESSION_FLAG_IS_GUEST: u16 = 0x0001;
+const SMB2_SESSION_FLAG_IS_NULL: u16 = 0x0002;
+// UNICODE | REQUEST_TARGET | NTLM | ALWAYS_SIGN | EXTENDED_SESSIONSECURITY | 128 | 56
+const NTLMSSP_NEGOTIATE_FLAGS: u32 =
+    0x0000_0001 | 0x0000_0004 | 0x0000_0200 | 0x0000_8000 | 0x0008_0000 | 0x2000_0000 | 0x8000_0000;
+const NTLMSSP_NEGOTIATE_ANONYMOUS: u32 = 0x0000_0800;
+
+// SMB2 negotiate, then NTLM session setup with empty user / password. Raw NTLMSSP without SPNEGO, both Windows and Samba take it.
+fn check_smb_null_session(
+    conversation: &mut Conversation,
+    target: SocketAddr,
+) -> anyhow::Result<Option<String>> {
+    let mut negotiate = vec![];
+    negotiate.extend_from_slice(&36u16.to_le_bytes()); // StructureSize
+    negotiate.extend_from_slice(&(SMB2_DIALECTS.len() as u16).to_le_bytes());
+    negotiate.extend_from_slice(&1u16.to_le_bytes()); // SecurityMode: signing enabled
+    negotiate.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+    negotiate.extend_from_slice(&0u32.to_le_bytes()); // Capabilities
+    negotiate.extend_from_slice(&random::<[u8; 16]>()); // ClientGuid
+    negotiate.extend_from_slice(&0u64.to_le_bytes()); // ClientStartTime
+    for dialect in SMB2_DIALECTS {
+        negotiate.extend_from_slice(&dialect.to_le_bytes());
+    }
+    let (status, _, body) = smb2_request(conversation, SMB2_NEGOTIATE, 0, 0, &negotiate)?;
+    if status != STATUS_SUCCESS {
+        return Err(anyhow!(
+            "SMB2 negotiate failed with status {:#010x}",
+            status
+        ));
+    }
+    let dialect = body
+        .get(4..6)
+        .map(|s| u16::from_le_bytes([s[0], s[1]]))
+        .unwrap_or_default();
+
+    let mut ntlm_negotiate = vec![];
+    ntlm_negotiate.extend_from_slice(b"NTLMSSP\0");
+    ntlm_negotiate.extend_from_slice(&1u32.to_le_bytes());
+    ntlm_negotiate.extend_from_slice(&NTLMSSP_NEGOTIATE_FLAGS.to_le_bytes());
+    ntlm_negotiate.extend_from_slice(&[0; 16]); // no domain, no workstation
+    let (status, session_id, _) = smb2_request(
+        conversation,
+        SMB2_SESSION_SETUP,
+        1,
+        0,
+        &build_session_setup(&ntlm_negotiate),
+    )?;
+    if status != STATUS_MORE_PROCESSING_REQUIRED {
+        return Err(anyhow!(
+            "NTLM negotiate failed with status {:#010x}",
+            status
+        ));
+    }
+
+    // Anonymous => LM response is a single zero byte, everything else is empty
+    let mut ntlm_authenticate = vec![];
+    ntlm_authenticate.extend_from_slice(b"NTLMSSP\0");
+    ntlm_authenticate.extend_from_slice(&3u32.to_le_bytes());
+    let payload_offset = 64u32;
+    for (len, offset) in [
+        (1u16, payload_offset),  // LmChallengeResponse
+        (0, payload_offset + 1), // NtChallengeResponse
+        (0, payload_offset + 1), // DomainName
+        (0, payload_offset + 1), // UserName
+        (0, payload_offset + 1), // Workstation
+        (0, payload_offset + 1), // EncryptedRandomSessionKey
+    ] {
+        ntlm_authenticate.extend_from_slice(&len.to_le_bytes());
+        ntlm_authenticate.extend_from_slice(&len.to_le_bytes());
+        ntlm_authenticate.extend_from_slice(&offset.to_le_bytes());
+    }
+    ntlm_authenticate
+        .extend_from_slice(&(NTLMSSP_NEGOTIATE_FLAGS | NTLMSSP_NEGOTIATE_ANONYMOUS).to_le_bytes());
+    ntlm_authenticate.push(0x00);
+    let (status, _, body) = smb2_request(
+        conversation,
+        SMB2_SESSION_SETUP,
+        2,
+        session_id,
+        &build_session_setup(&ntlm_authenticate),
+    )?;
+    if status != STATUS_SUCCESS {
+        return Ok(None);
+    }
+    let session_flags = body
+        .get(2..4)
+        .map(|s| u16::from_le_bytes([s[0], s[1]]))
+        .unwrap_or_default();
+
+    let path = format!("\\\\{}\\IPC$", target.ip())
+        .encode_utf16()
+        .flat_map(|s| s.to_le_bytes())
+        .collect::<Vec<u8>>();
+    let mut tree_connect = vec![];
+    tree_connect.extend_from_slice(&9u16.to_le_bytes()); // StructureSize
+    tree_connect.extend_from_slice(&0u16.to_le_bytes()); // Flags
+    tree_connect.extend_from_slice(&((SMB2_HEADER_LEN + 8) as u16).to_le_bytes()); // PathOffset
+    tree_connect.extend_from_slice(&(path.len() as u16).to_le_bytes());
+    tree_connect.extend_from_slice(&path);
+    let (ipc_status, _, _) = smb2_request(
+        conversation,
+        SMB2_TREE_CONNECT,
+        3,
+        session_id,
+        &tree_connect,
+    )?;
+
+    Ok(Some(format!(
+        "Session without credentials accepted{} (SMB {}.{}.{}), IPC$ {}",
+        if session_flags & SMB2_SESSION_FLAG_IS_NULL != 0 {
+            " as null session"
+        } else if session_flags & SMB2_SESSION_FLAG_IS_GUEST != 0 {
+            " as guest"
+        } else {
+            ""
+        },
+        dialect >> 8,
+        (dialect >> 4) & 0xF,
+        dialect & 0xF,
+        if ipc_status == STATUS_SUCCESS {
+            "is reachable".to_owned()
+        } else {
+            format!("refused with status {:#010x}", ipc_status)
+        }
+    )))
+}
+
+fn build_session_setup(security_buffer: &[u8]) -> Vec<u8> {
+    let mut session_setup = vec![];
+    session_setup.extend_from_slice(&25u16.to_le_bytes()); // StructureSize
+    session_setup.push(0x00); // Flags
+    session_setup.push(0x01); // SecurityMode: signing enabled
+    session_setup.extend_from_slice(&0u32.to_le_bytes()); // Capabilities
+    session_setup.extend_from_slice(&0u32.to_le_bytes()); // Channel
+    session_setup.extend_from_slice(&((SMB2_HEADER_LEN + 24) as u16).to_le_bytes()); // SecurityBufferOffset
+    session_setup.extend_from_slice(&(security_buffer.len() as u16).to_le_bytes());
+    session_setup.extend_from_slice(&0u64.to_le_bytes()); // PreviousSessionId
+    session_setup.extend_from_slice(security_buffer);
+    session_setup
+}
+
+// => (status, session id, response body)
+fn smb2_request(
+    conversation: &mut Conversation,
+    command: u16,
+    message_id: u64,
+    session_id: u64,
+    body: &[u8],
+) -> anyhow::Result<(u32, u64, Vec<u8>)> {
+    let mut message = vec![];
+    message.extend_from_slice(&SMB2_PROTOCOL_ID);
+    message.extend_from_slice(&(SMB2_HEADER_LEN as u16).to_le_bytes()); // StructureSize
+    message.extend_from_slice(&0u16.to_le_bytes()); // CreditCharge
+    message.extend_from_slice(&0u32.to_le_bytes()); // Status
+    message.extend_from_slice(&command.to_le_bytes());
+    message.extend_from_slice(&1u16.to_le_bytes()); // CreditRequest
+    message.extend_from_slice(&0u32.to_le_bytes()); // Flags
+    message.extend_from_slice(&0u32.to_le_bytes()); // NextCommand
+    message.extend_from_slice(&message_id.to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes()); // Reserved
+    message.extend_from_slice(&0u32.to_le_bytes()); // TreeId
+    message.extend_from_slice(&session_id.to_le_bytes());
+    message.extend_from_slice(&[0; 16]); // Signature
+    message.extend_from_slice(body);
+    // Direct TCP transport: zero byte + 24 bit length
+    let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+    frame.extend_from_slice(&message);
+    conversation.send(&frame)?;
+
+    let reply = conversation.receive(|s| {
+        s.len() >= 4 && s.len() >= 4 + u32::from_be_bytes([0, s[1], s[2], s[3]]) as usize
+    })?;
+    let header = reply
+        .get(4..4 + SMB2_HEADER_LEN)
+        .filter(|s| s.starts_with(&SMB2_PROTOCOL_ID))
+        .ok_or_else(|| anyhow!("Not an SMB2 answer (SMB1 only servers aren't supported)"))?;
+    let status = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    let session_id = u64::from_le_bytes(header[40..48].try_into().unwrap());
+    Ok((status, session_id, reply[4 + SMB2_HEADER_LEN..].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{SocketAddr, TcpStream},
+    };
+
+    use crate::utils::{
+        ip::{BannerGrab, FuzzingResult},
+        test_helpers::serve,
+    };
+
+    use super::*;
+
+    fn test_config() -> ScanIpPortsConfig {
+        ScanIpPortsConfig {
+            connection_timeout_ms: 1000,
+            should_banner_grab: false,
+            should_fuzz: false,
+            should_check_access: true,
+            read_write_timeout_ms: 1000,
+            exclusions: Default::default(),
+            source: Default::default(),
+            proxy: None,
+        }
+    }
+
+    fn read_until(stream: &mut TcpStream, is_complete: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+        let mut data = vec![];
+        let mut buffer = [0; 1024];
+        while !is_complete(&data) {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(bytes_read) => data.extend_from_slice(&buffer[..bytes_read]),
+            }
+        }
+        data
+    }
+
+    fn read_line(stream: &mut TcpStream) -> String {
+        String::from_utf8_lossy(&read_until(stream, |s| s.ends_with(b"\n"))).into_owned()
+    }
+
+    fn ftp_server(pass_reply: &'static str) -> SocketAddr {
+        serve(move |mut stream| {
+            stream.write_all(b"220 (vsFTPd 3.0.5)\r\n").unwrap();
+            assert_eq!(read_line(&mut stream), "USER anonymous\r\n");
+            stream
+                .write_all(b"331 Please specify the password.\r\n")
+                .unwrap();
+            assert!(read_line(&mut stream).starts_with("PASS "));
+            stream.write_all(pass_reply.as_bytes()).unwrap();
+            read_line(&mut stream);
+        })
+    }
+
+    #[test]
+    fn anonymous_ftp() {
+        let target = ftp_server("230-Welcome\r\n230 Login successful.\r\n");
+        let finding = AccessCheck::AnonymousFtp
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::AnonymousFtp);
+        assert!(finding.summary.contains("230-Welcome"));
+        assert!(bytes_to_utf8(&finding.evidence).contains("Login successful"));
+
+        let target = ftp_server("530 Login incorrect.\r\n");
+        let result = AccessCheck::AnonymousFtp.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    fn line_server(request: &'static str, reply: &'static str) -> SocketAddr {
+        serve(move |mut stream| {
+            assert_eq!(read_line(&mut stream), request);
+            stream.write_all(reply.as_bytes()).unwrap();
+        })
+    }
+
+    #[test]
+    fn redis_without_auth() {
+        let target = line_server("PING\r\n", "+PONG\r\n");
+        let finding = AccessCheck::RedisWithoutAuth
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::RedisWithoutAuth);
+        assert_eq!(finding.evidence, b"+PONG\r\n");
+
+        let target = line_server("PING\r\n", "-NOAUTH Authentication required.\r\n");
+        let result = AccessCheck::RedisWithoutAuth.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn memcached_without_auth() {
+        let target = line_server(
+            "stats\r\n",
+            "STAT pid 1\r\nSTAT version 1.6.21\r\nSTAT curr_items 0\r\nEND\r\n",
+        );
+        let finding = AccessCheck::MemcachedWithoutAuth
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::MemcachedWithoutAuth);
+        assert!(finding.summary.contains("1.6.21"));
+
+        let target = line_server("stats\r\n", "ERROR\r\n");
+        let result = AccessCheck::MemcachedWithoutAuth.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    fn http_server(status_line: &'static str, body: &'static str) -> SocketAddr {
+        serve(move |mut stream| {
+            let request = read_until(&mut stream, |s| s.ends_with(b"\r\n\r\n"));
+            assert!(request.starts_with(b"GET / HTTP/1.1\r\n"));
+            let response = format!(
+                "{}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        })
+    }
+
+    #[test]
+    fn http_directory_listing() {
+        let target = http_server(
+            "HTTP/1.1 200 OK",
+            "<html><head><title>Index of /</title></head><body><a href=\"backup.zip\">backup.zip</a></body></html>",
+        );
+        let finding = AccessCheck::HttpDirectoryListing
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::HttpDirectoryListing);
+        assert!(bytes_to_utf8(&finding.evidence).contains("backup.zip"));
+
+        let target = http_server("HTTP/1.1 200 OK", "<html><body>It works!</body></html>");
+        let result = AccessCheck::HttpDirectoryListing.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+
+        let target = http_server("HTTP/1.1 403 Forbidden", "");
+        let result = AccessCheck::HttpDirectoryListing.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn elasticsearch_without_auth() {
+        let target = http_server(
+            "HTTP/1.1 200 OK",
+            "{\"name\":\"node-1\",\"cluster_name\":\"prod-logs\",\"version\":{\"number\":\"8.11.1\"}}",
+        );
+        let finding = AccessCheck::ElasticsearchWithoutAuth
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::ElasticsearchWithoutAuth);
+        assert!(finding.summary.contains("\"prod-logs\""));
+        assert!(finding.summary.contains("8.11.1"));
+
+        let target = http_server("HTTP/1.1 401 Unauthorized", "");
+        let result = AccessCheck::ElasticsearchWithoutAuth.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+
+        // Some other web server on the port
+        let target = http_server("HTTP/1.1 200 OK", "<html><body>It works!</body></html>");
+        let result = AccessCheck::ElasticsearchWithoutAuth.run(target, &test_config());
+        assert!(result.is_err());
+    }
+
+    fn bson_document(elements: &[u8]) -> Vec<u8> {
+        let mut document = ((elements.len() + 5) as i32).to_le_bytes().to_vec();
+        document.extend_from_slice(elements);
+        document.push(0x00);
+        document
+    }
+
+    fn bson_string(name: &str, value: &str) -> Vec<u8> {
+        let mut element = vec![0x02];
+        element.extend_from_slice(name.as_bytes());
+        element.push(0x00);
+        element.extend_from_slice(&((value.len() + 1) as i32).to_le_bytes());
+        element.extend_from_slice(value.as_bytes());
+        element.push(0x00);
+        element
+    }
+
+    fn bson_double(name: &str, value: f64) -> Vec<u8> {
+        let mut element = vec![0x01];
+        element.extend_from_slice(name.as_bytes());
+        element.push(0x00);
+        element.extend_from_slice(&value.to_le_bytes());
+        element
+    }
+
+    fn mongodb_server(reply_document: Vec<u8>) -> SocketAddr {
+        serve(move |mut stream| {
+            let request = read_until(&mut stream, |s| {
+                s.len() >= 4 && s.len() >= i32::from_le_bytes(s[..4].try_into().unwrap()) as usize
+            });
+            assert_eq!(&request[12..16], &MONGODB_OP_MSG.to_le_bytes());
+            assert!(bytes_to_utf8(&request).contains("listDatabases"));
+
+            let mut reply = vec![];
+            reply.extend_from_slice(&1i32.to_le_bytes()); // requestID
+            reply.extend_from_slice(&request[4..8]); // responseTo
+            reply.extend_from_slice(&MONGODB_OP_MSG.to_le_bytes());
+            reply.extend_from_slice(&0u32.to_le_bytes()); // flagBits
+            reply.push(0x00); // section kind: body
+            reply.extend_from_slice(&reply_document);
+            let reply_len = (reply.len() + 4) as i32;
+            reply.splice(0..0, reply_len.to_le_bytes());
+            stream.write_all(&reply).unwrap();
+        })
+    }
+
+    #[test]
+    fn mongodb_without_auth() {
+        let mut databases = vec![0x04];
+        databases.extend_from_slice(b"databases\0");
+        let mut first_database = vec![0x03];
+        first_database.extend_from_slice(b"0\0");
+        first_database.extend(bson_document(&bson_string("name", "admin")));
+        let mut second_database = vec![0x03];
+        second_database.extend_from_slice(b"1\0");
+        second_database.extend(bson_document(&bson_string("name", "customers")));
+        databases.extend(bson_document(&[first_database, second_database].concat()));
+        let target = mongodb_server(bson_document(&[databases, bson_double("ok", 1.0)].concat()));
+        let finding = AccessCheck::MongoDbWithoutAuth
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::MongoDbWithoutAuth);
+        assert!(finding.summary.contains("admin, customers"));
+
+        let target = mongodb_server(bson_document(
+            &[
+                bson_double("ok", 0.0),
+                bson_string("errmsg", "command listDatabases requires authentication"),
+            ]
+            .concat(),
+        ));
+        let result = AccessCheck::MongoDbWithoutAuth.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn malformed_bson_lengths() {
+        let mut negative_string = bson_string("errmsg", "x");
+        negative_string[8..12].copy_from_slice(&(-1i32).to_le_bytes());
+        assert!(read_bson_fields(&bson_document(&negative_string)).is_none());
+
+        let mut huge_binary = vec![0x05];
+        huge_binary.extend_from_slice(b"data\0");
+        huge_binary.extend_from_slice(&i32::MAX.to_le_bytes());
+        huge_binary.push(0x00);
+        assert!(read_bson_fields(&bson_document(&huge_binary)).is_none());
+
+        assert!(read_bson_fields(&(-5i32).to_le_bytes()).is_none());
+    }
+
+    const STATUS_ACCESS_DENIED: u32 = 0xC000_0022;
+    const STATUS_LOGON_FAILURE: u32 = 0xC000_006D;
+
+    // => (command, message id, session id, body)
+    fn read_smb2_request(stream: &mut TcpStream) -> (u16, u64, u64, Vec<u8>) {
+        let frame = read_until(stream, |s| {
+            s.len() >= 4 && s.len() >= 4 + u32::from_be_bytes([0, s[1], s[2], s[3]]) as usize
+        });
+        let message = &frame[4..];
+        assert!(message.starts_with(&SMB2_PROTOCOL_ID));
+        (
+            u16::from_le_bytes(message[12..14].try_into().unwrap()),
+            u64::from_le_bytes(message[24..32].try_into().unwrap()),
+            u64::from_le_bytes(message[40..48].try_into().unwrap()),
+            message[SMB2_HEADER_LEN..].to_vec(),
+        )
+    }
+
+    fn write_smb2_response(
+        stream: &mut TcpStream,
+        command: u16,
+        message_id: u64,
+        status: u32,
+        session_id: u64,
+        body: &[u8],
+    ) {
+        let mut message = vec![];
+        message.extend_from_slice(&SMB2_PROTOCOL_ID);
+        message.extend_from_slice(&(SMB2_HEADER_LEN as u16).to_le_bytes());
+        message.extend_from_slice(&0u16.to_le_bytes());
+        message.extend_from_slice(&status.to_le_bytes());
+        message.extend_from_slice(&command.to_le_bytes());
+        message.extend_from_slice(&1u16.to_le_bytes());
+        message.extend_from_slice(&1u32.to_le_bytes()); // Flags: response
+        message.extend_from_slice(&0u32.to_le_bytes());
+        message.extend_from_slice(&message_id.to_le_bytes());
+        message.extend_from_slice(&0u32.to_le_bytes());
+        message.extend_from_slice(&0u32.to_le_bytes());
+        message.extend_from_slice(&session_id.to_le_bytes());
+        message.extend_from_slice(&[0; 16]);
+        message.extend_from_slice(body);
+        let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(&message);
+        stream.write_all(&frame).unwrap();
+    }
+
+    // NTLMSSP message carried by a session setup request
+    fn ntlm_message(session_setup: &[u8]) -> Vec<u8> {
+        let offset = u16::from_le_bytes(session_setup[12..14].try_into().unwrap()) as usize;
+        let len = u16::from_le_bytes(session_setup[14..16].try_into().unwrap()) as usize;
+        let ntlm = session_setup[offset - SMB2_HEADER_LEN..offset - SMB2_HEADER_LEN + len].to_vec();
+        assert!(ntlm.starts_with(b"NTLMSSP\0"));
+        ntlm
+    }
+
+    fn session_setup_response(session_flags: u16) -> Vec<u8> {
+        let mut body = vec![];
+        body.extend_from_slice(&9u16.to_le_bytes()); // StructureSize
+        body.extend_from_slice(&session_flags.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes()); // SecurityBufferOffset
+        body.extend_from_slice(&0u16.to_le_bytes()); // SecurityBufferLength
+        body
+    }
+
+    // tree_connect_status None => the session setup gets refused
+    fn smb_server(session_flags: u16, tree_connect_status: Option<u32>) -> SocketAddr {
+        const SESSION_ID: u64 = 0x1122_3344_5566_7788;
+        serve(move |mut stream| {
+            let (command, message_id, _, body) = read_smb2_request(&mut stream);
+            assert_eq!(command, SMB2_NEGOTIATE);
+            let dialect_count = u16::from_le_bytes(body[2..4].try_into().unwrap()) as usize;
+            let dialects = body[36..36 + dialect_count * 2]
+                .chunks(2)
+                .map(|s| u16::from_le_bytes([s[0], s[1]]))
+                .collect::<Vec<u16>>();
+            assert!(dialects.contains(&0x0302));
+            let mut negotiate_response = vec![];
+            negotiate_response.extend_from_slice(&65u16.to_le_bytes()); // StructureSize
+            negotiate_response.extend_from_slice(&1u16.to_le_bytes()); // SecurityMode
+            negotiate_response.extend_from_slice(&0x0302u16.to_le_bytes()); // DialectRevision
+            write_smb2_response(
+                &mut stream,
+                command,
+                message_id,
+                STATUS_SUCCESS,
+                0,
+                &negotiate_response,
+            );
+
+            let (command, message_id, _, body) = read_smb2_request(&mut stream);
+            assert_eq!(command, SMB2_SESSION_SETUP);
+            let ntlm = ntlm_message(&body);
+            assert_eq!(u32::from_le_bytes(ntlm[8..12].try_into().unwrap()), 1);
+            write_smb2_response(
+                &mut stream,
+                command,
+                message_id,
+                STATUS_MORE_PROCESSING_REQUIRED,
+                SESSION_ID,
+                &session_setup_response(0),
+            );
+
+            let (command, message_id, session_id, body) = read_smb2_request(&mut stream);
+            assert_eq!(command, SMB2_SESSION_SETUP);
+            assert_eq!(session_id, SESSION_ID);
+            let ntlm = ntlm_message(&body);
+            assert_eq!(u32::from_le_bytes(ntlm[8..12].try_into().unwrap()), 3);
+            assert_eq!(&ntlm[36..38], &[0, 0]); // empty UserName
+            let flags = u32::from_le_bytes(ntlm[60..64].try_into().unwrap());
+            assert_ne!(flags & NTLMSSP_NEGOTIATE_ANONYMOUS, 0);
+            let Some(tree_connect_status) = tree_connect_status else {
+                write_smb2_response(
+                    &mut stream,
+                    command,
+                    message_id,
+                    STATUS_LOGON_FAILURE,
+                    SESSION_ID,
+                    &session_setup_response(0),
+                );
+                return;
+            };
+            write_smb2_response(
+                &mut stream,
+                command,
+                message_id,
+                STATUS_SUCCESS,
+                SESSION_ID,
+                &session_setup_response(session_flags),
+            );
+
+            let (command, message_id, session_id, body) = read_smb2_request(&mut stream);
+            assert_eq!(command, SMB2_TREE_CONNECT);
+            assert_eq!(session_id, SESSION_ID);
+            let path = String::from_utf16(
+                &body[8..]
+                    .chunks(2)
+                    .map(|s| u16::from_le_bytes([s[0], s[1]]))
+                    .collect::<Vec<u16>>(),
+            )
+            .unwrap();
+            assert_eq!(path, "\\\\127.0.0.1\\IPC$");
+            let mut tree_connect_response = vec![];
+            tree_connect_response.extend_from_slice(&16u16.to_le_bytes()); // StructureSize
+            tree_connect_response.extend_from_slice(&[0; 14]);
+            write_smb2_response(
+                &mut stream,
+                command,
+                message_id,
+                tree_connect_status,
+                SESSION_ID,
+                &tree_connect_response,
+            );
+        })
+    }
+
+    #[test]
+    fn smb_null_session() {
+        let target = smb_server(SMB2_SESSION_FLAG_IS_NULL, Some(STATUS_SUCCESS));
+        let finding = AccessCheck::SmbNullSession
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert_eq!(finding.check, AccessCheck::SmbNullSession);
+        assert_eq!(
+            finding.summary,
+            "Session without credentials accepted as null session (SMB 3.0.2), IPC$ is reachable"
+        );
+
+        let target = smb_server(SMB2_SESSION_FLAG_IS_GUEST, Some(STATUS_ACCESS_DENIED));
+        let finding = AccessCheck::SmbNullSession
+            .run(target, &test_config())
+            .unwrap()
+            .unwrap();
+        assert!(finding.summary.contains(" as guest "));
+        assert!(finding
+            .summary
+            .contains("IPC$ refused with status 0xc0000022"));
+
+        let target = smb_server(0, None);
+        let result = AccessCheck::SmbNullSession.run(target, &test_config());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn smb1_only_server() {
+        let target = serve(|mut stream| {
+            read_smb2_request(&mut stream);
+            let mut frame = 40u32.to_be_bytes().to_vec();
+            frame.extend_from_slice(&[0xFF, b'S', b'M', b'B']);
+            frame.extend_from_slice(&[0; 36]);
+            stream.write_all(&frame).unwrap();
+        });
+        let err = AccessCheck::SmbNullSession
+            .run(target, &test_config())
+            .unwrap_err();
+        assert!(err.to_string().contains("SMB1"));
+    }
+
+    fn port_info(service_name: &str, banner: Option<&str>, fuzzing_results: &[&str]) -> PortInfo {
+        PortInfo::new(
+            12345,
+            banner.map(|s| BannerGrab {
+                result: s.to_owned(),
+                result_raw: s.as_bytes().to_vec(),
+            }),
+            (!fuzzing_results.is_empty()).then(|| {
+                fuzzing_results
+                    .iter()
+                    .map(|s| FuzzingResult {
+                        command: "HELP\r\n".to_owned(),
+                        result: s.to_string(),
+                        result_raw: s.as_bytes().to_vec(),
+                    })
+                    .collect()
+            }),
+            service_name.to_owned(),
+            None,
+        )
+    }
+
+    #[test]
+    fn access_check_for_port() {
+        let by_service = [
+            ("FTP", AccessCheck::AnonymousFtp),
+            ("Redis", AccessCheck::RedisWithoutAuth),
+            ("MongoDB", AccessCheck::MongoDbWithoutAuth),
+            ("Elasticsearch", AccessCheck::ElasticsearchWithoutAuth),
+            ("Memcached", AccessCheck::MemcachedWithoutAuth),
+            ("Microsoft DS SMB", AccessCheck::SmbNullSession),
+            ("HTTP", AccessCheck::HttpDirectoryListing),
+            ("HTTP Proxy", AccessCheck::HttpDirectoryListing),
+        ];
+        for (service_name, check) in by_service {
+            assert_eq!(
+                AccessCheck::for_port(&port_info(service_name, None, &[])),
+                Some(check)
+            );
+        }
+        // The identified service wins over whatever the port answered
+        assert_eq!(
+            AccessCheck::for_port(&port_info("Redis", Some("HTTP/1.1 400 Bad Request"), &[])),
+            Some(AccessCheck::RedisWithoutAuth)
+        );
+
+        let by_answer = [
+            ("220 ProFTPD Server ready.", AccessCheck::AnonymousFtp),
+            (
+                "-NOAUTH Authentication required.",
+                AccessCheck::RedisWithoutAuth,
+            ),
+            ("-ERR unknown command `HELP`", AccessCheck::RedisWithoutAuth),
+            ("VERSION 1.6.21", AccessCheck::MemcachedWithoutAuth),
+            ("HTTP/1.0 404 Not Found", AccessCheck::HttpDirectoryListing),
+            (
+                "<!DOCTYPE html><html></html>",
+                AccessCheck::HttpDirectoryListing,
+            ),
+        ];
+        for (answer, check) in by_answer {
+            assert_eq!(
+                AccessCheck::for_port(&port_info("Unknown", Some(answer), &[])),
+                Some(check)
+            );
+            assert_eq!(
+                AccessCheck::for_port(&port_info("Unknown", None, &["", answer])),
+                Some(check)
+            );
+        }
+
+        // SMTP greets with 220 as well
+        assert_eq!(
+            AccessCheck::for_port(&port_info(
+                "Unknown",
+                Some("220 mail.example.com ESMTP"),
+                &[]
+            )),
+            None
+        );
+        assert_eq!(
+            AccessCheck::for_port(&port_info("SSH", Some("SSH-2.0-OpenSSH_9.6"), &[])),
+            None
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use super::constants::LINE_ENDING;
+
+const HEX_DUMP_BYTES_PER_LINE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BytesEncoding {
+    Utf8,
+    Latin1,
+    HexDump,
+}
+impl BytesEncoding {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BytesEncoding::Utf8 => "UTF-8",
+            BytesEncoding::Latin1 => "Latin-1",
+            BytesEncoding::HexDump => "Hex + ASCII",
+        }
+    }
+}
+
+pub fn bytes_to_string(buffer: &[u8], encoding: BytesEncoding) -> String {
+    match encoding {
+        BytesEncoding::Utf8 => bytes_to_utf8(buffer),
+        BytesEncoding::Latin1 => bytes_to_latin1(buffer),
+        BytesEncoding::HexDump => bytes_to_hex_dump(buffer),
+    }
+}
+
+// Invalid sequences get replaced with U+FFFD, so binary protocols should rather be viewed using hex dump
+pub fn bytes_to_utf8(buffer: &[u8]) -> String {
+    String::from_utf8_lossy(buffer).into_owned()
+}
+
+// Every byte maps 1:1 to unicode code point of the same value, so this one never fails
+pub fn bytes_to_latin1(buffer: &[u8]) -> String {
+    buffer.iter().map(|s| char::from(*s)).collect()
+}
+
+// Same layout as `hexdump -C` => offset | 16 bytes in hex | printable ascii
+pub fn bytes_to_hex_dump(buffer: &[u8]) -> String {
+    let mut dump = String::new();
+    for (line_index, line) in buffer.chunks(HEX_DUMP_BYTES_PER_LINE).enumerate() {
+        _ = write!(dump, "{:08x}  ", line_index * HEX_DUMP_BYTES_PER_LINE);
+        for i in 0..HEX_DUMP_BYTES_PER_LINE {
+            match line.get(i) {
+                Some(byte) => _ = write!(dump, "{:02x} ", byte),
+                None => dump.push_str("   "),
+            }
+            if i == HEX_DUMP_BYTES_PER_LINE / 2 - 1 {
+                dump.push(' ');
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(line.iter().map(|s| {
+            if s.is_ascii_graphic() || *s == b' ' {
+                char::from(*s)
+            } else {
+                '.'
+            }
+        }));
+        dump.push('|');
+        dump.push_str(LINE_ENDING);
+    }
+    _ = write!(dump, "{:08x}", buffer.len());
+
+    dump
+}
+
+// Standard alphabet with padding (RFC 4648), e.g. for HTTP basic auth
+pub fn bytes_to_base64(buffer: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(buffer.len().div_ceil(3) * 4);
+    for chunk in buffer.chunks(3) {
+        let value = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, s)| acc | (*s as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[(value >> (18 - i * 6)) as usize & 0x3f],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
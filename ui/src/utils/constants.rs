@@ -25,6 +25,8 @@ pub const DEFAULT_SPACER: f32 = 5.0;
 pub const ACTION_SPACER: f32 = 10.0;
 
 pub const TRASH_ICON: &str = "🗑";
+pub const RAW_SOCKETS_WITH_PROXY_HINT: &str =
+    "Needs raw sockets, which can't go through the configured proxy.";
 
 pub const MOST_COMMON_PORTS: [Port; 7] = [13, 22, 23, 80, 443, 8080, 8443];
 
@@ -35,7 +37,7 @@ pub const MOST_COMMON_PORTS: [Port; 7] = [13, 22, 23, 80, 443, 8080, 8443];
 // https://help.sap.com/docs/Security/575a9f0e56f34c6e8138439eefc32b16/616a3c0b1cc748238de9c0341b15c63c.html
 // https://www.speedguide.net/ports_sg.php?page=1&sort=&category=&seek=
 // https://nullsec.us/top-1-000-tcp-and-udp-ports-nmap-default/ <--- make sure all top 1000 are included here
-pub const ALL_COMMON_PORTS_LENGHT: usize = 420;
+pub const ALL_COMMON_PORTS_LENGHT: usize = 421;
 // Port , Protocol , Usefull info
 pub const ALL_COMMON_PORTS: [(Port, &str, &str); ALL_COMMON_PORTS_LENGHT] = [
     // Well-known/System ports: 0 – 1023
@@ -434,6 +436,7 @@ pub const ALL_COMMON_PORTS: [(Port, &str, &str); ALL_COMMON_PORTS_LENGHT] = [
     (10116, "NetIQ", "NetIQ VoIP Assessor"),
     (10161, "SNMP-agents (encrypted)", "Simple network management protocol; agents communicate on this port"),
     (10162, "SNMP-trap (encrypted)", "Simple network management protocol; listens for asynchronous traps"),
+    (11211, "Memcached", "Distributed memory object caching system"),
     (11371, "OpenPGP", "OpenPGP HTTP Keyserver"),
     (12345, "NetBus", "Possibly NetBus remote administration tool (usage could be legit, or possibly as a Trojan horse)"),
     (13720, "NetBackup", "NetBackup request daemon"),
@@ -0,0 +1,419 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use anyhow::anyhow;
+
+pub type DnsRecordType = u16;
+pub const DNS_TYPE_A: DnsRecordType = 1;
+pub const DNS_TYPE_NS: DnsRecordType = 2;
+pub const DNS_TYPE_CNAME: DnsRecordType = 5;
+pub const DNS_TYPE_SOA: DnsRecordType = 6;
+pub const DNS_TYPE_PTR: DnsRecordType = 12;
+pub const DNS_TYPE_MX: DnsRecordType = 15;
+pub const DNS_TYPE_TXT: DnsRecordType = 16;
+pub const DNS_TYPE_AAAA: DnsRecordType = 28;
+pub const DNS_TYPE_SRV: DnsRecordType = 33;
+pub const DNS_TYPE_AXFR: DnsRecordType = 252;
+
+pub const DNS_CLASS_IN: u16 = 1;
+
+pub const DNS_HEADER_SIZE: usize = 12;
+
+pub const DNS_RCODE_NO_ERROR: u16 = 0;
+pub const DNS_RCODE_SERVER_FAILURE: u16 = 2;
+pub const DNS_RCODE_REFUSED: u16 = 5;
+const DNS_RCODE_MASK: u16 = 0x000f;
+const DNS_MAX_COMPRESSION_JUMPS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsQuestion {
+    pub name: String,
+    pub record_type: DnsRecordType,
+    pub class: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DnsRecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ptr(String),
+    Cname(String),
+    Ns(String),
+    Mx {
+        preference: u16,
+        exchange: String,
+    },
+    Txt(Vec<String>),
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    Soa {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    Other(Vec<u8>),
+}
+impl Display for DnsRecordData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DnsRecordData::A(s) => write!(f, "{}", s),
+            DnsRecordData::Aaaa(s) => write!(f, "{}", s),
+            DnsRecordData::Ptr(s) | DnsRecordData::Cname(s) | DnsRecordData::Ns(s) => {
+                write!(f, "{}", s)
+            }
+            DnsRecordData::Mx {
+                preference,
+                exchange,
+            } => write!(f, "{} {}", preference, exchange),
+            DnsRecordData::Txt(s) => write!(
+                f,
+                "{}",
+                s.iter()
+                    .map(|s| format!("\"{}\"", s))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            DnsRecordData::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            DnsRecordData::Soa {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            DnsRecordData::Other(s) => write!(f, "{} bytes of unparsed data", s.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: DnsRecordType,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: DnsRecordData,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub flags: u16,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+    pub authorities: Vec<DnsRecord>,
+    pub additionals: Vec<DnsRecord>,
+}
+impl DnsMessage {
+    pub fn parse(buffer: &[u8]) -> anyhow::Result<Self> {
+        if buffer.len() < DNS_HEADER_SIZE {
+            return Err(anyhow!("DNS message is too short ({} bytes)", buffer.len()));
+        }
+
+        let id = read_u16(buffer, 0)?;
+        let flags = read_u16(buffer, 2)?;
+        let question_count = read_u16(buffer, 4)?;
+        let answer_count = read_u16(buffer, 6)?;
+        let authority_count = read_u16(buffer, 8)?;
+        let additional_count = read_u16(buffer, 10)?;
+
+        let mut offset = DNS_HEADER_SIZE;
+        let mut questions = vec![];
+        for _ in 0..question_count {
+            let name = read_name(buffer, &mut offset)?;
+            let record_type = read_u16(buffer, offset)?;
+            let class = read_u16(buffer, offset + 2)?;
+            offset += 4;
+            questions.push(DnsQuestion {
+                name,
+                record_type,
+                class,
+            });
+        }
+
+        let answers = read_records(buffer, &mut offset, answer_count)?;
+        let authorities = read_records(buffer, &mut offset, authority_count)?;
+        let additionals = read_records(buffer, &mut offset, additional_count)?;
+
+        Ok(Self {
+            id,
+            flags,
+            questions,
+            answers,
+            authorities,
+            additionals,
+        })
+    }
+
+    pub fn is_response(&self) -> bool {
+        self.flags & 0x8000 != 0
+    }
+
+    pub fn response_code(&self) -> u16 {
+        self.flags & DNS_RCODE_MASK
+    }
+
+    pub fn all_records(&self) -> impl Iterator<Item = &DnsRecord> {
+        self.answers
+            .iter()
+            .chain(self.authorities.iter())
+            .chain(self.additionals.iter())
+    }
+}
+
+pub fn dns_record_type_name(record_type: DnsRecordType) -> String {
+    match record_type {
+        DNS_TYPE_A => "A".to_owned(),
+        DNS_TYPE_NS => "NS".to_owned(),
+        DNS_TYPE_CNAME => "CNAME".to_owned(),
+        DNS_TYPE_SOA => "SOA".to_owned(),
+        DNS_TYPE_PTR => "PTR".to_owned(),
+        DNS_TYPE_MX => "MX".to_owned(),
+        DNS_TYPE_TXT => "TXT".to_owned(),
+        DNS_TYPE_AAAA => "AAAA".to_owned(),
+        DNS_TYPE_SRV => "SRV".to_owned(),
+        DNS_TYPE_AXFR => "AXFR".to_owned(),
+        _ => format!("TYPE{}", record_type),
+    }
+}
+
+// 192.168.0.1 => 1.0.168.192.in-addr.arpa ; ipv6 => every nibble reversed + ip6.arpa
+pub fn reverse_lookup_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(s) => {
+            let octets = s.octets();
+            format!(
+                "{}.{}.{}.{}.in-addr.arpa",
+                octets[3], octets[2], octets[1], octets[0]
+            )
+        }
+        IpAddr::V6(s) => {
+            let mut name = s
+                .octets()
+                .iter()
+                .rev()
+                .map(|s| format!("{:x}.{:x}.", s & 0x0f, s >> 4))
+                .collect::<String>();
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+pub fn build_dns_query(
+    id: u16,
+    name: &str,
+    record_type: DnsRecordType,
+    class: u16,
+    recursion_desired: bool,
+) -> anyhow::Result<Vec<u8>> {
+    let mut query = Vec::with_capacity(DNS_HEADER_SIZE + name.len() + 6);
+    query.extend_from_slice(&id.to_be_bytes());
+    query.extend_from_slice(&(if recursion_desired { 0x0100u16 } else { 0 }).to_be_bytes());
+    query.extend_from_slice(&1u16.to_be_bytes()); // questions
+    query.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // answers, authorities, additionals
+    write_name(&mut query, name)?;
+    query.extend_from_slice(&record_type.to_be_bytes());
+    query.extend_from_slice(&class.to_be_bytes());
+
+    Ok(query)
+}
+
+fn write_name(buffer: &mut Vec<u8>, name: &str) -> anyhow::Result<()> {
+    for label in name
+        .trim_end_matches('.')
+        .split('.')
+        .filter(|s| !s.is_empty())
+    {
+        if label.len() > 63 {
+            return Err(anyhow!("DNS label \"{}\" is longer than 63 bytes", label));
+        }
+        buffer.push(label.len() as u8);
+        buffer.extend_from_slice(label.as_bytes());
+    }
+    buffer.push(0);
+
+    Ok(())
+}
+
+pub fn read_u16(buffer: &[u8], offset: usize) -> anyhow::Result<u16> {
+    buffer
+        .get(offset..offset + 2)
+        .map(|s| u16::from_be_bytes([s[0], s[1]]))
+        .ok_or_else(|| anyhow!("Unexpected end of DNS message at offset {}", offset))
+}
+
+pub fn read_u32(buffer: &[u8], offset: usize) -> anyhow::Result<u32> {
+    buffer
+        .get(offset..offset + 4)
+        .map(|s| u32::from_be_bytes([s[0], s[1], s[2], s[3]]))
+        .ok_or_else(|| anyhow!("Unexpected end of DNS message at offset {}", offset))
+}
+
+// Reads (possibly compressed) name and moves offset right behind it
+pub fn read_name(buffer: &[u8], offset: &mut usize) -> anyhow::Result<String> {
+    let mut labels: Vec<String> = vec![];
+    let mut position = *offset;
+    let mut jumps = 0;
+    let mut end_of_name = None;
+
+    loop {
+        let Some(&length) = buffer.get(position) else {
+            return Err(anyhow!("Unexpected end of DNS message while reading name"));
+        };
+        match length {
+            0 => {
+                end_of_name.get_or_insert(position + 1);
+                break;
+            }
+            s if s & 0xc0 == 0xc0 => {
+                let pointer = (read_u16(buffer, position)? & 0x3fff) as usize;
+                end_of_name.get_or_insert(position + 2);
+                jumps += 1;
+                if jumps > DNS_MAX_COMPRESSION_JUMPS {
+                    return Err(anyhow!("DNS name compression loop detected"));
+                }
+                position = pointer;
+            }
+            s if s & 0xc0 != 0 => {
+                return Err(anyhow!("Unsupported DNS label type {:#x}", s));
+            }
+            s => {
+                let label = buffer
+                    .get(position + 1..position + 1 + s as usize)
+                    .ok_or_else(|| anyhow!("Unexpected end of DNS message while reading label"))?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                position += 1 + s as usize;
+            }
+        }
+    }
+
+    *offset = end_of_name.unwrap(); // safe to unwrap, every way out of the loop sets it
+    Ok(labels.join("."))
+}
+
+fn read_records(buffer: &[u8], offset: &mut usize, count: u16) -> anyhow::Result<Vec<DnsRecord>> {
+    let mut records = vec![];
+    for _ in 0..count {
+        let name = read_name(buffer, offset)?;
+        let record_type = read_u16(buffer, *offset)?;
+        let class = read_u16(buffer, *offset + 2)?;
+        let ttl = read_u32(buffer, *offset + 4)?;
+        let data_length = read_u16(buffer, *offset + 8)? as usize;
+        let data_start = *offset + 10;
+        let data_end = data_start + data_length;
+        if data_end > buffer.len() {
+            return Err(anyhow!("Record data of \"{}\" overflows DNS message", name));
+        }
+
+        // Record data can be of some other protocol that reuses the format (NetBIOS), so fall back to raw bytes
+        let data = read_record_data(buffer, record_type, data_start, data_end)
+            .unwrap_or_else(|_| DnsRecordData::Other(buffer[data_start..data_end].to_vec()));
+        records.push(DnsRecord {
+            name,
+            record_type,
+            class,
+            ttl,
+            data,
+        });
+        *offset = data_end;
+    }
+
+    Ok(records)
+}
+
+fn read_record_data(
+    buffer: &[u8],
+    record_type: DnsRecordType,
+    data_start: usize,
+    data_end: usize,
+) -> anyhow::Result<DnsRecordData> {
+    let data = &buffer[data_start..data_end];
+    let mut offset = data_start;
+    let record_data = match record_type {
+        DNS_TYPE_A if data.len() == 4 => {
+            DnsRecordData::A(Ipv4Addr::new(data[0], data[1], data[2], data[3]))
+        }
+        DNS_TYPE_AAAA if data.len() == 16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(data);
+            DnsRecordData::Aaaa(Ipv6Addr::from(octets))
+        }
+        DNS_TYPE_PTR => DnsRecordData::Ptr(read_name(buffer, &mut offset)?),
+        DNS_TYPE_CNAME => DnsRecordData::Cname(read_name(buffer, &mut offset)?),
+        DNS_TYPE_NS => DnsRecordData::Ns(read_name(buffer, &mut offset)?),
+        DNS_TYPE_MX => {
+            let preference = read_u16(buffer, offset)?;
+            offset += 2;
+            DnsRecordData::Mx {
+                preference,
+                exchange: read_name(buffer, &mut offset)?,
+            }
+        }
+        DNS_TYPE_TXT => {
+            let mut texts = vec![];
+            let mut position = 0;
+            while position < data.len() {
+                let length = data[position] as usize;
+                let text = data
+                    .get(position + 1..position + 1 + length)
+                    .ok_or_else(|| anyhow!("TXT record is malformed"))?;
+                texts.push(String::from_utf8_lossy(text).into_owned());
+                position += 1 + length;
+            }
+            DnsRecordData::Txt(texts)
+        }
+        DNS_TYPE_SRV => {
+            let priority = read_u16(buffer, offset)?;
+            let weight = read_u16(buffer, offset + 2)?;
+            let port = read_u16(buffer, offset + 4)?;
+            offset += 6;
+            DnsRecordData::Srv {
+                priority,
+                weight,
+                port,
+                target: read_name(buffer, &mut offset)?,
+            }
+        }
+        DNS_TYPE_SOA => {
+            let mname = read_name(buffer, &mut offset)?;
+            let rname = read_name(buffer, &mut offset)?;
+            DnsRecordData::Soa {
+                mname,
+                rname,
+                serial: read_u32(buffer, offset)?,
+                refresh: read_u32(buffer, offset + 4)?,
+                retry: read_u32(buffer, offset + 8)?,
+                expire: read_u32(buffer, offset + 12)?,
+                minimum: read_u32(buffer, offset + 16)?,
+            }
+        }
+        _ => DnsRecordData::Other(data.to_vec()),
+    };
+    if offset > data_end {
+        return Err(anyhow!("Record data is longer than declared"));
+    }
+
+    Ok(record_data)
+}
@@ -0,0 +1,475 @@
+use std::{
+    io::{Read, Write},
+    net::{IpAddr, SocketAddr, TcpStream},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use crossbeam::channel::bounded;
+use rand::random;
+
+use super::{
+    dns::{
+        build_dns_query, reverse_lookup_name, DnsMessage, DnsRecordData, DnsRecordType,
+        DNS_CLASS_IN, DNS_RCODE_REFUSED, DNS_RCODE_SERVER_FAILURE, DNS_TYPE_A, DNS_TYPE_AAAA,
+        DNS_TYPE_PTR,
+    },
+    interfaces::SourceBinding,
+};
+
+pub const DNS_PORT: u16 = 53;
+pub const DEFAULT_DNS_TIMEOUT_MS: u64 = 2000;
+
+const DNS_FLAG_TRUNCATED: u16 = 0x0200;
+
+// No servers => use whatever the OS is configured with
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsResolverConfig {
+    pub servers: Vec<SocketAddr>,
+    pub timeout: Duration,
+    pub force_tcp: bool,
+    pub source: SourceBinding, // only applies to the configured servers, the OS resolver picks its own
+}
+impl DnsResolverConfig {
+    pub fn uses_system_resolver(&self) -> bool {
+        self.servers.is_empty()
+    }
+}
+
+// Anything other than A / AAAA / PTR can't be asked through the OS resolver api, so we need the servers themselves.
+// Only resolv.conf is supported for now (=> nothing on windows).
+pub fn get_system_dns_servers() -> Vec<SocketAddr> {
+    let Ok(resolv_conf) = std::fs::read_to_string("/etc/resolv.conf") else {
+        return vec![];
+    };
+    resolv_conf
+        .lines()
+        .filter_map(|s| s.trim().strip_prefix("nameserver"))
+        .filter_map(|s| s.split_whitespace().next())
+        // scoped ipv6 addresses (fe80::1%eth0) can't be parsed into SocketAddr
+        .filter_map(|s| IpAddr::from_str(s).ok())
+        .map(|s| SocketAddr::new(s, DNS_PORT))
+        .collect()
+}
+
+// "10.0.0.1", "10.0.0.1:5353", "::1" or "[::1]:5353"
+pub fn parse_dns_server(value: &str) -> Option<SocketAddr> {
+    let value = value.trim();
+    if let Ok(ip) = IpAddr::from_str(value) {
+        return Some(SocketAddr::new(ip, DNS_PORT));
+    }
+    SocketAddr::from_str(value).ok()
+}
+
+// Letters, digits, hyphens and underscores (seen a lot in internal zones) separated by dots
+pub fn is_valid_hostname(value: &str) -> bool {
+    let value = value.trim().trim_end_matches('.');
+    !value.is_empty()
+        && value.len() <= 253
+        && value.split('.').all(|s| {
+            !s.is_empty()
+                && s.len() <= 63
+                && !s.starts_with('-')
+                && s.chars()
+                    .all(|s| s.is_ascii_alphanumeric() || s == '-' || s == '_')
+        })
+}
+
+// Asks servers one by one, until someone gives a usable answer
+pub fn query_dns(
+    config: &DnsResolverConfig,
+    name: &str,
+    record_type: DnsRecordType,
+) -> anyhow::Result<DnsMessage> {
+    let mut last_err = anyhow!("No DNS servers configured");
+    for server in config.servers.iter() {
+        match query_dns_server(*server, config, name, record_type) {
+            Ok(s) => return Ok(s),
+            Err(e) => last_err = anyhow!("{} => {}", server, e),
+        }
+    }
+    Err(last_err)
+}
+
+fn query_dns_server(
+    server: SocketAddr,
+    config: &DnsResolverConfig,
+    name: &str,
+    record_type: DnsRecordType,
+) -> anyhow::Result<DnsMessage> {
+    let id = random::<u16>();
+    let query = build_dns_query(id, name, record_type, DNS_CLASS_IN, true)?;
+
+    let response = if config.force_tcp {
+        query_dns_server_tcp(server, config, &query, id)?
+    } else {
+        let response = query_dns_server_udp(server, config, &query, id)?;
+        // Answer didn't fit into the datagram => same question over tcp
+        if response.flags & DNS_FLAG_TRUNCATED != 0 {
+            query_dns_server_tcp(server, config, &query, id)?
+        } else {
+            response
+        }
+    };
+
+    match response.response_code() {
+        DNS_RCODE_SERVER_FAILURE => Err(anyhow!("Server failure")),
+        DNS_RCODE_REFUSED => Err(anyhow!("Query refused")),
+        _ => Ok(response),
+    }
+}
+
+fn query_dns_server_udp(
+    server: SocketAddr,
+    config: &DnsResolverConfig,
+    query: &[u8],
+    id: u16,
+) -> anyhow::Result<DnsMessage> {
+    let socket = config.source.bind_udp(server.ip())?;
+    socket.send_to(query, server)?;
+
+    // One timeout for the whole query, unrelated datagrams mustn't keep extending it
+    let deadline = Instant::now() + config.timeout;
+    let mut buffer = [0u8; 4096];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(anyhow!(
+                "No answer within {} ms",
+                config.timeout.as_millis()
+            ));
+        }
+        socket.set_read_timeout(Some(remaining))?;
+        let (bytes_read, responder) = socket.recv_from(&mut buffer)?;
+        if responder != server {
+            continue;
+        }
+        match DnsMessage::parse(&buffer[..bytes_read]) {
+            Ok(s) if s.id == id && s.is_response() => return Ok(s),
+            _ => continue,
+        }
+    }
+}
+
+fn query_dns_server_tcp(
+    server: SocketAddr,
+    config: &DnsResolverConfig,
+    query: &[u8],
+    id: u16,
+) -> anyhow::Result<DnsMessage> {
+    let mut stream = open_dns_tcp_stream(server, config)?;
+    write_dns_tcp_message(&mut stream, query)?;
+
+    let response = DnsMessage::parse(&read_dns_tcp_message(&mut stream)?)?;
+    if response.id != id {
+        return Err(anyhow!("DNS response id does not match the query"));
+    }
+    Ok(response)
+}
+
+pub fn open_dns_tcp_stream(
+    server: SocketAddr,
+    config: &DnsResolverConfig,
+) -> anyhow::Result<TcpStream> {
+    let stream = config.source.connect_tcp(server, config.timeout)?;
+    stream.set_read_timeout(Some(config.timeout))?;
+    stream.set_write_timeout(Some(config.timeout))?;
+    Ok(stream)
+}
+
+// Over tcp every message is prefixed with its length (RFC 1035, 4.2.2)
+pub fn write_dns_tcp_message(stream: &mut TcpStream, message: &[u8]) -> anyhow::Result<()> {
+    let mut framed_message = (message.len() as u16).to_be_bytes().to_vec();
+    framed_message.extend_from_slice(message);
+    stream.write_all(&framed_message)?;
+    Ok(())
+}
+
+pub fn read_dns_tcp_message(stream: &mut TcpStream) -> anyhow::Result<Vec<u8>> {
+    let mut length = [0u8; 2];
+    stream.read_exact(&mut length)?;
+    let mut message = vec![0u8; u16::from_be_bytes(length) as usize];
+    stream.read_exact(&mut message)?;
+    Ok(message)
+}
+
+pub fn lookup_ptr(config: &DnsResolverConfig, ip: IpAddr) -> anyhow::Result<Option<String>> {
+    if config.uses_system_resolver() {
+        return Ok(run_with_timeout(config.timeout, move || dns_lookup::lookup_addr(&ip))?.ok());
+    }
+
+    let response = query_dns(config, &reverse_lookup_name(ip), DNS_TYPE_PTR)?;
+    Ok(response.answers.iter().find_map(|s| match &s.data {
+        DnsRecordData::Ptr(name) => Some(name.trim_end_matches('.').to_owned()),
+        _ => None,
+    }))
+}
+
+// A + AAAA, fails only if neither of the queries got through
+pub fn lookup_host(config: &DnsResolverConfig, hostname: &str) -> anyhow::Result<Vec<IpAddr>> {
+    if config.uses_system_resolver() {
+        let hostname = hostname.to_owned();
+        return Ok(run_with_timeout(config.timeout, move || {
+            dns_lookup::lookup_host(&hostname)
+        })??);
+    }
+
+    let mut addresses = vec![];
+    let mut last_err = None;
+    for record_type in [DNS_TYPE_A, DNS_TYPE_AAAA] {
+        match query_dns(config, hostname, record_type) {
+            Ok(response) => {
+                for record in response.answers.iter() {
+                    let address = match record.data {
+                        DnsRecordData::A(s) => IpAddr::V4(s),
+                        DnsRecordData::Aaaa(s) => IpAddr::V6(s),
+                        _ => continue,
+                    };
+                    if !addresses.contains(&address) {
+                        addresses.push(address);
+                    }
+                }
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    match last_err {
+        Some(e) if addresses.is_empty() => Err(e),
+        _ => Ok(addresses),
+    }
+}
+
+// OS resolver can't be told to give up, so the lookup gets left behind on its own thread instead
+fn run_with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    lookup: impl FnOnce() -> T + Send + 'static,
+) -> anyhow::Result<T> {
+    let (sender, receiver) = bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.send(lookup());
+    });
+    receiver
+        .recv_timeout(timeout)
+        .map_err(|_| anyhow!("Lookup timed out after {} ms", timeout.as_millis()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, Ipv6Addr, TcpListener, UdpSocket},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    use crate::utils::dns::{DnsRecordType, DNS_HEADER_SIZE};
+
+    use super::*;
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(500);
+    const HOSTNAME: &str = "host.example.lan";
+    const HOST_IPV4: Ipv4Addr = Ipv4Addr::new(10, 0, 0, 1);
+    const HOST_IPV6: Ipv6Addr = Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1);
+
+    // Stand-in DNS server listening on the same port over UDP and TCP
+    struct StandInDnsServer {
+        address: SocketAddr,
+        tcp_queries: Arc<AtomicUsize>,
+    }
+    impl StandInDnsServer {
+        // `truncate_udp` => UDP answers are empty with the TC bit set, the records only come over TCP
+        fn start(truncate_udp: bool) -> Self {
+            let (udp_socket, tcp_listener) = bind_udp_and_tcp();
+            let address = udp_socket.local_addr().unwrap();
+            let tcp_queries = Arc::new(AtomicUsize::new(0));
+
+            thread::spawn(move || {
+                let mut buffer = [0u8; 512];
+                while let Ok((bytes_read, client)) = udp_socket.recv_from(&mut buffer) {
+                    let response = build_response(&buffer[..bytes_read], truncate_udp);
+                    udp_socket.send_to(&response, client).unwrap();
+                }
+            });
+            let thread_tcp_queries = Arc::clone(&tcp_queries);
+            thread::spawn(move || {
+                for mut stream in tcp_listener.incoming().flatten() {
+                    let query = read_dns_tcp_message(&mut stream).unwrap();
+                    thread_tcp_queries.fetch_add(1, Ordering::Relaxed);
+                    write_dns_tcp_message(&mut stream, &build_response(&query, false)).unwrap();
+                }
+            });
+
+            Self {
+                address,
+                tcp_queries,
+            }
+        }
+
+        fn config(&self, force_tcp: bool) -> DnsResolverConfig {
+            DnsResolverConfig {
+                servers: vec![self.address],
+                timeout: TEST_TIMEOUT,
+                force_tcp,
+                source: Default::default(),
+            }
+        }
+    }
+
+    // Free UDP port doesn't have to be free over TCP
+    fn bind_udp_and_tcp() -> (UdpSocket, TcpListener) {
+        loop {
+            let udp_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+            if let Ok(tcp_listener) = TcpListener::bind(udp_socket.local_addr().unwrap()) {
+                return (udp_socket, tcp_listener);
+            }
+        }
+    }
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut encoded = vec![];
+        for label in name.split('.') {
+            encoded.push(label.len() as u8);
+            encoded.extend_from_slice(label.as_bytes());
+        }
+        encoded.push(0);
+        encoded
+    }
+
+    fn build_response(query: &[u8], truncated: bool) -> Vec<u8> {
+        let question = DnsMessage::parse(query).unwrap().questions.remove(0);
+        let reverse_name = reverse_lookup_name(IpAddr::V4(HOST_IPV4));
+        let records: Vec<(DnsRecordType, Vec<u8>)> = match question.record_type {
+            DNS_TYPE_PTR if question.name == reverse_name => {
+                vec![(DNS_TYPE_PTR, encode_name(HOSTNAME))]
+            }
+            DNS_TYPE_A if question.name == HOSTNAME => {
+                vec![(DNS_TYPE_A, HOST_IPV4.octets().to_vec())]
+            }
+            DNS_TYPE_AAAA if question.name == HOSTNAME => {
+                vec![(DNS_TYPE_AAAA, HOST_IPV6.octets().to_vec())]
+            }
+            _ => vec![],
+        };
+        let records = if truncated { vec![] } else { records };
+
+        let mut response = query[..2].to_vec(); // id
+        let flags = 0x8180 | if truncated { DNS_FLAG_TRUNCATED } else { 0 };
+        response.extend_from_slice(&flags.to_be_bytes());
+        response.extend_from_slice(&1u16.to_be_bytes()); // questions
+        response.extend_from_slice(&(records.len() as u16).to_be_bytes());
+        response.extend_from_slice(&[0, 0, 0, 0]); // authorities, additionals
+        response.extend_from_slice(&query[DNS_HEADER_SIZE..]);
+        for (record_type, data) in records {
+            response.extend_from_slice(&[0xc0, DNS_HEADER_SIZE as u8]); // pointer to the question's name
+            response.extend_from_slice(&record_type.to_be_bytes());
+            response.extend_from_slice(&DNS_CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&60u32.to_be_bytes());
+            response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            response.extend_from_slice(&data);
+        }
+        response
+    }
+
+    #[test]
+    fn ptr_answer() {
+        let server = StandInDnsServer::start(false);
+        let config = server.config(false);
+        assert_eq!(
+            lookup_ptr(&config, IpAddr::V4(HOST_IPV4)).unwrap(),
+            Some(HOSTNAME.to_owned())
+        );
+        assert_eq!(
+            lookup_ptr(&config, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))).unwrap(),
+            None
+        );
+        assert_eq!(server.tcp_queries.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn a_and_aaaa_answers() {
+        let server = StandInDnsServer::start(false);
+        assert_eq!(
+            lookup_host(&server.config(false), HOSTNAME).unwrap(),
+            vec![IpAddr::V4(HOST_IPV4), IpAddr::V6(HOST_IPV6)]
+        );
+        assert!(lookup_host(&server.config(false), "unknown.example.lan")
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            lookup_host(&server.config(true), HOSTNAME).unwrap(),
+            vec![IpAddr::V4(HOST_IPV4), IpAddr::V6(HOST_IPV6)]
+        );
+        assert_eq!(server.tcp_queries.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn truncated_answer_is_retried_over_tcp() {
+        let server = StandInDnsServer::start(true);
+        let config = server.config(false);
+        assert_eq!(
+            lookup_ptr(&config, IpAddr::V4(HOST_IPV4)).unwrap(),
+            Some(HOSTNAME.to_owned())
+        );
+        assert_eq!(server.tcp_queries.load(Ordering::Relaxed), 1);
+
+        assert_eq!(
+            lookup_host(&config, HOSTNAME).unwrap(),
+            vec![IpAddr::V4(HOST_IPV4), IpAddr::V6(HOST_IPV6)]
+        );
+        assert_eq!(server.tcp_queries.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn silent_server_times_out() {
+        let silent_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let config = DnsResolverConfig {
+            servers: vec![silent_socket.local_addr().unwrap()],
+            timeout: TEST_TIMEOUT,
+            force_tcp: false,
+            source: Default::default(),
+        };
+
+        let started_at = Instant::now();
+        assert!(query_dns(&config, HOSTNAME, DNS_TYPE_A).is_err());
+        assert!(lookup_ptr(&config, IpAddr::V4(HOST_IPV4)).is_err());
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= TEST_TIMEOUT * 2);
+        assert!(elapsed < TEST_TIMEOUT * 6);
+    }
+
+    #[test]
+    fn unrelated_answers_do_not_extend_the_timeout() {
+        let (udp_socket, _) = bind_udp_and_tcp();
+        let address = udp_socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 512];
+            let (bytes_read, client) = udp_socket.recv_from(&mut buffer).unwrap();
+            let mut response = build_response(&buffer[..bytes_read], false);
+            // Answers to some other query, well within the timeout every time
+            response[0] ^= 0xff;
+            for _ in 0..20 {
+                if udp_socket.send_to(&response, client).is_err() {
+                    break;
+                }
+                thread::sleep(TEST_TIMEOUT / 5);
+            }
+        });
+        let config = DnsResolverConfig {
+            servers: vec![address],
+            timeout: TEST_TIMEOUT,
+            force_tcp: false,
+            source: Default::default(),
+        };
+
+        let started_at = Instant::now();
+        assert!(query_dns(&config, HOSTNAME, DNS_TYPE_A).is_err());
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= TEST_TIMEOUT);
+        assert!(elapsed < TEST_TIMEOUT * 2);
+    }
+}
@@ -0,0 +1,487 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::{IpAddr, SocketAddr},
+};
+
+use anyhow::anyhow;
+use crossbeam::channel::unbounded;
+use rand::random;
+
+use crate::app::{
+    network_topology::{HostnameSource, NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    dns::{
+        build_dns_query, dns_record_type_name, DnsMessage, DnsRecord, DnsRecordData, DnsRecordType,
+        DNS_CLASS_IN, DNS_RCODE_NO_ERROR, DNS_TYPE_A, DNS_TYPE_AAAA, DNS_TYPE_AXFR, DNS_TYPE_MX,
+        DNS_TYPE_NS, DNS_TYPE_SOA, DNS_TYPE_SRV, DNS_TYPE_TXT,
+    },
+    dns_resolver::{
+        get_system_dns_servers, lookup_host, open_dns_tcp_stream, query_dns, read_dns_tcp_message,
+        write_dns_tcp_message, DnsResolverConfig, DNS_PORT,
+    },
+};
+
+pub const DEFAULT_SUBDOMAIN_BRUTE_FORCE_CONCURRENCY: usize = 16;
+
+// Safety net against servers that keep on sending
+const ZONE_TRANSFER_MAX_MESSAGES: usize = 10_000;
+
+const ENUMERATED_RECORD_TYPES: [DnsRecordType; 6] = [
+    DNS_TYPE_A,
+    DNS_TYPE_AAAA,
+    DNS_TYPE_MX,
+    DNS_TYPE_NS,
+    DNS_TYPE_TXT,
+    DNS_TYPE_SOA,
+];
+
+// SRV records only exist under well known service names, so these are the ones worth asking for
+const COMMON_SRV_SERVICES: [&str; 18] = [
+    "_ldap._tcp",
+    "_kerberos._tcp",
+    "_kerberos._udp",
+    "_kpasswd._tcp",
+    "_gc._tcp",
+    "_sip._tcp",
+    "_sip._udp",
+    "_sips._tcp",
+    "_xmpp-client._tcp",
+    "_xmpp-server._tcp",
+    "_autodiscover._tcp",
+    "_caldav._tcp",
+    "_carddav._tcp",
+    "_imap._tcp",
+    "_imaps._tcp",
+    "_submission._tcp",
+    "_pop3s._tcp",
+    "_vlmcs._tcp",
+];
+
+pub const DEFAULT_SUBDOMAIN_WORDLIST: [&str; 64] = [
+    "www",
+    "mail",
+    "webmail",
+    "smtp",
+    "pop",
+    "imap",
+    "mx",
+    "ns",
+    "ns1",
+    "ns2",
+    "ns3",
+    "dns",
+    "ftp",
+    "sftp",
+    "vpn",
+    "remote",
+    "gateway",
+    "gw",
+    "fw",
+    "firewall",
+    "proxy",
+    "portal",
+    "intranet",
+    "extranet",
+    "internal",
+    "corp",
+    "ad",
+    "dc",
+    "ldap",
+    "sso",
+    "auth",
+    "login",
+    "id",
+    "owa",
+    "exchange",
+    "autodiscover",
+    "lync",
+    "sip",
+    "api",
+    "app",
+    "apps",
+    "dev",
+    "test",
+    "stage",
+    "staging",
+    "uat",
+    "qa",
+    "demo",
+    "beta",
+    "admin",
+    "cpanel",
+    "git",
+    "gitlab",
+    "jenkins",
+    "ci",
+    "jira",
+    "wiki",
+    "confluence",
+    "docs",
+    "files",
+    "backup",
+    "db",
+    "monitor",
+    "status",
+];
+
+pub struct DomainReconConfig {
+    pub domain: String,
+    pub enumerate_records: bool,
+    pub attempt_zone_transfer: bool,
+    pub brute_force_subdomains: bool,
+    pub wordlist: Vec<String>,
+    pub dns_resolver: DnsResolverConfig,
+}
+
+// name => addresses it points to, collected over all the steps
+type ResolvedNames = BTreeMap<String, BTreeSet<IpAddr>>;
+
+pub fn start_domain_recon(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    mut config: DomainReconConfig,
+) {
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info(format!(
+            "Initiating domain reconnaissance of {}.",
+            config.domain
+        )),
+    );
+
+    std::thread::spawn(move || {
+        if config.dns_resolver.uses_system_resolver() {
+            config.dns_resolver.servers = get_system_dns_servers();
+            if config.dns_resolver.servers.is_empty() {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(
+                        "Unable to determine system DNS servers, please set them in Project settings.".to_owned(),
+                    ),
+                );
+                return;
+            }
+        }
+
+        let mut resolved_names = ResolvedNames::new();
+        let mut name_servers = BTreeSet::new();
+
+        if config.enumerate_records || config.attempt_zone_transfer {
+            enumerate_records(
+                &status_info_ref,
+                &config,
+                &mut resolved_names,
+                &mut name_servers,
+            );
+        }
+
+        if config.attempt_zone_transfer {
+            for name_server in name_servers.iter() {
+                attempt_zone_transfer(&status_info_ref, &config, name_server, &mut resolved_names);
+            }
+        }
+
+        if config.brute_force_subdomains {
+            brute_force_subdomains(&status_info_ref, &config, &mut resolved_names);
+        }
+
+        let mut recorded_addresses = BTreeSet::new();
+        for (name, addresses) in resolved_names.iter() {
+            for address in addresses {
+                let (node_index, _) =
+                    NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, *address);
+                NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+                    node.add_hostname(name, HostnameSource::Dns);
+                });
+                recorded_addresses.insert(*address);
+            }
+        }
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Finished domain reconnaissance of {}. Found {} names pointing to {} addresses.",
+                config.domain,
+                resolved_names.len(),
+                recorded_addresses.len()
+            )),
+        );
+    });
+}
+
+fn enumerate_records(
+    status_info_ref: &StatusInfoRef,
+    config: &DomainReconConfig,
+    resolved_names: &mut ResolvedNames,
+    name_servers: &mut BTreeSet<String>,
+) {
+    let mut questions = ENUMERATED_RECORD_TYPES
+        .iter()
+        .map(|s| (config.domain.clone(), *s))
+        .collect::<Vec<(String, DnsRecordType)>>();
+    if config.enumerate_records {
+        questions.extend(
+            COMMON_SRV_SERVICES
+                .iter()
+                .map(|s| (format!("{}.{}", s, config.domain), DNS_TYPE_SRV)),
+        );
+    }
+
+    // Hosts that other records (MX, NS, SRV) point to, they get resolved afterwards
+    let mut referenced_hosts = BTreeSet::new();
+    for (name, record_type) in questions {
+        let response = match query_dns(&config.dns_resolver, &name, record_type) {
+            Ok(s) => s,
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    status_info_ref,
+                    StatusMessage::Warn(format!(
+                        "{} lookup of {} failed => {}",
+                        dns_record_type_name(record_type),
+                        name,
+                        e
+                    )),
+                );
+                continue;
+            }
+        };
+
+        for record in response.answers.iter() {
+            if config.enumerate_records {
+                log_dns_record(status_info_ref, record);
+            }
+            match &record.data {
+                DnsRecordData::Ns(s) => {
+                    name_servers.insert(normalize_name(s));
+                    referenced_hosts.insert(normalize_name(s));
+                }
+                DnsRecordData::Mx { exchange, .. } => {
+                    referenced_hosts.insert(normalize_name(exchange));
+                }
+                DnsRecordData::Srv { target, .. } => {
+                    referenced_hosts.insert(normalize_name(target));
+                }
+                _ => {}
+            }
+        }
+        add_address_records(&response.answers, resolved_names);
+    }
+
+    if !config.enumerate_records {
+        return;
+    }
+    for host in referenced_hosts {
+        if host.is_empty() || resolved_names.contains_key(&host) {
+            continue;
+        }
+        if let Ok(addresses) = lookup_host(&config.dns_resolver, &host) {
+            resolved_names.entry(host).or_default().extend(addresses);
+        }
+    }
+}
+
+fn attempt_zone_transfer(
+    status_info_ref: &StatusInfoRef,
+    config: &DomainReconConfig,
+    name_server: &str,
+    resolved_names: &mut ResolvedNames,
+) {
+    let name_server_addresses = match lookup_host(&config.dns_resolver, name_server) {
+        Ok(s) => s,
+        Err(e) => {
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Warn(format!(
+                    "Unable to resolve name server {} => {}",
+                    name_server, e
+                )),
+            );
+            return;
+        }
+    };
+
+    for address in name_server_addresses {
+        let server = SocketAddr::new(address, DNS_PORT);
+        match request_zone_transfer(&config.dns_resolver, server, &config.domain) {
+            Ok(records) => {
+                AppState::log_to_status_generic(
+                    status_info_ref,
+                    StatusMessage::Warn(format!(
+                        "Zone transfer of {} from {} ({}) succeeded, got {} records!",
+                        config.domain,
+                        name_server,
+                        address,
+                        records.len()
+                    )),
+                );
+                for record in records.iter() {
+                    log_dns_record(status_info_ref, record);
+                }
+                add_address_records(&records, resolved_names);
+                // Every server of the zone has the same data
+                return;
+            }
+            Err(e) => AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Info(format!(
+                    "Zone transfer of {} from {} ({}) failed => {}",
+                    config.domain, name_server, address, e
+                )),
+            ),
+        }
+    }
+}
+
+// AXFR over tcp, the zone is wrapped between two SOA records and may span multiple messages
+fn request_zone_transfer(
+    dns_resolver: &DnsResolverConfig,
+    server: SocketAddr,
+    domain: &str,
+) -> anyhow::Result<Vec<DnsRecord>> {
+    let id = random::<u16>();
+    let mut stream = open_dns_tcp_stream(server, dns_resolver)?;
+    write_dns_tcp_message(
+        &mut stream,
+        &build_dns_query(id, domain, DNS_TYPE_AXFR, DNS_CLASS_IN, false)?,
+    )?;
+
+    let mut records = vec![];
+    let mut soa_count = 0;
+    for _ in 0..ZONE_TRANSFER_MAX_MESSAGES {
+        let response = DnsMessage::parse(&read_dns_tcp_message(&mut stream)?)?;
+        if response.id != id {
+            return Err(anyhow!("DNS response id does not match the query"));
+        }
+        if response.response_code() != DNS_RCODE_NO_ERROR {
+            return Err(anyhow!(
+                "Server refused (response code {})",
+                response.response_code()
+            ));
+        }
+        if response.answers.is_empty() {
+            return Err(anyhow!("Server sent empty response"));
+        }
+
+        for record in response.answers {
+            if record.record_type == DNS_TYPE_SOA {
+                soa_count += 1;
+            }
+            records.push(record);
+        }
+        if soa_count >= 2 {
+            return Ok(records);
+        }
+    }
+
+    Err(anyhow!("Zone transfer did not finish"))
+}
+
+fn brute_force_subdomains(
+    status_info_ref: &StatusInfoRef,
+    config: &DomainReconConfig,
+    resolved_names: &mut ResolvedNames,
+) {
+    // Wildcard record => every made up name resolves, those addresses are meaningless
+    let wildcard_addresses = lookup_host(
+        &config.dns_resolver,
+        &format!("{:x}.{}", random::<u64>(), config.domain),
+    )
+    .unwrap_or_default();
+    if !wildcard_addresses.is_empty() {
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Warn(format!(
+                "{} has a wildcard record, ignoring subdomains pointing to {:?}",
+                config.domain, wildcard_addresses
+            )),
+        );
+    }
+
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "Brute forcing {} subdomains of {}.",
+            config.wordlist.len(),
+            config.domain
+        )),
+    );
+
+    let (words_sender, words_receiver) = unbounded::<String>();
+    let (results_sender, results_receiver) = unbounded::<(String, Vec<IpAddr>)>();
+    for word in config.wordlist.iter() {
+        let _ = words_sender.send(format!("{}.{}", word, config.domain));
+    }
+    drop(words_sender);
+
+    let wildcard_addresses = &wildcard_addresses;
+    std::thread::scope(|scope| {
+        for _ in 0..DEFAULT_SUBDOMAIN_BRUTE_FORCE_CONCURRENCY {
+            let words_receiver = words_receiver.clone();
+            let results_sender = results_sender.clone();
+            scope.spawn(move || {
+                for name in words_receiver.iter() {
+                    let Ok(addresses) = lookup_host(&config.dns_resolver, &name) else {
+                        continue;
+                    };
+                    let addresses = addresses
+                        .into_iter()
+                        .filter(|s| !wildcard_addresses.contains(s))
+                        .collect::<Vec<IpAddr>>();
+                    if !addresses.is_empty() {
+                        let _ = results_sender.send((name, addresses));
+                    }
+                }
+            });
+        }
+    });
+    drop(results_sender);
+
+    for (name, addresses) in results_receiver.iter() {
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!(
+                "Subdomain {} => {}",
+                name,
+                addresses
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        );
+        resolved_names.entry(name).or_default().extend(addresses);
+    }
+}
+
+fn add_address_records(records: &[DnsRecord], resolved_names: &mut ResolvedNames) {
+    for record in records {
+        let address = match record.data {
+            DnsRecordData::A(s) => IpAddr::V4(s),
+            DnsRecordData::Aaaa(s) => IpAddr::V6(s),
+            _ => continue,
+        };
+        resolved_names
+            .entry(normalize_name(&record.name))
+            .or_default()
+            .insert(address);
+    }
+}
+
+fn log_dns_record(status_info_ref: &StatusInfoRef, record: &DnsRecord) {
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "{} {} {}",
+            normalize_name(&record.name),
+            dns_record_type_name(record.record_type),
+            record.data
+        )),
+    );
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
@@ -1,13 +1,17 @@
 use eframe::{egui::Ui, epaint::Color32};
 use petgraph::visit::IntoNodeReferences;
+use std::sync::Arc;
 
 use crate::app::{
     network_topology::{NetworkTopology, NetworkTopologyNode},
     workspace_models::WorkspaceContext,
 };
 
+use super::localhost::inventory_localhost;
+
 pub fn add_localhost_pc(app_context: &mut WorkspaceContext) {
-    let new_localhost = NetworkTopologyNode::new_my_pc();
+    let new_localhost =
+        NetworkTopologyNode::new_my_pc(&app_context.app_state.project_settings.source);
 
     if new_localhost.is_err() {
         app_context.ui_state.add_this_computer_window_state.show("Unable to get information required to create localhost. This is probably, because you're not connected to any network.".to_string());
@@ -36,7 +40,13 @@ pub fn add_localhost_pc(app_context: &mut WorkspaceContext) {
     .is_none()
     {
         app_context.ui_state.add_this_computer_window_state.show(format!("Unable to create new instance of this computer, since your IP ({}) already exists as a node. Before creating a new one, please make sure to remove the old instance.", new_localhost.ip));
+        return;
     }
+
+    inventory_localhost(
+        Arc::clone(&app_context.app_state.network_topology.graph),
+        Arc::clone(&app_context.app_state.status_info),
+    );
 }
 
 pub fn render_validation_err(ui: &mut Ui, is_invalid: bool, message: &str) {
@@ -0,0 +1,156 @@
+use std::{net::IpAddr, thread::JoinHandle, time::Duration};
+
+use crossbeam::channel::{unbounded, Sender};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::app::{
+    network_topology::{HostnameSource, NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    dns_resolver::{lookup_ptr, DnsResolverConfig},
+    interfaces::SourceBinding,
+    llmnr::query_llmnr_reverse,
+    netbios::query_netbios_node_status,
+};
+
+pub const DEFAULT_NAME_QUERY_TIMEOUT_MS: u64 = 1000;
+pub const DEFAULT_HOSTNAME_RESOLUTION_CONCURRENCY: usize = 16;
+
+// Resolves hostnames on a fixed number of worker threads, so one unresponsive host doesn't hold up the rest
+pub struct HostnameResolutionPool {
+    sender: Sender<(NodeIndex, IpAddr)>,
+    workers: Vec<JoinHandle<()>>,
+}
+impl HostnameResolutionPool {
+    pub fn new(
+        graph_ref: NetworkTopologyGraph,
+        status_info_ref: StatusInfoRef,
+        dns_resolver: DnsResolverConfig,
+        source: SourceBinding,
+        concurrency: usize,
+    ) -> Self {
+        let (sender, receiver) = unbounded::<(NodeIndex, IpAddr)>();
+        let workers = (0..concurrency.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                let mut graph_ref = graph_ref.clone();
+                let status_info_ref = status_info_ref.clone();
+                let dns_resolver = dns_resolver.clone();
+                let source = source.clone();
+                std::thread::spawn(move || {
+                    for (node_index, ip) in receiver.iter() {
+                        resolve_node_hostnames(
+                            &mut graph_ref,
+                            &status_info_ref,
+                            node_index,
+                            ip,
+                            &dns_resolver,
+                            &source,
+                        );
+                    }
+                })
+            })
+            .collect();
+
+        Self { sender, workers }
+    }
+
+    pub fn resolve(&self, node_index: NodeIndex, ip: IpAddr) {
+        // Workers only stop once the sender is dropped, so this can't fail
+        let _ = self.sender.send((node_index, ip));
+    }
+
+    // Blocks until every queued lookup is done
+    pub fn finish(self) {
+        drop(self.sender);
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+// DNS (PTR), NetBIOS node status and LLMNR, every name found is recorded with its source
+fn resolve_node_hostnames(
+    graph_ref: &mut NetworkTopologyGraph,
+    status_info_ref: &StatusInfoRef,
+    node_index: NodeIndex,
+    ip: IpAddr,
+    dns_resolver: &DnsResolverConfig,
+    source: &SourceBinding,
+) {
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!("Performing DNS lookup-up for hostname for {}", ip)),
+    );
+
+    let mut found_any_hostname = false;
+    match lookup_ptr(dns_resolver, ip) {
+        Ok(Some(new_hostname)) => {
+            found_any_hostname = true;
+            NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+                // DNS is authoritative, so it's fine to override whatever was there before
+                node.hostname = new_hostname.clone();
+                node.add_hostname(&new_hostname, HostnameSource::Dns);
+            });
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Info(format!("Hostname for {} is \"{}\" (DNS)", ip, new_hostname)),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Warn(format!("DNS lookup for {} failed => {}", ip, e)),
+        ),
+    }
+
+    let name_query_timeout = Duration::from_millis(DEFAULT_NAME_QUERY_TIMEOUT_MS);
+    if let Ok(netbios_info) = query_netbios_node_status(ip, name_query_timeout, source) {
+        found_any_hostname |= netbios_info.computer_name.is_some();
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!(
+                "NetBIOS name of {} is \"{}\" (workgroup / domain \"{}\", mac {})",
+                ip,
+                netbios_info.computer_name.clone().unwrap_or_default(),
+                netbios_info.workgroup.clone().unwrap_or_default(),
+                netbios_info
+                    .mac
+                    .map(|s| s.to_string())
+                    .unwrap_or("unknown".to_owned())
+            )),
+        );
+        NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+            if let Some(computer_name) = &netbios_info.computer_name {
+                node.add_hostname(computer_name, HostnameSource::NetBios);
+            }
+            if node.mac.is_none() {
+                node.mac = netbios_info.mac;
+            }
+            node.netbios_info = Some(netbios_info);
+        });
+    }
+
+    if let Ok(Some(llmnr_hostname)) = query_llmnr_reverse(ip, name_query_timeout, source) {
+        found_any_hostname = true;
+        NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+            node.add_hostname(&llmnr_hostname, HostnameSource::Llmnr);
+        });
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!(
+                "Hostname for {} is \"{}\" (LLMNR)",
+                ip, llmnr_hostname
+            )),
+        );
+    }
+
+    if !found_any_hostname {
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!("Unable to determine hostname for {}", ip)),
+        );
+    }
+}
@@ -22,6 +22,9 @@ use pnet::packet::{
 use pnet::transport::icmp_packet_iter;
 use pnet::transport::TransportChannelType::Layer4;
 use pnet::transport::{transport_channel, TransportProtocol};
+use socket2::SockRef;
+
+use super::interfaces::SourceBinding;
 
 const ICMP_SIZE: usize = 64;
 pub const DEFAULT_PING_ENSURED_CONNECTIVITY_TIMEOUT_MS: u64 = 5000;
@@ -47,11 +50,30 @@ pub struct EchoReplyInfo {
     pub checksum: u16,
 }
 
+// pnet doesn't let us bind its raw sockets, so we borrow the descriptor for a bit
+#[cfg(unix)]
+struct BorrowedRawSocket(std::os::unix::io::RawFd);
+#[cfg(unix)]
+impl std::os::unix::io::AsRawFd for BorrowedRawSocket {
+    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
+        self.0
+    }
+}
+#[cfg(windows)]
+struct BorrowedRawSocket(std::os::windows::io::RawSocket);
+#[cfg(windows)]
+impl std::os::windows::io::AsRawSocket for BorrowedRawSocket {
+    fn as_raw_socket(&self) -> std::os::windows::io::RawSocket {
+        self.0
+    }
+}
+
 // Could be optimized by sending multiple pings at once, but I don't really care about perfomance atm
 pub fn send_icmp_echo_request_ping(
     address: IpAddr,
     ping_timeout_ms: u64,
     ping_checkup_ms: u64,
+    source: &SourceBinding,
 ) -> anyhow::Result<Option<EchoReplyInfo>> {
     let protocol = Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Icmp));
     let (mut tx, mut rx) = match transport_channel(4096, protocol) {
@@ -59,6 +81,11 @@ pub fn send_icmp_echo_request_ping(
         Err(e) => return Err(e.into()),
     };
 
+    if !source.is_default() {
+        let raw_socket = BorrowedRawSocket(tx.socket.fd as _);
+        source.bind_socket(SockRef::from(&raw_socket), address)?;
+    }
+
     let mut icmp_header: [u8; ICMP_SIZE] = [0; ICMP_SIZE];
     let icmp_packet = create_icmp_echo_request_packet(&mut icmp_header);
     let sent_at = Arc::new(RwLock::new(Instant::now()));
@@ -0,0 +1,142 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket},
+    time::Duration,
+};
+
+use local_ip_address::local_ip;
+use pnet::{
+    datalink::{self, NetworkInterface},
+    ipnetwork::IpNetwork,
+};
+use socket2::{Domain, Protocol, SockRef, Socket, Type};
+
+// Which local interface / address outgoing probes go out of, None => the OS decides (default route)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceBinding {
+    pub interface_name: Option<String>,
+    pub address: Option<IpAddr>,
+    pub interface_ips: Option<Vec<IpNetwork>>, // Some => looked up by `resolved`, None => looked up on every use
+}
+impl SourceBinding {
+    pub fn is_default(&self) -> bool {
+        self.interface_name.is_none() && self.address.is_none()
+    }
+
+    // Scans call this once before probing, so the interface list isn't read for every ping / connect
+    pub fn resolved(&self) -> Self {
+        Self {
+            interface_ips: self
+                .interface_name
+                .as_ref()
+                .map(|_| self.get_interface_ips()),
+            ..self.clone()
+        }
+    }
+
+    fn get_interface_ips(&self) -> Vec<IpNetwork> {
+        if let Some(interface_ips) = self.interface_ips.as_ref() {
+            return interface_ips.clone();
+        }
+        let Some(interface_name) = self.interface_name.as_ref() else {
+            return vec![];
+        };
+        datalink::interfaces()
+            .into_iter()
+            .find(|s| &s.name == interface_name)
+            .map(|s| s.ips)
+            .unwrap_or_default()
+    }
+
+    // Addresses of this pc the probes go out of, so scans can leave it out
+    pub fn own_addresses(&self) -> Vec<IpAddr> {
+        let mut addresses = self.address.into_iter().collect::<Vec<IpAddr>>();
+        addresses.extend(self.get_interface_ips().iter().map(|s| s.ip()));
+        if self.is_default() {
+            addresses.extend(local_ip().ok());
+        }
+        addresses
+    }
+
+    // The configured address if it's the same IP version as the target, otherwise the interface's best match
+    pub fn address_for(&self, target: IpAddr) -> Option<IpAddr> {
+        if let Some(address) = self.address.filter(|s| s.is_ipv4() == target.is_ipv4()) {
+            return Some(address);
+        }
+
+        let interface_ips = self.get_interface_ips();
+        let candidates = interface_ips
+            .iter()
+            .filter(|s| s.is_ipv4() == target.is_ipv4())
+            .collect::<Vec<_>>();
+        candidates
+            .iter()
+            .find(|s| s.contains(target))
+            // Link-local sources only work for link-local targets (and the other way around)
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .find(|s| is_ipv6_link_local(s.ip()) == is_ipv6_link_local(target))
+            })
+            .or(candidates.first())
+            .map(|s| s.ip())
+    }
+
+    // Has to happen before the socket sends anything
+    pub fn bind_socket(&self, socket: SockRef<'_>, target: IpAddr) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(interface_name) = self.interface_name.as_ref() {
+            socket.bind_device(Some(interface_name.as_bytes()))?;
+        }
+        if let Some(address) = self.address_for(target) {
+            socket.bind(&SocketAddr::new(address, 0).into())?;
+        }
+        Ok(())
+    }
+
+    pub fn connect_tcp(&self, target: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        if self.is_default() {
+            return TcpStream::connect_timeout(&target, timeout);
+        }
+
+        let socket = Socket::new(
+            Domain::for_address(target),
+            Type::STREAM,
+            Some(Protocol::TCP),
+        )?;
+        self.bind_socket(SockRef::from(&socket), target.ip())?;
+        socket.connect_timeout(&target.into(), timeout)?;
+        Ok(socket.into())
+    }
+
+    // Socket for request / response protocols (DNS, SNMP, NetBIOS, ...) sent to target
+    pub fn bind_udp(&self, target: IpAddr) -> io::Result<UdpSocket> {
+        if self.is_default() {
+            let unspecified = match target {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            };
+            return UdpSocket::bind(SocketAddr::new(unspecified, 0));
+        }
+
+        let socket = Socket::new(
+            Domain::for_address(SocketAddr::new(target, 0)),
+            Type::DGRAM,
+            Some(Protocol::UDP),
+        )?;
+        self.bind_socket(SockRef::from(&socket), target)?;
+        Ok(socket.into())
+    }
+}
+
+// Everything probes can be sent from
+pub fn get_source_interfaces() -> Vec<NetworkInterface> {
+    datalink::interfaces()
+        .into_iter()
+        .filter(|s| s.is_up() && !s.is_loopback() && !s.ips.is_empty())
+        .collect()
+}
+
+fn is_ipv6_link_local(ip: IpAddr) -> bool {
+    matches!(ip, IpAddr::V6(s) if (s.segments()[0] & 0xffc0) == 0xfe80)
+}
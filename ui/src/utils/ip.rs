@@ -1,33 +1,53 @@
 use log::info;
 use petgraph::{stable_graph::NodeIndex, visit::IntoNodeReferences};
 use pnet::packet::icmp::IcmpTypes;
-use std::net::IpAddr;
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 use crate::{
     app::{
         network_topology::{
-            NetworkTopology, NetworkTopologyEdge, NetworkTopologyGraph, NetworkTopologyGraphNode,
-            NetworkTopologyNode, PortInfo,
+            HostnameSource, NetworkTopology, NetworkTopologyEdge, NetworkTopologyGraph,
+            NetworkTopologyGraphNode, NetworkTopologyNode, PortInfo,
         },
         workspace_models::{AppState, StatusInfoRef, StatusMessage},
     },
-    utils::icmp::send_icmp_echo_request_ping,
+    utils::{
+        access_checks::AccessCheck,
+        dns_resolver::{lookup_host, DnsResolverConfig},
+        hostname_resolution::{HostnameResolutionPool, DEFAULT_HOSTNAME_RESOLUTION_CONCURRENCY},
+        icmp::send_icmp_echo_request_ping,
+        interfaces::SourceBinding,
+        proxy::ProxyConfig,
+        snapshots::{record_scan_snapshot, ScanSnapshotsRef},
+        targets::{TargetExclusions, TargetExpressions, TargetList, TargetRange},
+    },
 };
 
 use super::{constants::ALL_COMMON_PORTS, ports::is_port_open_using_tcp_stream};
 
+pub struct PingIpListConfig {
+    pub ping_timeout_ms: u64,
+    pub ping_checkup_ms: u64,
+    pub remove_connectivity_status_when_unreachable: bool,
+    pub reset_connectivity_status: bool,
+    pub resolve_hostnames_with: Option<DnsResolverConfig>, // Some => reverse lookups as soon as a host is found
+    pub exclusions: TargetExclusions,
+    pub source: SourceBinding,
+}
 // TODO: Implement option for multi threading
 // TODO: Implement option to change pc mac address for each ping
 pub fn ping_ip_list(
     mut graph_ref: NetworkTopologyGraph,
     status_info_ref: StatusInfoRef,
-    ips_to_ping: Vec<IpAddr>,
-    ping_timeout_ms: u64,
-    ping_checkup_ms: u64,
-    remove_connectivity_status_when_unreachable: bool,
-    reset_connectivity_status: bool,
+    scan_snapshots_ref: ScanSnapshotsRef,
+    targets: TargetList,
+    config: PingIpListConfig,
 ) {
-    if ips_to_ping.is_empty() {
+    let targets_count = targets.count();
+    if targets_count == 0 {
         AppState::log_to_status_generic(
             &status_info_ref,
             StatusMessage::Info("Didn't receive any ips to ping. Not performing ping.".to_owned()),
@@ -38,28 +58,58 @@ pub fn ping_ip_list(
         &status_info_ref,
         StatusMessage::Info(format!(
             "Initiating ip ping against {} hosts.",
-            ips_to_ping.len()
+            targets_count
         )),
     );
-    info!("Starting ip scan on following ips: {:?}", ips_to_ping);
+    info!(
+        "Starting ip scan on following targets: {:?}",
+        targets.entries
+    );
 
     std::thread::spawn(move || {
+        let source = config.source.resolved();
         let mut reachable_ips = vec![];
+        // Only ips of existing nodes, since targets can be huge and there'd be nothing to remove for the rest anyway
         let mut unreachable_ips = vec![];
+        let mut excluded_ips_count = 0;
         let localhost_node_index = match NetworkTopology::get_localhosts_node(&mut graph_ref) {
             Some(s) => Some(s.0),
             None => None,
         };
 
-        if reset_connectivity_status {
+        let resolution_pool = config.resolve_hostnames_with.map(|dns_resolver| {
+            HostnameResolutionPool::new(
+                graph_ref.clone(),
+                status_info_ref.clone(),
+                dns_resolver,
+                source.clone(),
+                DEFAULT_HOSTNAME_RESOLUTION_CONCURRENCY,
+            )
+        });
+
+        if config.reset_connectivity_status {
             if let Some(localhost) = localhost_node_index {
                 NetworkTopology::remove_edges_from_node(&mut graph_ref, localhost);
             }
         }
 
-        for ip in ips_to_ping {
-            unreachable_ips.push(ip);
-            let answ = send_icmp_echo_request_ping(ip, ping_timeout_ms, ping_checkup_ms);
+        let scanned_targets = targets.clone();
+        for ip in targets.into_addresses() {
+            if config.exclusions.is_excluded(ip) {
+                excluded_ips_count += 1;
+                continue;
+            }
+            let is_tracked_as_unreachable = config.remove_connectivity_status_when_unreachable
+                && NetworkTopology::get_node_by_ip(&mut graph_ref, ip).is_some();
+            if is_tracked_as_unreachable {
+                unreachable_ips.push(ip);
+            }
+            let answ = send_icmp_echo_request_ping(
+                ip,
+                config.ping_timeout_ms,
+                config.ping_checkup_ms,
+                &source,
+            );
             if answ.is_err() {
                 AppState::log_to_status_generic(
                     &status_info_ref,
@@ -72,6 +122,7 @@ pub fn ping_ip_list(
             }
             let Some(answ) = answ.unwrap() else {
                 AppState::log_to_status_generic(&status_info_ref, StatusMessage::Warn(format!("{} ping timed out", ip)));
+                NetworkTopology::record_ping(&mut graph_ref, ip, None);
                 continue;
             };
             if answ.icmp_type == IcmpTypes::DestinationUnreachable {
@@ -79,9 +130,11 @@ pub fn ping_ip_list(
                     &status_info_ref,
                     StatusMessage::Warn(format!("{} is unreachable", ip)),
                 );
+                NetworkTopology::record_ping(&mut graph_ref, ip, None);
                 continue;
             }
             if answ.icmp_type != IcmpTypes::EchoReply {
+                NetworkTopology::record_ping(&mut graph_ref, ip, None);
                 AppState::log_to_status_generic(
                     &status_info_ref,
                     StatusMessage::Warn(format!(
@@ -92,15 +145,7 @@ pub fn ping_ip_list(
                 continue;
             }
 
-            let target_node_index = match NetworkTopology::get_node_by_ip(&mut graph_ref, ip) {
-                Some((node_index, _)) => node_index,
-                None => NetworkTopology::add_node(
-                    &mut graph_ref,
-                    NetworkTopologyNode::new(ip, "".to_string(), None),
-                    None,
-                )
-                .unwrap(), // safe to unwrape, since we're 100% sure the node does not exist yet.
-            };
+            let (target_node_index, _) = NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, ip);
             if let Some(localhost) = localhost_node_index {
                 if !graph_ref
                     .lock()
@@ -116,15 +161,26 @@ pub fn ping_ip_list(
                 }
             }
 
+            NetworkTopology::record_ping(&mut graph_ref, ip, Some(answ.rtt));
             AppState::log_to_status_generic(
                 &status_info_ref,
-                StatusMessage::Info(format!("{} is reachable", ip)),
+                StatusMessage::Info(format!(
+                    "{} is reachable ({:.1} ms)",
+                    ip,
+                    answ.rtt.as_secs_f64() * 1000.0
+                )),
             );
             reachable_ips.push(ip);
-            unreachable_ips.pop();
+            if is_tracked_as_unreachable {
+                unreachable_ips.pop();
+            }
+
+            if let Some(resolution_pool) = &resolution_pool {
+                resolution_pool.resolve(target_node_index, ip);
+            }
         }
 
-        if remove_connectivity_status_when_unreachable {
+        if config.remove_connectivity_status_when_unreachable {
             AppState::log_to_status_generic(
                 &status_info_ref,
                 StatusMessage::Info("Removing connectivity status to unreachable IPs".to_owned()),
@@ -151,6 +207,88 @@ pub fn ping_ip_list(
                 reachable_ips.len()
             )),
         );
+        if excluded_ips_count > 0 {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info(format!("Skipped {} excluded targets.", excluded_ips_count)),
+            );
+        }
+
+        if let Some(resolution_pool) = resolution_pool {
+            resolution_pool.finish();
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info("Finished addr lookup.".to_string()),
+            );
+        }
+
+        record_scan_snapshot(
+            &scan_snapshots_ref,
+            &mut graph_ref,
+            format!("Ping of {} targets", targets_count),
+            scanned_targets,
+            config.exclusions,
+            vec![],
+            reachable_ips.into_iter().map(|s| (s, vec![])).collect(),
+        );
+    });
+}
+
+// Hostnames get resolved first, every address they resolve to is then pinged along with the rest of the targets
+pub fn ping_target_expressions(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    scan_snapshots_ref: ScanSnapshotsRef,
+    expressions: TargetExpressions,
+    dns_resolver: DnsResolverConfig,
+    config: PingIpListConfig,
+) {
+    let TargetExpressions {
+        mut targets,
+        hostnames,
+    } = expressions;
+    if hostnames.is_empty() {
+        ping_ip_list(graph_ref, status_info_ref, scan_snapshots_ref, targets, config);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for hostname in hostnames {
+            let resolved_ips = match lookup_host(&dns_resolver, &hostname) {
+                Ok(s) => s,
+                Err(e) => {
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Err(format!("Unable to resolve {} => {}", hostname, e)),
+                    );
+                    continue;
+                }
+            };
+            if resolved_ips.is_empty() {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Warn(format!("{} does not resolve to any address", hostname)),
+                );
+            }
+
+            for ip in resolved_ips {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Info(format!("{} resolves to {}", hostname, ip)),
+                );
+                // Excluded addresses don't even become nodes
+                if config.exclusions.is_excluded(ip) {
+                    continue;
+                }
+                let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, ip);
+                NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+                    node.add_hostname(&hostname, HostnameSource::Dns);
+                });
+                targets.entries.push(TargetRange::Single(ip));
+            }
+        }
+
+        ping_ip_list(graph_ref, status_info_ref, scan_snapshots_ref, targets, config);
     });
 }
 
@@ -158,11 +296,16 @@ pub fn update_hostname_list(
     mut graph_ref: NetworkTopologyGraph,
     status_info_ref: StatusInfoRef,
     ips: Vec<IpAddr>,
+    dns_resolver: DnsResolverConfig,
+    exclusions: TargetExclusions,
+    source: SourceBinding,
 ) {
     std::thread::spawn(move || {
+        // NetBIOS and LLMNR query the hosts directly, so excluded ones are left alone
         let nodes_to_test = NetworkTopology::get_all_nodes_except_localhost(&mut graph_ref)
             .iter()
             .filter(|s| ips.contains(&s.1.data().unwrap().ip))
+            .filter(|s| !exclusions.is_excluded(s.1.data().unwrap().ip))
             .cloned()
             .collect::<Vec<NetworkTopologyGraphNode>>();
 
@@ -187,52 +330,99 @@ pub fn update_hostname_list(
             nodes_to_test
         );
 
+        let resolution_pool = HostnameResolutionPool::new(
+            graph_ref,
+            status_info_ref.clone(),
+            dns_resolver,
+            source.resolved(),
+            DEFAULT_HOSTNAME_RESOLUTION_CONCURRENCY,
+        );
         for node in nodes_to_test {
-            let ip_to_test = node.1.data().unwrap().ip;
-            AppState::log_to_status_generic(
-                &status_info_ref,
-                StatusMessage::Info(format!(
-                    "Performing DNS lookup-up for hostname for {}",
-                    ip_to_test
-                )),
-            );
+            resolution_pool.resolve(node.0, node.1.data().unwrap().ip);
+        }
+        resolution_pool.finish();
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info("Finished addr lookup.".to_string()),
+        );
+    });
+}
 
-            let Ok(new_hostname) = dns_lookup::lookup_addr(&ip_to_test) else {
+// Forward lookup (A + AAAA), every resolved address becomes a node named after the hostname
+pub fn add_nodes_by_hostname(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    scan_snapshots_ref: ScanSnapshotsRef,
+    hostname: String,
+    notes: String,
+    dns_resolver: DnsResolverConfig,
+    ping_after_creation_with: Option<PingIpListConfig>,
+) {
+    std::thread::spawn(move || {
+        let resolved_ips = match lookup_host(&dns_resolver, &hostname) {
+            Ok(s) if !s.is_empty() => s,
+            Ok(_) => {
                 AppState::log_to_status_generic(
                     &status_info_ref,
-                    StatusMessage::Info(format!("Unable to determine hostname for {}", ip_to_test)),
+                    StatusMessage::Warn(format!("{} does not resolve to any address", hostname)),
                 );
-                continue;
-            };
+                return;
+            }
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(format!("Unable to resolve {} => {}", hostname, e)),
+                );
+                return;
+            }
+        };
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "{} resolves to {}",
+                hostname,
+                resolved_ips
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        );
 
-            let mut graph_lock = graph_ref.lock().unwrap();
-            let node_to_update = graph_lock.node_weight_mut(node.0).unwrap();
-            let mut new_data = node_to_update.data().unwrap().clone();
-            new_data.hostname = new_hostname.clone();
-            node_to_update.set_data(Some(new_data));
-            drop(graph_lock);
+        for ip in resolved_ips.iter() {
+            let mut new_node = NetworkTopologyNode::new(*ip, notes.clone(), Some(hostname.clone()));
+            new_node.add_hostname(&hostname, HostnameSource::Dns);
+            if NetworkTopology::add_node(&mut graph_ref, new_node, None).is_none() {
+                let (existing_node, _) =
+                    NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, *ip);
+                NetworkTopology::update_node_data(&mut graph_ref, existing_node, |node| {
+                    node.add_hostname(&hostname, HostnameSource::Dns);
+                });
+            }
+        }
 
-            NetworkTopology::update_node_label(&mut graph_ref, node.0);
-            AppState::log_to_status_generic(
-                &status_info_ref,
-                StatusMessage::Info(format!(
-                    "Hostname for {} is \"{}\"",
-                    ip_to_test, new_hostname
-                )),
+        if let Some(ping_config) = ping_after_creation_with {
+            ping_ip_list(
+                graph_ref,
+                status_info_ref,
+                scan_snapshots_ref,
+                resolved_ips.into(),
+                ping_config,
             );
         }
-
-        AppState::log_to_status_generic(
-            &status_info_ref,
-            StatusMessage::Info("Finished addr lookup.".to_string()),
-        );
     });
 }
 
 // TODO: Implement option for multi threading
 // TODO: Implement option to change pc mac address for each ping
 pub type Port = u16;
-pub type BannerGrabResult = Option<String>;
+#[derive(Debug, Clone)]
+pub struct BannerGrab {
+    pub result: String,
+    pub result_raw: Vec<u8>,
+}
+pub type BannerGrabResult = Option<BannerGrab>;
 #[derive(Debug, Clone)]
 pub struct FuzzingResult {
     pub command: String,
@@ -244,20 +434,53 @@ pub struct ScanIpPortsConfig {
     pub connection_timeout_ms: u64,
     pub should_banner_grab: bool,
     pub should_fuzz: bool,
+    pub should_check_access: bool, // anonymous / unauthenticated access checks on the identified services
     pub read_write_timeout_ms: u64,
+    pub exclusions: TargetExclusions,
+    pub source: SourceBinding,
+    pub proxy: Option<ProxyConfig>,
 }
 pub fn scap_ip_ports(
-    graph_ref: NetworkTopologyGraph,
+    mut graph_ref: NetworkTopologyGraph,
     status_info_ref: StatusInfoRef,
+    scan_snapshots_ref: ScanSnapshotsRef,
     ip: IpAddr,
     ports: Vec<Port>,
     node_index: NodeIndex,
     config: ScanIpPortsConfig,
 ) {
+    if config.exclusions.is_excluded(ip) {
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Warn(format!(
+                "{} is excluded from probing, not scanning its ports.",
+                ip
+            )),
+        );
+        return;
+    }
+
     std::thread::spawn(move || {
+        let config = ScanIpPortsConfig {
+            source: config.source.resolved(),
+            ..config
+        };
+        if let Some(proxy) = config.proxy.as_ref() {
+            let timeout = Duration::from_millis(config.connection_timeout_ms);
+            if let Err(e) = proxy.ensure_reachable(timeout, &config.source) {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(format!(
+                        "Unable to use {} proxy {}, not scanning ports of {} => {}",
+                        proxy.kind, proxy.address, ip, e
+                    )),
+                );
+                return;
+            }
+        }
         let mut reachable_ports = vec![];
 
-        for port in ports {
+        for port in ports.iter().copied() {
             let port_info_raw = is_port_open_using_tcp_stream(ip, port, &config);
             if !port_info_raw.0 {
                 AppState::log_to_status_generic(
@@ -281,7 +504,7 @@ pub fn scap_ip_ports(
                     &status_info_ref,
                     StatusMessage::Info(format!(
                         "Port {port} - banner grab result: {:?}",
-                        port_info_raw.1
+                        port_info_raw.1.as_ref().map(|s| &s.result)
                     )),
                 );
             }
@@ -295,13 +518,17 @@ pub fn scap_ip_ports(
                 );
             }
 
-            reachable_ports.push(PortInfo::new(
+            let mut port_info = PortInfo::new(
                 port,
                 port_info_raw.1.clone(),
                 port_info_raw.2.clone(),
                 possible_port_service,
                 possible_service_usefull_info,
-            ));
+            );
+            if config.should_check_access {
+                check_port_access(&status_info_ref, ip, &mut port_info, &config);
+            }
+            reachable_ports.push(port_info);
         }
 
         AppState::log_to_status_generic(
@@ -316,6 +543,17 @@ pub fn scap_ip_ports(
                     .join(", ")
             )),
         );
+        // Node only keeps the latest answer, the snapshot is what the diffs compare.
+        // The host is kept even without open ports, so ports that got closed show up.
+        record_scan_snapshot(
+            &scan_snapshots_ref,
+            &mut graph_ref,
+            format!("Port scan of {} ({} ports)", ip, ports.len()),
+            vec![ip].into(),
+            config.exclusions.clone(),
+            ports,
+            vec![(ip, reachable_ports.clone())],
+        );
 
         let mut graph_lock = graph_ref.lock().unwrap();
         let node_to_update = graph_lock.node_weight_mut(node_index);
@@ -348,8 +586,40 @@ pub fn scap_ip_ports(
     });
 }
 
+fn check_port_access(
+    status_info_ref: &StatusInfoRef,
+    ip: IpAddr,
+    port_info: &mut PortInfo,
+    config: &ScanIpPortsConfig,
+) {
+    let port = port_info.number;
+    let Some(check) = AccessCheck::for_port(port_info) else {
+        return;
+    };
+    match check.run(SocketAddr::new(ip, port), config) {
+        Ok(Some(finding)) => {
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Warn(format!(
+                    "Port {port} - {}: {}",
+                    finding.check, finding.summary
+                )),
+            );
+            port_info.access_findings.push(finding);
+        }
+        Ok(None) => AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!("Port {port} - {check}: credentials are required.")),
+        ),
+        Err(e) => AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Warn(format!("Port {port} - {check}: unable to tell => {e}")),
+        ),
+    }
+}
+
 #[allow(unused_variables)]
-fn recognize_port_service(
+pub fn recognize_port_service(
     port: &Port,
     banner: &BannerGrabResult,
     fuzzing_results: &FuzzingResults,
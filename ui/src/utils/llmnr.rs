@@ -0,0 +1,54 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use rand::random;
+
+use super::{
+    dns::{
+        build_dns_query, reverse_lookup_name, DnsMessage, DnsRecordData, DNS_CLASS_IN, DNS_TYPE_PTR,
+    },
+    interfaces::SourceBinding,
+};
+
+pub const LLMNR_PORT: u16 = 5355;
+
+// Windows answers LLMNR reverse (PTR) queries sent directly to it, even when there's no reverse DNS zone
+pub fn query_llmnr_reverse(
+    ip: IpAddr,
+    timeout: Duration,
+    source: &SourceBinding,
+) -> anyhow::Result<Option<String>> {
+    let socket = source.bind_udp(ip)?;
+    socket.set_read_timeout(Some(timeout))?;
+
+    let transaction_id = random::<u16>();
+    let query = build_dns_query(
+        transaction_id,
+        &reverse_lookup_name(ip),
+        DNS_TYPE_PTR,
+        DNS_CLASS_IN,
+        false,
+    )?;
+    socket.send_to(&query, SocketAddr::new(ip, LLMNR_PORT))?;
+
+    let mut buffer = [0u8; 2048];
+    loop {
+        let (bytes_read, responder) = socket.recv_from(&mut buffer)?;
+        if responder.ip() != ip {
+            continue;
+        }
+        let Ok(response) = DnsMessage::parse(&buffer[..bytes_read]) else {
+            continue;
+        };
+        if response.id != transaction_id || !response.is_response() {
+            continue;
+        }
+
+        return Ok(response.answers.iter().find_map(|s| match &s.data {
+            DnsRecordData::Ptr(name) => Some(name.trim_end_matches('.').to_owned()),
+            _ => None,
+        }));
+    }
+}
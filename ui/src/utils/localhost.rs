@@ -0,0 +1,401 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    thread,
+};
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph, PortInfo},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{constants::ALL_COMMON_PORTS, interfaces::get_source_interfaces, ip::Port};
+
+// <net/tcp_states.h>, unconnected UDP sockets show up as TCP_CLOSE
+const TCP_LISTEN: u8 = 0x0a;
+const TCP_CLOSE: u8 = 0x07;
+const MAX_COMMAND_LINE_LEN: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+impl Display for SocketProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp => write!(f, "TCP"),
+            Self::Udp => write!(f, "UDP"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalProcess {
+    pub pid: u32,
+    pub name: String,
+    pub command_line: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListeningSocket {
+    pub protocol: SocketProtocol,
+    pub address: SocketAddr,
+    pub process: Option<LocalProcess>, // None => owned by another user (not root) or already gone
+}
+impl ListeningSocket {
+    fn is_loopback_only(&self) -> bool {
+        self.address.ip().is_loopback()
+    }
+
+    // What a TCP port scan from another device would find
+    fn is_reachable_by_tcp_scans(&self) -> bool {
+        self.protocol == SocketProtocol::Tcp && !self.is_loopback_only()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} {}{} - {}",
+            self.protocol,
+            self.address,
+            if self.is_loopback_only() {
+                " (only reachable from this computer)"
+            } else {
+                ""
+            },
+            self.process
+                .as_ref()
+                .map(|s| format!("{} (pid {}): {}", s.name, s.pid, s.command_line))
+                .unwrap_or("unknown process".to_owned())
+        )
+    }
+}
+
+// Fills "this computer" from the local system instead of scanning ourselves
+pub fn inventory_localhost(mut graph_ref: NetworkTopologyGraph, status_info_ref: StatusInfoRef) {
+    thread::spawn(move || {
+        let Some((node_index, _)) = NetworkTopology::get_localhosts_node(&mut graph_ref) else {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Warn("There's no node for this computer to fill in.".to_owned()),
+            );
+            return;
+        };
+
+        let sockets = match get_listening_sockets() {
+            Ok(s) => s,
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(format!("Unable to list the listening sockets => {}", e)),
+                );
+                vec![]
+            }
+        };
+        let opened_ports = sockets_to_ports(&sockets);
+
+        let mut notes = vec!["This is the current pc.".to_owned()];
+        if let Some(os_release) = get_os_release() {
+            notes.push(format!("OS: {}", os_release));
+        }
+        for interface in get_source_interfaces() {
+            notes.push(format!(
+                "{}{}: {}",
+                interface.name,
+                interface
+                    .mac
+                    .map(|s| format!(" ({})", s))
+                    .unwrap_or_default(),
+                interface
+                    .ips
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ));
+        }
+        // Opened ports are compared against scans, everything else only gets listed
+        let other_sockets = sockets
+            .iter()
+            .filter(|s| !s.is_reachable_by_tcp_scans())
+            .map(|s| s.describe())
+            .collect::<Vec<String>>();
+        if !other_sockets.is_empty() {
+            notes.push("UDP and local only sockets:".to_owned());
+            notes.extend(other_sockets);
+        }
+
+        let opened_ports_count = opened_ports.len();
+        NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+            node.notes = notes.join("\n");
+            for opened_port in opened_ports {
+                node.opened_ports.retain(|s| s.number != opened_port.number);
+                node.opened_ports.push(opened_port);
+            }
+            node.opened_ports.sort_by_key(|s| s.number);
+        });
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "This computer listens on {} TCP ports reachable from the network ({} sockets, {} of them reachable only from here).",
+                opened_ports_count,
+                sockets.len(),
+                sockets.iter().filter(|s| s.is_loopback_only()).count()
+            )),
+        );
+    });
+}
+
+// One port per number, only the TCP sockets reachable from the network
+fn sockets_to_ports(sockets: &[ListeningSocket]) -> Vec<PortInfo> {
+    let sockets = sockets
+        .iter()
+        .filter(|s| s.is_reachable_by_tcp_scans())
+        .collect::<Vec<&ListeningSocket>>();
+    let mut port_numbers = sockets
+        .iter()
+        .map(|s| s.address.port())
+        .collect::<Vec<Port>>();
+    port_numbers.sort();
+    port_numbers.dedup();
+
+    port_numbers
+        .into_iter()
+        .map(|port| {
+            let port_sockets = sockets
+                .iter()
+                .filter(|s| s.address.port() == port)
+                .copied()
+                .collect::<Vec<&ListeningSocket>>();
+            let service_name = port_sockets
+                .iter()
+                .find_map(|s| s.process.as_ref().map(|s| s.name.clone()))
+                .or_else(|| {
+                    ALL_COMMON_PORTS
+                        .iter()
+                        .find(|s| s.0 == port)
+                        .map(|s| s.1.to_string())
+                })
+                .unwrap_or("unknown".to_owned());
+            let description = port_sockets
+                .iter()
+                .map(|s| s.describe())
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            PortInfo::new(port, None, None, service_name, Some(description))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_listening_sockets() -> anyhow::Result<Vec<ListeningSocket>> {
+    let mut raw_sockets = vec![];
+    for (path, protocol) in [
+        ("/proc/net/tcp", SocketProtocol::Tcp),
+        ("/proc/net/tcp6", SocketProtocol::Tcp),
+        ("/proc/net/udp", SocketProtocol::Udp),
+        ("/proc/net/udp6", SocketProtocol::Udp),
+    ] {
+        match std::fs::read_to_string(path) {
+            Ok(s) => raw_sockets.extend(parse_proc_net_sockets(&s, protocol)),
+            // IPv6 might be disabled, that's fine
+            Err(_) if path.ends_with('6') => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let socket_owners = get_socket_owners();
+    Ok(raw_sockets
+        .into_iter()
+        .map(|(protocol, address, inode)| ListeningSocket {
+            protocol,
+            address,
+            process: socket_owners
+                .iter()
+                .find(|s| s.0 == inode)
+                .map(|s| s.1.clone()),
+        })
+        .collect())
+}
+#[cfg(not(target_os = "linux"))]
+pub fn get_listening_sockets() -> anyhow::Result<Vec<ListeningSocket>> {
+    Err(anyhow::anyhow!(
+        "Listing the listening sockets is only supported on linux atm."
+    ))
+}
+
+// sl local_address rem_address st ... uid timeout inode, addresses are hex in host byte order
+fn parse_proc_net_sockets(
+    content: &str,
+    protocol: SocketProtocol,
+) -> Vec<(SocketProtocol, SocketAddr, u64)> {
+    let listening_state = match protocol {
+        SocketProtocol::Tcp => TCP_LISTEN,
+        SocketProtocol::Udp => TCP_CLOSE,
+    };
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<&str>>();
+            let state = u8::from_str_radix(columns.get(3)?, 16).ok()?;
+            if state != listening_state {
+                return None;
+            }
+
+            let (address, port) = columns.get(1)?.split_once(':')?;
+            let address = parse_proc_net_address(address)?;
+            let port = u16::from_str_radix(port, 16).ok()?;
+            let inode = columns.get(9)?.parse::<u64>().ok()?;
+            // Port 0 => UDP sockets which never got bound
+            (port != 0).then_some((protocol, SocketAddr::new(address, port), inode))
+        })
+        .collect()
+}
+
+// IPv6 addresses are four 32 bit words, each one in host byte order
+fn parse_proc_net_address(address: &str) -> Option<IpAddr> {
+    match address.len() {
+        8 => Some(IpAddr::V4(Ipv4Addr::from(
+            u32::from_str_radix(address, 16).ok()?.to_ne_bytes(),
+        ))),
+        32 => {
+            let mut octets = [0u8; 16];
+            for (i, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(address.get(i * 8..i * 8 + 8)?, 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            let address = Ipv6Addr::from(octets);
+            // Dual stack sockets bound to ::ffff:a.b.c.d are IPv4 sockets really
+            Some(
+                address
+                    .to_ipv4_mapped()
+                    .map(IpAddr::V4)
+                    .unwrap_or(IpAddr::V6(address)),
+            )
+        }
+        _ => None,
+    }
+}
+
+// (socket inode, owner), every fd of a socket is a link to "socket:[<inode>]".
+// Without root only our own processes can be looked into.
+#[cfg(target_os = "linux")]
+fn get_socket_owners() -> Vec<(u64, LocalProcess)> {
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return vec![];
+    };
+
+    let mut owners = vec![];
+    for process in processes.flatten() {
+        let Some(pid) = process
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let inodes = fds
+            .flatten()
+            .filter_map(|s| std::fs::read_link(s.path()).ok())
+            .filter_map(|s| {
+                s.to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .collect::<Vec<u64>>();
+        if inodes.is_empty() {
+            continue;
+        }
+
+        let name = std::fs::read_to_string(process.path().join("comm"))
+            .map(|s| s.trim_end().to_owned())
+            .unwrap_or(pid.to_string());
+        let mut command_line = std::fs::read(process.path().join("cmdline"))
+            .map(|s| {
+                String::from_utf8_lossy(&s)
+                    .replace('\0', " ")
+                    .trim_end()
+                    .to_owned()
+            })
+            .unwrap_or_default();
+        if command_line.chars().count() > MAX_COMMAND_LINE_LEN {
+            command_line = format!(
+                "{}...",
+                command_line
+                    .chars()
+                    .take(MAX_COMMAND_LINE_LEN)
+                    .collect::<String>()
+            );
+        }
+        let process = LocalProcess {
+            pid,
+            name,
+            command_line,
+        };
+        owners.extend(inodes.into_iter().map(|s| (s, process.clone())));
+    }
+    owners
+}
+
+// PRETTY_NAME from os-release(5), falls back to the kernel version
+pub fn get_os_release() -> Option<String> {
+    let pretty_name = ["/etc/os-release", "/usr/lib/os-release"]
+        .iter()
+        .filter_map(|s| std::fs::read_to_string(s).ok())
+        .find_map(|s| {
+            s.lines().find_map(|s| {
+                s.strip_prefix("PRETTY_NAME=")
+                    .map(|s| s.trim_matches(|c| c == '"' || c == '\'').to_owned())
+            })
+        });
+    if pretty_name.is_some() {
+        return pretty_name;
+    }
+
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .ok()
+        .map(|s| format!("Linux {}", s.trim()))
+        .or_else(|| (!cfg!(target_os = "linux")).then(|| std::env::consts::OS.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket(protocol: SocketProtocol, address: &str) -> ListeningSocket {
+        ListeningSocket {
+            protocol,
+            address: address.parse().unwrap(),
+            process: None,
+        }
+    }
+
+    #[test]
+    fn only_tcp_sockets_reachable_from_the_network_are_ports() {
+        let sockets = [
+            socket(SocketProtocol::Tcp, "0.0.0.0:22"),
+            socket(SocketProtocol::Tcp, "[::]:22"),
+            socket(SocketProtocol::Tcp, "127.0.0.1:631"),
+            socket(SocketProtocol::Tcp, "[::1]:5432"),
+            socket(SocketProtocol::Tcp, "127.0.0.1:8080"),
+            socket(SocketProtocol::Tcp, "192.168.1.10:8080"),
+            socket(SocketProtocol::Udp, "0.0.0.0:53"),
+            socket(SocketProtocol::Udp, "0.0.0.0:8080"),
+        ];
+        let ports = sockets_to_ports(&sockets);
+        assert_eq!(
+            ports.iter().map(|s| s.number).collect::<Vec<Port>>(),
+            vec![22, 8080]
+        );
+
+        let description = ports[1].possible_service_usefull_info.clone().unwrap();
+        assert_eq!(description, "TCP 192.168.1.10:8080 - unknown process");
+    }
+}
@@ -0,0 +1,345 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use log::{debug, info};
+use rand::random;
+
+use crate::app::{
+    network_topology::{HostnameSource, NetworkTopology, NetworkTopologyGraph, PortInfo},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    dns::{
+        build_dns_query, DnsMessage, DnsRecordData, DnsRecordType, DNS_CLASS_IN, DNS_TYPE_A,
+        DNS_TYPE_PTR, DNS_TYPE_SRV, DNS_TYPE_TXT,
+    },
+    ip::Port,
+};
+
+pub const MDNS_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub const MDNS_PORT: u16 = 5353;
+pub const DEFAULT_MDNS_RESPONSE_WAIT_MS: u64 = 1500;
+
+const DNS_SD_SERVICES_QUERY: &str = "_services._dns-sd._udp.local";
+// Top bit of the question class => please respond directly to me (RFC 6762, 5.4)
+const MDNS_CLASS_IN_UNICAST_RESPONSE: u16 = DNS_CLASS_IN | 0x8000;
+const MDNS_SOCKET_READ_TIMEOUT_MS: u64 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdnsService {
+    pub instance_name: String,
+    pub service_type: String,
+    pub hostname: String,
+    pub port: Port,
+    pub txt_records: Vec<String>,
+}
+impl MdnsService {
+    // "My Printer._ipp._tcp.local" => "My Printer"
+    pub fn instance_label(&self) -> &str {
+        self.instance_name
+            .strip_suffix(&self.service_type)
+            .map(|s| s.trim_end_matches('.'))
+            .unwrap_or(&self.instance_name)
+    }
+
+    // "_ipp._tcp.local" => "ipp"
+    pub fn service_label(&self) -> &str {
+        self.service_type
+            .split('.')
+            .next()
+            .map(|s| s.trim_start_matches('_'))
+            .unwrap_or(&self.service_type)
+    }
+
+    pub fn is_tcp(&self) -> bool {
+        self.service_type.contains("._tcp")
+    }
+}
+
+#[derive(Default)]
+struct MdnsRecordCache {
+    service_types: BTreeSet<String>,
+    // instance name => service type
+    instances: BTreeMap<String, String>,
+    // instance name => (target hostname, port)
+    srv_records: HashMap<String, (String, Port)>,
+    txt_records: HashMap<String, Vec<String>>,
+    // hostname => addresses
+    addresses: HashMap<String, BTreeSet<IpAddr>>,
+    // instance name => ip of the host, that answered with the SRV record
+    responders: HashMap<String, IpAddr>,
+}
+impl MdnsRecordCache {
+    fn add_message(&mut self, message: &DnsMessage, responder: IpAddr) {
+        for record in message.all_records() {
+            let name = record.name.trim_end_matches('.').to_owned();
+            match &record.data {
+                DnsRecordData::Ptr(s) if name == DNS_SD_SERVICES_QUERY => {
+                    self.service_types
+                        .insert(s.trim_end_matches('.').to_owned());
+                }
+                DnsRecordData::Ptr(s) if name.contains("._tcp") || name.contains("._udp") => {
+                    self.service_types.insert(name.clone());
+                    self.instances
+                        .insert(s.trim_end_matches('.').to_owned(), name);
+                }
+                DnsRecordData::Srv { port, target, .. } => {
+                    self.srv_records.insert(
+                        name.clone(),
+                        (target.trim_end_matches('.').to_owned(), *port),
+                    );
+                    self.responders.insert(name, responder);
+                }
+                DnsRecordData::Txt(s) => {
+                    self.txt_records
+                        .insert(name, s.iter().filter(|s| !s.is_empty()).cloned().collect());
+                }
+                // AAAA records are ignored on purpose, dual stack hosts would end up as two separate nodes
+                DnsRecordData::A(s) => {
+                    self.addresses
+                        .entry(name.to_lowercase())
+                        .or_default()
+                        .insert(IpAddr::V4(*s));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn resolve_services(&self) -> Vec<(IpAddr, MdnsService)> {
+        let mut resolved = vec![];
+        for (instance_name, service_type) in self.instances.iter() {
+            let Some((hostname, port)) = self.srv_records.get(instance_name) else {
+                debug!("mDNS instance {} has no SRV record", instance_name);
+                continue;
+            };
+
+            let service = MdnsService {
+                instance_name: instance_name.clone(),
+                service_type: service_type.clone(),
+                hostname: hostname.clone(),
+                port: *port,
+                txt_records: self
+                    .txt_records
+                    .get(instance_name)
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+
+            match self.addresses.get(&hostname.to_lowercase()) {
+                Some(addresses) if !addresses.is_empty() => {
+                    for address in addresses {
+                        resolved.push((*address, service.clone()));
+                    }
+                }
+                _ => {
+                    if let Some(responder) = self.responders.get(instance_name) {
+                        resolved.push((*responder, service));
+                    }
+                }
+            }
+        }
+
+        resolved
+    }
+}
+
+pub fn browse_mdns_services(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    response_wait_ms: u64,
+) {
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info("Initiating mDNS / DNS-SD service discovery.".to_owned()),
+    );
+
+    std::thread::spawn(move || {
+        let socket = match create_mdns_socket() {
+            Ok(s) => s,
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(format!("Unable to create mDNS socket => {:?}", e)),
+                );
+                return;
+            }
+        };
+        let response_wait = Duration::from_millis(response_wait_ms);
+        let mut cache = MdnsRecordCache::default();
+
+        // 1. Which service types are advertised on the network
+        query_and_collect(
+            &socket,
+            &mut cache,
+            &[(DNS_SD_SERVICES_QUERY.to_owned(), DNS_TYPE_PTR)],
+            response_wait,
+        );
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "mDNS found {} service types: {}",
+                cache.service_types.len(),
+                cache
+                    .service_types
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        );
+
+        // 2. Instances of every service type (responders usually attach SRV, TXT and A records right away)
+        let service_type_questions = cache
+            .service_types
+            .iter()
+            .map(|s| (s.clone(), DNS_TYPE_PTR))
+            .collect::<Vec<(String, DnsRecordType)>>();
+        query_and_collect(&socket, &mut cache, &service_type_questions, response_wait);
+
+        // 3. Ask for whatever is still missing
+        let mut missing_questions = vec![];
+        for instance_name in cache.instances.keys() {
+            if !cache.srv_records.contains_key(instance_name) {
+                missing_questions.push((instance_name.clone(), DNS_TYPE_SRV));
+            }
+            if !cache.txt_records.contains_key(instance_name) {
+                missing_questions.push((instance_name.clone(), DNS_TYPE_TXT));
+            }
+        }
+        if !missing_questions.is_empty() {
+            query_and_collect(&socket, &mut cache, &missing_questions, response_wait);
+        }
+        let missing_address_questions = cache
+            .srv_records
+            .values()
+            .filter(|s| !cache.addresses.contains_key(&s.0.to_lowercase()))
+            .map(|s| (s.0.clone(), DNS_TYPE_A))
+            .collect::<BTreeSet<(String, DnsRecordType)>>()
+            .into_iter()
+            .collect::<Vec<(String, DnsRecordType)>>();
+        if !missing_address_questions.is_empty() {
+            query_and_collect(
+                &socket,
+                &mut cache,
+                &missing_address_questions,
+                response_wait,
+            );
+        }
+
+        let resolved_services = cache.resolve_services();
+        let mut updated_hosts = BTreeSet::new();
+        for (ip, service) in resolved_services.iter() {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info(format!(
+                    "mDNS: {} advertises \"{}\" ({}) on port {}",
+                    ip,
+                    service.instance_label(),
+                    service.service_label(),
+                    service.port
+                )),
+            );
+            record_mdns_service(&mut graph_ref, *ip, service);
+            updated_hosts.insert(*ip);
+        }
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Finished mDNS / DNS-SD service discovery. Found {} services on {} hosts.",
+                resolved_services.len(),
+                updated_hosts.len()
+            )),
+        );
+    });
+}
+
+fn create_mdns_socket() -> anyhow::Result<UdpSocket> {
+    // Source port other than 5353 => responders answer with "legacy unicast" directly to us (RFC 6762, 6.7),
+    // so there's no need to join the multicast group (and fight with the OS's mDNS responder over the port).
+    let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0))?;
+    socket.set_read_timeout(Some(Duration::from_millis(MDNS_SOCKET_READ_TIMEOUT_MS)))?;
+    socket.set_multicast_ttl_v4(255)?;
+    Ok(socket)
+}
+
+fn query_and_collect(
+    socket: &UdpSocket,
+    cache: &mut MdnsRecordCache,
+    questions: &[(String, DnsRecordType)],
+    response_wait: Duration,
+) {
+    let destination = SocketAddr::new(IpAddr::V4(MDNS_MULTICAST_ADDR), MDNS_PORT);
+    for (name, record_type) in questions {
+        let query = match build_dns_query(
+            random::<u16>(),
+            name,
+            *record_type,
+            MDNS_CLASS_IN_UNICAST_RESPONSE,
+            false,
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                info!("Unable to build mDNS query for {}: {}", name, e);
+                continue;
+            }
+        };
+        if let Err(e) = socket.send_to(&query, destination) {
+            info!("Unable to send mDNS query for {}: {}", name, e);
+        }
+    }
+
+    let started_at = Instant::now();
+    let mut buffer = [0u8; 9000];
+    while started_at.elapsed() < response_wait {
+        let Ok((bytes_read, responder)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        match DnsMessage::parse(&buffer[..bytes_read]) {
+            Ok(message) if message.is_response() => cache.add_message(&message, responder.ip()),
+            Ok(_) => {}
+            Err(e) => debug!("Unable to parse mDNS response from {}: {}", responder, e),
+        }
+    }
+}
+
+fn record_mdns_service(graph_ref: &mut NetworkTopologyGraph, ip: IpAddr, service: &MdnsService) {
+    let now = chrono::Local::now();
+    let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(graph_ref, ip);
+    NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+        node.first_seen.get_or_insert(now);
+        node.last_seen = Some(now);
+        node.add_hostname(&service.hostname, HostnameSource::Mdns);
+
+        match node
+            .mdns_services
+            .iter_mut()
+            .find(|s| s.instance_name == service.instance_name)
+        {
+            Some(existing_service) => *existing_service = service.clone(),
+            None => node.mdns_services.push(service.clone()),
+        }
+
+        if service.is_tcp() && !node.opened_ports.iter().any(|s| s.number == service.port) {
+            node.opened_ports.push(PortInfo::new(
+                service.port,
+                None,
+                None,
+                service.service_label().to_owned(),
+                Some(format!(
+                    "Advertised via mDNS as \"{}\"",
+                    service.instance_label()
+                )),
+            ));
+            node.opened_ports.sort_by_key(|s| s.number);
+        }
+    });
+
+    // mDNS is link-local only, so whoever answered is on our link
+    NetworkTopology::ensure_edge_from_localhost(graph_ref, node_index);
+}
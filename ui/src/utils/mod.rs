@@ -1,7 +1,33 @@
+pub mod access_checks;
+pub mod bytes;
 pub mod constants;
+pub mod dns;
+pub mod dns_resolver;
+pub mod domain_recon;
 pub mod env;
 pub mod general;
+pub mod hostname_resolution;
 pub mod icmp;
+pub mod interfaces;
 pub mod ip;
+pub mod llmnr;
+pub mod localhost;
 pub mod logging;
+pub mod mdns;
+pub mod monitoring;
+pub mod ndp;
+pub mod neighbors;
+pub mod netbios;
+pub mod notifications;
+pub mod oui;
+pub mod passive;
 pub mod ports;
+pub mod proxy;
+pub mod routes;
+pub mod snapshots;
+pub mod snmp;
+pub mod ssdp;
+pub mod targets;
+#[cfg(test)]
+pub mod test_helpers;
+pub mod wol;
@@ -0,0 +1,456 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use pnet::packet::icmp::IcmpTypes;
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph, PortInfo},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    dns_resolver::{lookup_host, DnsResolverConfig},
+    icmp::send_icmp_echo_request_ping,
+    interfaces::SourceBinding,
+    ip::{recognize_port_service, Port, ScanIpPortsConfig},
+    notifications::send_desktop_notification,
+    ports::is_port_open_using_tcp_stream,
+    proxy::ProxyConfig,
+    snapshots::{record_scan_snapshot, ScanSnapshotsRef},
+    targets::{TargetExclusions, TargetExpressions},
+};
+
+pub const DEFAULT_MONITORING_INTERVAL_MIN: u64 = 15;
+pub const DEFAULT_MONITORING_PORTS: &str = "22, 80, 443, 445, 3389";
+const MONITORING_PING_CHECKUP_MS: u64 = 20;
+const MONITORING_STOP_CHECKUP_MS: u64 = 500;
+const MAX_MONITORING_HISTORY_LEN: usize = 500;
+const MAX_NOTIFIED_CHANGES: usize = 5;
+
+pub struct MonitoringConfig {
+    pub targets: TargetExpressions,
+    pub include_project_nodes: bool, // every node (except localhost) at the time of the run
+    pub ports: Vec<Port>,
+    pub interval: Duration,
+    pub ping_timeout_ms: u64,
+    pub connection_timeout_ms: u64,
+    pub should_notify: bool,
+    pub dns_resolver: DnsResolverConfig,
+    pub exclusions: TargetExclusions,
+    pub source: SourceBinding,
+    pub proxy: Option<ProxyConfig>, // Some => no pings, hosts are up when one of the ports is open
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitoringChange {
+    HostAppeared(IpAddr),
+    HostVanished(IpAddr),
+    PortOpened(IpAddr, Port),
+    PortClosed(IpAddr, Port),
+}
+impl Display for MonitoringChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HostAppeared(ip) => write!(f, "New host {} is up", ip),
+            Self::HostVanished(ip) => write!(f, "Host {} is gone", ip),
+            Self::PortOpened(ip, port) => write!(f, "Port {} opened on {}", port, ip),
+            Self::PortClosed(ip, port) => write!(f, "Port {} closed on {}", port, ip),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MonitoringAlert {
+    pub raised_at: DateTime<Local>,
+    pub change: MonitoringChange,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonitoringRunSummary {
+    pub finished_at: DateTime<Local>,
+    pub hosts_up: usize,
+    pub ports_open: usize,
+    pub changes: usize,
+}
+
+// Shared between the monitoring thread and the window
+#[derive(Debug, Default)]
+pub struct MonitoringHistory {
+    pub runs: Vec<MonitoringRunSummary>,
+    pub alerts: Vec<MonitoringAlert>,
+    pub unseen_alerts: usize,
+    pub next_run_at: Option<DateTime<Local>>, // None => run in progress
+}
+pub type MonitoringHistoryRef = Arc<Mutex<MonitoringHistory>>;
+
+// ip => open ports (of the monitored ones) of every host that was up
+type MonitoringObservation = BTreeMap<IpAddr, Vec<Port>>;
+
+// Returns flag, which stops the monitoring when set to true
+pub fn start_monitoring(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    scan_snapshots_ref: ScanSnapshotsRef,
+    history_ref: MonitoringHistoryRef,
+    config: MonitoringConfig,
+) -> Arc<AtomicBool> {
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let thread_should_stop = Arc::clone(&should_stop);
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info(format!(
+            "Started monitoring, every {} min.",
+            config.interval.as_secs() / 60
+        )),
+    );
+
+    thread::spawn(move || {
+        let mut previous_observation: Option<MonitoringObservation> = None;
+        let mut has_notification_failed = false;
+        while !thread_should_stop.load(Ordering::Relaxed) {
+            history_ref.lock().unwrap().next_run_at = None;
+            let observation = observe(
+                &mut graph_ref,
+                &status_info_ref,
+                &config,
+                &thread_should_stop,
+            );
+
+            if let Some((targets, observation)) = observation {
+                let changes = previous_observation
+                    .as_ref()
+                    .map(|s| compare_observations(s, &observation))
+                    .unwrap_or_default();
+                apply_observation(&mut graph_ref, &config, &observation, &changes);
+                record_scan_snapshot(
+                    &scan_snapshots_ref,
+                    &mut graph_ref,
+                    format!("Monitoring run ({} ports)", config.ports.len()),
+                    targets.into(),
+                    config.exclusions.clone(),
+                    config.ports.clone(),
+                    observation
+                        .iter()
+                        .map(|(ip, ports)| (*ip, ports.iter().map(to_port_info).collect()))
+                        .collect(),
+                );
+                report_changes(
+                    &status_info_ref,
+                    &history_ref,
+                    &observation,
+                    &changes,
+                    previous_observation.is_none(),
+                );
+                if config.should_notify && !changes.is_empty() && !has_notification_failed {
+                    if let Err(e) = notify_changes(&changes) {
+                        has_notification_failed = true;
+                        AppState::log_to_status_generic(
+                            &status_info_ref,
+                            StatusMessage::Warn(format!(
+                                "Unable to show desktop notification, alerts are only in the monitoring window => {}",
+                                e
+                            )),
+                        );
+                    }
+                }
+                previous_observation = Some(observation);
+            }
+
+            let next_run_at = Local::now()
+                + chrono::Duration::from_std(config.interval).unwrap_or(chrono::Duration::zero());
+            history_ref.lock().unwrap().next_run_at = Some(next_run_at);
+            while !thread_should_stop.load(Ordering::Relaxed) && Local::now() < next_run_at {
+                thread::sleep(Duration::from_millis(MONITORING_STOP_CHECKUP_MS));
+            }
+        }
+
+        history_ref.lock().unwrap().next_run_at = None;
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info("Stopped monitoring.".to_owned()),
+        );
+    });
+
+    should_stop
+}
+
+// => (every probed target, what answered). None => the run got stopped or couldn't be done at all, nothing to compare
+fn observe(
+    graph_ref: &mut NetworkTopologyGraph,
+    status_info_ref: &StatusInfoRef,
+    config: &MonitoringConfig,
+    should_stop: &AtomicBool,
+) -> Option<(Vec<IpAddr>, MonitoringObservation)> {
+    let mut targets = config
+        .targets
+        .targets
+        .clone()
+        .into_addresses()
+        .collect::<Vec<IpAddr>>();
+    for hostname in config.targets.hostnames.iter() {
+        match lookup_host(&config.dns_resolver, hostname) {
+            Ok(s) => targets.extend(s),
+            Err(e) => AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Warn(format!(
+                    "Monitoring: unable to resolve {} => {}",
+                    hostname, e
+                )),
+            ),
+        }
+    }
+    if config.include_project_nodes {
+        targets.extend(NetworkTopology::get_all_ips_except_localhost(graph_ref));
+    }
+    targets.sort();
+    targets.dedup();
+    targets.retain(|s| !config.exclusions.is_excluded(*s));
+    // Pings are IPv4 only, IPv6 hosts count as up when one of the ports is open (same as with a proxy)
+    if config.ports.is_empty() {
+        let targets_count = targets.len();
+        targets.retain(|s| s.is_ipv4());
+        if targets.len() < targets_count {
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Warn(format!(
+                    "Monitoring: skipping {} IPv6 targets, they can't be pinged and there are no ports to check.",
+                    targets_count - targets.len()
+                )),
+            );
+        }
+    }
+
+    let port_scan_config = ScanIpPortsConfig {
+        connection_timeout_ms: config.connection_timeout_ms,
+        should_banner_grab: false,
+        should_fuzz: false,
+        should_check_access: false,
+        read_write_timeout_ms: config.connection_timeout_ms,
+        exclusions: config.exclusions.clone(),
+        source: config.source.resolved(),
+        proxy: config.proxy.clone(),
+    };
+    if let Some(proxy) = config.proxy.as_ref() {
+        let timeout = Duration::from_millis(config.connection_timeout_ms);
+        if let Err(e) = proxy.ensure_reachable(timeout, &port_scan_config.source) {
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Err(format!(
+                    "Monitoring: unable to use {} proxy {}, skipping this run => {}",
+                    proxy.kind, proxy.address, e
+                )),
+            );
+            return None;
+        }
+    }
+
+    let mut observation = MonitoringObservation::new();
+    for ip in targets.iter().copied() {
+        if should_stop.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let should_ping = config.proxy.is_none() && ip.is_ipv4();
+        if should_ping {
+            match send_icmp_echo_request_ping(
+                ip,
+                config.ping_timeout_ms,
+                MONITORING_PING_CHECKUP_MS,
+                &port_scan_config.source,
+            ) {
+                Ok(Some(reply)) if reply.icmp_type == IcmpTypes::EchoReply => {
+                    NetworkTopology::record_ping(graph_ref, ip, Some(reply.rtt));
+                }
+                Ok(_) => {
+                    NetworkTopology::record_ping(graph_ref, ip, None);
+                    continue;
+                }
+                Err(e) => {
+                    AppState::log_to_status_generic(
+                        status_info_ref,
+                        StatusMessage::Err(format!(
+                            "Monitoring: unable to ping, skipping this run => {}",
+                            e
+                        )),
+                    );
+                    return None;
+                }
+            }
+        }
+
+        let open_ports = config
+            .ports
+            .iter()
+            .copied()
+            .filter(|s| is_port_open_using_tcp_stream(ip, *s, &port_scan_config).0)
+            .collect::<Vec<Port>>();
+        if !should_ping && open_ports.is_empty() {
+            continue;
+        }
+        observation.insert(ip, open_ports);
+    }
+    Some((targets, observation))
+}
+
+fn compare_observations(
+    previous: &MonitoringObservation,
+    current: &MonitoringObservation,
+) -> Vec<MonitoringChange> {
+    let mut changes = vec![];
+    for (ip, ports) in current.iter() {
+        match previous.get(ip) {
+            None => {
+                changes.push(MonitoringChange::HostAppeared(*ip));
+                changes.extend(ports.iter().map(|s| MonitoringChange::PortOpened(*ip, *s)));
+            }
+            Some(previous_ports) => {
+                changes.extend(
+                    ports
+                        .iter()
+                        .filter(|s| !previous_ports.contains(s))
+                        .map(|s| MonitoringChange::PortOpened(*ip, *s)),
+                );
+                changes.extend(
+                    previous_ports
+                        .iter()
+                        .filter(|s| !ports.contains(s))
+                        .map(|s| MonitoringChange::PortClosed(*ip, *s)),
+                );
+            }
+        }
+    }
+    changes.extend(
+        previous
+            .keys()
+            .filter(|s| !current.contains_key(s))
+            .map(|s| MonitoringChange::HostVanished(*s)),
+    );
+    changes
+}
+
+// Topology shows the latest state, the same way a manual refresh / port scan would
+fn apply_observation(
+    graph_ref: &mut NetworkTopologyGraph,
+    config: &MonitoringConfig,
+    observation: &MonitoringObservation,
+    changes: &[MonitoringChange],
+) {
+    let now = Local::now();
+    for (ip, open_ports) in observation.iter() {
+        let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(graph_ref, *ip);
+        NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+            node.first_seen.get_or_insert(now);
+            node.last_seen = Some(now);
+            // Only the monitored ports are known to be closed, the rest stays as it was
+            node.opened_ports
+                .retain(|s| !config.ports.contains(&s.number) || open_ports.contains(&s.number));
+            for port in open_ports {
+                if node.opened_ports.iter().any(|s| s.number == *port) {
+                    continue;
+                }
+                node.opened_ports.push(to_port_info(port));
+            }
+            node.opened_ports.sort_by_key(|s| s.number);
+        });
+        NetworkTopology::ensure_edge_from_localhost(graph_ref, node_index);
+    }
+
+    for change in changes {
+        let MonitoringChange::HostVanished(ip) = change else {
+            continue;
+        };
+        if let Some((node_index, _)) = NetworkTopology::get_node_by_ip(graph_ref, *ip) {
+            NetworkTopology::remove_edge_from_localhost(graph_ref, node_index);
+        }
+    }
+}
+
+// Monitoring only connects, so the service is a guess by the port number
+fn to_port_info(port: &Port) -> PortInfo {
+    let (service_name, service_info) = recognize_port_service(port, &None, &None);
+    PortInfo::new(*port, None, None, service_name, service_info)
+}
+
+fn report_changes(
+    status_info_ref: &StatusInfoRef,
+    history_ref: &MonitoringHistoryRef,
+    observation: &MonitoringObservation,
+    changes: &[MonitoringChange],
+    is_baseline: bool,
+) {
+    let now = Local::now();
+    for change in changes {
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Warn(format!("Monitoring: {}.", change)),
+        );
+    }
+    let ports_open = observation.values().map(|s| s.len()).sum::<usize>();
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "Monitoring run finished, {} hosts up, {} monitored ports open, {}.",
+            observation.len(),
+            ports_open,
+            if is_baseline {
+                "this is the baseline for the next runs".to_owned()
+            } else {
+                format!("{} changes", changes.len())
+            }
+        )),
+    );
+
+    let mut history = history_ref.lock().unwrap();
+    history.runs.push(MonitoringRunSummary {
+        finished_at: now,
+        hosts_up: observation.len(),
+        ports_open,
+        changes: changes.len(),
+    });
+    history
+        .alerts
+        .extend(changes.iter().map(|s| MonitoringAlert {
+            raised_at: now,
+            change: s.clone(),
+        }));
+    history.unseen_alerts += changes.len();
+    let runs_overflow = history
+        .runs
+        .len()
+        .saturating_sub(MAX_MONITORING_HISTORY_LEN);
+    history.runs.drain(..runs_overflow);
+    let alerts_overflow = history
+        .alerts
+        .len()
+        .saturating_sub(MAX_MONITORING_HISTORY_LEN);
+    history.alerts.drain(..alerts_overflow);
+}
+
+fn notify_changes(changes: &[MonitoringChange]) -> anyhow::Result<()> {
+    let mut body = changes
+        .iter()
+        .take(MAX_NOTIFIED_CHANGES)
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    if changes.len() > MAX_NOTIFIED_CHANGES {
+        body.push_str(&format!(
+            "\n... and {} more",
+            changes.len() - MAX_NOTIFIED_CHANGES
+        ));
+    }
+    send_desktop_notification(
+        &format!("{} network changes detected", changes.len()),
+        &body,
+    )
+}
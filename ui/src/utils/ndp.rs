@@ -0,0 +1,395 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::ErrorKind,
+    net::{IpAddr, Ipv6Addr},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use log::error;
+use pnet::{
+    datalink::{self, Channel, DataLinkReceiver, DataLinkSender, NetworkInterface},
+    packet::{
+        ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket},
+        icmpv6::{
+            checksum,
+            echo_reply::EchoReplyPacket,
+            echo_request::MutableEchoRequestPacket,
+            ndp::{
+                MutableNeighborSolicitPacket, NdpOption, NdpOptionTypes, NeighborAdvertPacket,
+                NeighborSolicitPacket,
+            },
+            Icmpv6Code, Icmpv6Packet, Icmpv6Types, MutableIcmpv6Packet,
+        },
+        ip::IpNextHeaderProtocols,
+        ipv6::{Ipv6Packet, MutableIpv6Packet},
+        Packet,
+    },
+    util::MacAddr,
+};
+use rand::random;
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::passive::{get_passive_discovery_interfaces, is_on_local_link};
+
+pub const DEFAULT_NEIGHBOR_DISCOVERY_LISTEN_MS: u64 = 3000;
+
+const NEIGHBOR_DISCOVERY_READ_TIMEOUT_MS: u64 = 100;
+const ALL_NODES_MULTICAST: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+const ALL_NODES_MULTICAST_MAC: MacAddr = MacAddr(0x33, 0x33, 0, 0, 0, 1);
+// Receivers drop neighbor discovery messages with any other hop limit (RFC 4861, 7.1.1)
+const NDP_HOP_LIMIT: u8 = 255;
+const ETHERNET_HEADER_SIZE: usize = 14;
+const IPV6_HEADER_SIZE: usize = 40;
+const ECHO_REQUEST_SIZE: usize = 16;
+// 24 bytes of header + source link-layer address option
+const NEIGHBOR_SOLICIT_SIZE: usize = 32;
+
+pub struct Ipv6NeighborDiscoveryConfig {
+    pub interface_name: Option<String>, // None => every interface with an ipv6 address
+    pub listen_ms: u64,
+}
+
+pub fn get_ipv6_discovery_interfaces() -> Vec<NetworkInterface> {
+    get_passive_discovery_interfaces()
+        .into_iter()
+        .filter(|s| s.mac.is_some() && s.ips.iter().any(|s| s.is_ipv6()))
+        .collect()
+}
+
+// Enumerating a /64 is hopeless, so we ask the link instead: everyone answers a ping to ff02::1,
+// and the hosts ignoring multicast pings (windows) still answer neighbor solicitations.
+pub fn start_ipv6_neighbor_discovery(
+    graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    config: Ipv6NeighborDiscoveryConfig,
+) -> anyhow::Result<()> {
+    let interfaces = get_ipv6_discovery_interfaces()
+        .into_iter()
+        .filter(|s| {
+            config
+                .interface_name
+                .as_ref()
+                .is_none_or(|name| s.name == *name)
+        })
+        .collect::<Vec<NetworkInterface>>();
+    if interfaces.is_empty() {
+        return Err(anyhow!("No usable interface with an IPv6 address found."));
+    }
+
+    // Opening the channels outside of the threads, so permission errors can be shown right away
+    let datalink_config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(NEIGHBOR_DISCOVERY_READ_TIMEOUT_MS)),
+        ..Default::default()
+    };
+    let mut channels = vec![];
+    for interface in interfaces {
+        match datalink::channel(&interface, datalink_config)? {
+            Channel::Ethernet(tx, rx) => channels.push((interface, tx, rx)),
+            _ => return Err(anyhow!("Unsupported channel type for {}.", interface.name)),
+        }
+    }
+
+    for (interface, tx, rx) in channels {
+        let mut graph_ref = graph_ref.clone();
+        let status_info_ref = status_info_ref.clone();
+        let listen_for = Duration::from_millis(config.listen_ms);
+        std::thread::spawn(move || {
+            run_neighbor_discovery(
+                &mut graph_ref,
+                &status_info_ref,
+                &interface,
+                tx,
+                rx,
+                listen_for,
+            )
+        });
+    }
+    Ok(())
+}
+
+fn run_neighbor_discovery(
+    graph_ref: &mut NetworkTopologyGraph,
+    status_info_ref: &StatusInfoRef,
+    interface: &NetworkInterface,
+    mut tx: Box<dyn DataLinkSender>,
+    mut rx: Box<dyn DataLinkReceiver>,
+    listen_for: Duration,
+) {
+    let Some(interface_mac) = interface.mac else {
+        return;
+    };
+    let my_addresses = interface
+        .ips
+        .iter()
+        .filter_map(|s| match s.ip() {
+            IpAddr::V6(s) => Some(s),
+            IpAddr::V4(_) => None,
+        })
+        .collect::<Vec<Ipv6Addr>>();
+    let Some(solicitation_source) = my_addresses
+        .iter()
+        .find(|s| is_link_local(s))
+        .or(my_addresses.first())
+        .copied()
+    else {
+        return;
+    };
+
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "Initiating IPv6 neighbor discovery on {}.",
+            interface.name
+        )),
+    );
+
+    // Once from every address, hosts answer from the address of the same scope => link-local and global ones
+    let identifier = random::<u16>();
+    for source in my_addresses.iter() {
+        let frame = build_echo_request_frame(interface_mac, *source, identifier);
+        if let Some(Err(e)) = tx.send_to(&frame, None) {
+            AppState::log_to_status_generic(
+                status_info_ref,
+                StatusMessage::Err(format!(
+                    "Unable to ping {} from {} => {}",
+                    ALL_NODES_MULTICAST, source, e
+                )),
+            );
+        }
+    }
+
+    // Addresses we already know about on this link get solicited as well
+    let mut solicitation_targets = NetworkTopology::get_all_ips_except_localhost(graph_ref)
+        .into_iter()
+        .filter(|s| is_on_local_link(interface, *s))
+        .filter_map(|s| match s {
+            IpAddr::V6(s) => Some(s),
+            IpAddr::V4(_) => None,
+        })
+        .collect::<BTreeSet<Ipv6Addr>>();
+
+    let started_at = Instant::now();
+    let mut has_solicited = false;
+    let mut found_neighbors: BTreeMap<Ipv6Addr, MacAddr> = BTreeMap::new();
+    while started_at.elapsed() < listen_for {
+        // Half of the time goes to the ping responses, the rest to the solicitations
+        if !has_solicited && started_at.elapsed() >= listen_for / 2 {
+            has_solicited = true;
+            solicitation_targets.extend(found_neighbors.keys());
+            for target in solicitation_targets.iter() {
+                let frame =
+                    build_neighbor_solicit_frame(interface_mac, solicitation_source, *target);
+                let _ = tx.send_to(&frame, None);
+            }
+        }
+
+        let frame = match rx.next() {
+            Ok(frame) => frame,
+            Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+            Err(e) => {
+                error!(
+                    "An error occurred while reading on {}: {}",
+                    interface.name, e
+                );
+                AppState::log_to_status_generic(
+                    status_info_ref,
+                    StatusMessage::Err(format!(
+                        "IPv6 neighbor discovery on {} failed, stopping => {}",
+                        interface.name, e
+                    )),
+                );
+                break;
+            }
+        };
+
+        let Some((ip, mac, via)) = parse_neighbor_response(frame, identifier) else {
+            continue;
+        };
+        if ip.is_unspecified() || ip.is_multicast() || my_addresses.contains(&ip) {
+            continue;
+        }
+        if found_neighbors.insert(ip, mac).is_some() {
+            continue;
+        }
+
+        let now = chrono::Local::now();
+        let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(graph_ref, IpAddr::V6(ip));
+        NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+            node.first_seen.get_or_insert(now);
+            node.last_seen = Some(now);
+            node.mac = Some(mac);
+        });
+        NetworkTopology::ensure_edge_from_localhost(graph_ref, node_index);
+        AppState::log_to_status_generic(
+            status_info_ref,
+            StatusMessage::Info(format!("{} ({}) is reachable (via {})", ip, mac, via)),
+        );
+    }
+
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "Finished IPv6 neighbor discovery on {}. Found {} neighbors.",
+            interface.name,
+            found_neighbors.len()
+        )),
+    );
+}
+
+fn is_link_local(ip: &Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xffc0) == 0xfe80
+}
+
+// (ip, mac, what gave it away)
+fn parse_neighbor_response(
+    frame: &[u8],
+    identifier: u16,
+) -> Option<(Ipv6Addr, MacAddr, &'static str)> {
+    let ethernet = EthernetPacket::new(frame)?;
+    if ethernet.get_ethertype() != EtherTypes::Ipv6 {
+        return None;
+    }
+    let ipv6 = Ipv6Packet::new(ethernet.payload())?;
+    if ipv6.get_next_header() != IpNextHeaderProtocols::Icmpv6 {
+        return None;
+    }
+    let icmpv6 = Icmpv6Packet::new(ipv6.payload())?;
+
+    match icmpv6.get_icmpv6_type() {
+        Icmpv6Types::EchoReply => {
+            let echo_reply = EchoReplyPacket::new(ipv6.payload())?;
+            (echo_reply.get_identifier() == identifier)
+                .then(|| (ipv6.get_source(), ethernet.get_source(), "echo reply"))
+        }
+        Icmpv6Types::NeighborAdvert => {
+            let advert = NeighborAdvertPacket::new(ipv6.payload())?;
+            let mac = advert
+                .get_options()
+                .iter()
+                .find(|s| s.option_type == NdpOptionTypes::TargetLLAddr && s.data.len() >= 6)
+                .map(|s| {
+                    MacAddr::new(
+                        s.data[0], s.data[1], s.data[2], s.data[3], s.data[4], s.data[5],
+                    )
+                })
+                .unwrap_or(ethernet.get_source());
+            Some((advert.get_target_addr(), mac, "neighbor advertisement"))
+        }
+        // Hosts answering our ping have to resolve our mac first
+        Icmpv6Types::NeighborSolicit => {
+            NeighborSolicitPacket::new(ipv6.payload())?;
+            Some((
+                ipv6.get_source(),
+                ethernet.get_source(),
+                "neighbor solicitation",
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn build_echo_request_frame(source_mac: MacAddr, source: Ipv6Addr, identifier: u16) -> Vec<u8> {
+    let mut icmpv6 = vec![0u8; ECHO_REQUEST_SIZE];
+    {
+        let mut echo_request = MutableEchoRequestPacket::new(&mut icmpv6).unwrap();
+        echo_request.set_icmpv6_type(Icmpv6Types::EchoRequest);
+        echo_request.set_icmpv6_code(Icmpv6Code(0));
+        echo_request.set_identifier(identifier);
+        echo_request.set_sequence_number(1);
+        echo_request.set_payload(&random::<[u8; ECHO_REQUEST_SIZE - 8]>());
+    }
+    build_icmpv6_frame(
+        source_mac,
+        ALL_NODES_MULTICAST_MAC,
+        source,
+        ALL_NODES_MULTICAST,
+        icmpv6,
+    )
+}
+
+// Sent to the solicited-node multicast address of the target, as the target's mac is what we don't know yet
+fn build_neighbor_solicit_frame(
+    source_mac: MacAddr,
+    source: Ipv6Addr,
+    target: Ipv6Addr,
+) -> Vec<u8> {
+    let target_octets = target.octets();
+    let destination = Ipv6Addr::from([
+        0xff,
+        0x02,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0x01,
+        0xff,
+        target_octets[13],
+        target_octets[14],
+        target_octets[15],
+    ]);
+    let destination_mac = MacAddr::new(
+        0x33,
+        0x33,
+        0xff,
+        target_octets[13],
+        target_octets[14],
+        target_octets[15],
+    );
+
+    let mut icmpv6 = vec![0u8; NEIGHBOR_SOLICIT_SIZE];
+    {
+        let mut solicit = MutableNeighborSolicitPacket::new(&mut icmpv6).unwrap();
+        solicit.set_icmpv6_type(Icmpv6Types::NeighborSolicit);
+        solicit.set_icmpv6_code(Icmpv6Code(0));
+        solicit.set_target_addr(target);
+        solicit.set_options(&[NdpOption {
+            option_type: NdpOptionTypes::SourceLLAddr,
+            length: 1,
+            data: source_mac.octets().to_vec(),
+        }]);
+    }
+    build_icmpv6_frame(source_mac, destination_mac, source, destination, icmpv6)
+}
+
+fn build_icmpv6_frame(
+    source_mac: MacAddr,
+    destination_mac: MacAddr,
+    source: Ipv6Addr,
+    destination: Ipv6Addr,
+    mut icmpv6: Vec<u8>,
+) -> Vec<u8> {
+    {
+        let mut icmpv6_packet = MutableIcmpv6Packet::new(&mut icmpv6).unwrap();
+        let icmpv6_checksum = checksum(&icmpv6_packet.to_immutable(), &source, &destination);
+        icmpv6_packet.set_checksum(icmpv6_checksum);
+    }
+
+    let mut frame = vec![0u8; ETHERNET_HEADER_SIZE + IPV6_HEADER_SIZE + icmpv6.len()];
+    {
+        let mut ethernet = MutableEthernetPacket::new(&mut frame).unwrap();
+        ethernet.set_source(source_mac);
+        ethernet.set_destination(destination_mac);
+        ethernet.set_ethertype(EtherTypes::Ipv6);
+    }
+    {
+        let mut ipv6 = MutableIpv6Packet::new(&mut frame[ETHERNET_HEADER_SIZE..]).unwrap();
+        ipv6.set_version(6);
+        ipv6.set_payload_length(icmpv6.len() as u16);
+        ipv6.set_next_header(IpNextHeaderProtocols::Icmpv6);
+        ipv6.set_hop_limit(NDP_HOP_LIMIT);
+        ipv6.set_source(source);
+        ipv6.set_destination(destination);
+        ipv6.set_payload(&icmpv6);
+    }
+    frame
+}
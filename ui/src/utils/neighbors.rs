@@ -0,0 +1,282 @@
+use std::{
+    io::Read,
+    net::{IpAddr, Ipv6Addr},
+    str::FromStr,
+    time::Duration,
+};
+
+use pnet::{datalink, util::MacAddr};
+use socket2::{Domain, Protocol, Socket, Type};
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+// <linux/if_arp.h>
+const ATF_COM: u32 = 0x02;
+const ATF_PERM: u32 = 0x04;
+
+// <linux/netlink.h>, <linux/rtnetlink.h>, <linux/neighbour.h>
+const AF_NETLINK: i32 = 16;
+const AF_INET6: u8 = 10;
+const NETLINK_ROUTE: i32 = 0;
+const NLMSG_HEADER_LEN: usize = 16;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 0x0001;
+const NLM_F_DUMP: u16 = 0x0300;
+const RTM_NEWNEIGH: u16 = 28;
+const RTM_GETNEIGH: u16 = 30;
+const NDMSG_LEN: usize = 12;
+const NDA_LLADDR: u16 = 2;
+const NDA_DST: u16 = 1;
+const NUD_INCOMPLETE: u16 = 0x01;
+const NUD_REACHABLE: u16 = 0x02;
+const NUD_STALE: u16 = 0x04;
+const NUD_DELAY: u16 = 0x08;
+const NUD_PROBE: u16 = 0x10;
+const NUD_FAILED: u16 = 0x20;
+const NUD_NOARP: u16 = 0x40;
+const NUD_PERMANENT: u16 = 0x80;
+const NETLINK_READ_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Debug, Clone)]
+pub struct NeighborEntry {
+    pub ip: IpAddr,
+    pub mac: MacAddr,
+    pub interface_name: String,
+    pub state: &'static str,
+}
+
+// Reads what the kernel already knows, doesn't send a single packet
+pub fn seed_from_neighbor_cache(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+) {
+    let mut entries = vec![];
+    match read_arp_cache() {
+        Ok(s) => entries.extend(s),
+        Err(e) => AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Err(format!("Unable to read the ARP cache => {}", e)),
+        ),
+    }
+    match read_ipv6_neighbor_cache() {
+        Ok(s) => entries.extend(s),
+        Err(e) => AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Warn(format!("Unable to read the IPv6 neighbor cache => {}", e)),
+        ),
+    }
+
+    let mut new_nodes_count = 0;
+    for entry in entries.iter() {
+        let (node_index, is_new) = NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, entry.ip);
+        NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+            node.mac = Some(entry.mac);
+        });
+        NetworkTopology::ensure_edge_from_localhost(&mut graph_ref, node_index);
+        if is_new {
+            new_nodes_count += 1;
+        }
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "{} ({}) is a neighbor on {} ({})",
+                entry.ip, entry.mac, entry.interface_name, entry.state
+            )),
+        );
+    }
+
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info(format!(
+            "Found {} entries in the neighbor cache, {} of them are new nodes.",
+            entries.len(),
+            new_nodes_count
+        )),
+    );
+}
+
+// IP address | HW type | Flags | HW address | Mask | Device
+#[cfg(target_os = "linux")]
+fn read_arp_cache() -> anyhow::Result<Vec<NeighborEntry>> {
+    Ok(parse_proc_net_arp(&std::fs::read_to_string(
+        "/proc/net/arp",
+    )?))
+}
+#[cfg(not(target_os = "linux"))]
+fn read_arp_cache() -> anyhow::Result<Vec<NeighborEntry>> {
+    Err(anyhow::anyhow!(
+        "Reading the ARP cache is only supported on linux atm."
+    ))
+}
+
+fn parse_proc_net_arp(content: &str) -> Vec<NeighborEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<&str>>();
+            let flags = u32::from_str_radix(columns.get(2)?.trim_start_matches("0x"), 16).ok()?;
+            let mac = MacAddr::from_str(columns.get(3)?).ok()?;
+            if flags & ATF_COM == 0 || mac == MacAddr::zero() {
+                return None;
+            }
+
+            Some(NeighborEntry {
+                ip: IpAddr::from_str(columns.first()?).ok()?,
+                mac,
+                interface_name: columns.get(5)?.to_string(),
+                state: if flags & ATF_PERM != 0 {
+                    "permanent"
+                } else {
+                    "complete"
+                },
+            })
+        })
+        .collect()
+}
+
+// There's no /proc file for IPv6 neighbors, so we dump them over rtnetlink (same as `ip -6 neigh`)
+#[cfg(target_os = "linux")]
+fn read_ipv6_neighbor_cache() -> anyhow::Result<Vec<NeighborEntry>> {
+    let socket = Socket::new(
+        Domain::from(AF_NETLINK),
+        Type::RAW,
+        Some(Protocol::from(NETLINK_ROUTE)),
+    )?;
+    socket.set_read_timeout(Some(Duration::from_millis(NETLINK_READ_TIMEOUT_MS)))?;
+
+    let mut request = vec![];
+    request.extend_from_slice(&((NLMSG_HEADER_LEN + NDMSG_LEN) as u32).to_ne_bytes());
+    request.extend_from_slice(&RTM_GETNEIGH.to_ne_bytes());
+    request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+    request.extend_from_slice(&1u32.to_ne_bytes()); // sequence number
+    request.extend_from_slice(&0u32.to_ne_bytes()); // port id, 0 => kernel
+    request.push(AF_INET6);
+    request.extend_from_slice(&[0; NDMSG_LEN - 1]);
+    // Unconnected netlink sockets send to the kernel by default
+    socket.send(&request)?;
+
+    let interface_names = datalink::interfaces()
+        .into_iter()
+        .map(|s| (s.index, s.name))
+        .collect::<Vec<(u32, String)>>();
+    let mut entries = vec![];
+    let mut buffer = vec![0u8; 32 * 1024];
+    loop {
+        let received = (&socket).read(&mut buffer)?;
+        let mut offset = 0;
+        while offset + NLMSG_HEADER_LEN <= received {
+            let message_len = read_u32(&buffer, offset) as usize;
+            let message_type = read_u16(&buffer, offset + 4);
+            if message_len < NLMSG_HEADER_LEN || offset + message_len > received {
+                return Err(anyhow::anyhow!("kernel sent a malformed netlink message"));
+            }
+
+            match message_type {
+                NLMSG_DONE => return Ok(entries),
+                NLMSG_ERROR => {
+                    let errno = -(read_u32(&buffer, offset + NLMSG_HEADER_LEN) as i32);
+                    return Err(std::io::Error::from_raw_os_error(errno).into());
+                }
+                RTM_NEWNEIGH => {
+                    let message = &buffer[offset + NLMSG_HEADER_LEN..offset + message_len];
+                    if let Some(s) = parse_neighbor_message(message, &interface_names) {
+                        entries.push(s);
+                    }
+                }
+                _ => {}
+            }
+            offset += align_to_4(message_len);
+        }
+    }
+}
+#[cfg(not(target_os = "linux"))]
+fn read_ipv6_neighbor_cache() -> anyhow::Result<Vec<NeighborEntry>> {
+    Err(anyhow::anyhow!(
+        "Reading the IPv6 neighbor cache is only supported on linux atm."
+    ))
+}
+
+// ndmsg (family, pad, pad, ifindex, state, flags, type) followed by rtattrs
+fn parse_neighbor_message(
+    message: &[u8],
+    interface_names: &[(u32, String)],
+) -> Option<NeighborEntry> {
+    if message.len() < NDMSG_LEN || message[0] != AF_INET6 {
+        return None;
+    }
+    let interface_index = read_u32(message, 4);
+    let state = read_u16(message, 8);
+    if state & (NUD_INCOMPLETE | NUD_FAILED | NUD_NOARP) != 0 {
+        return None;
+    }
+
+    let mut ip = None;
+    let mut mac = None;
+    let mut offset = NDMSG_LEN;
+    while offset + 4 <= message.len() {
+        let attribute_len = read_u16(message, offset) as usize;
+        let attribute_type = read_u16(message, offset + 2);
+        if attribute_len < 4 || offset + attribute_len > message.len() {
+            break;
+        }
+        let data = &message[offset + 4..offset + attribute_len];
+        match (attribute_type, data.len()) {
+            (NDA_DST, 16) => ip = Some(Ipv6Addr::from(<[u8; 16]>::try_from(data).ok()?)),
+            (NDA_LLADDR, 6) => {
+                mac = Some(MacAddr::new(
+                    data[0], data[1], data[2], data[3], data[4], data[5],
+                ))
+            }
+            _ => {}
+        }
+        offset += align_to_4(attribute_len);
+    }
+
+    let ip = ip?;
+    if ip.is_multicast() || ip.is_unspecified() {
+        return None;
+    }
+    Some(NeighborEntry {
+        ip: IpAddr::V6(ip),
+        mac: mac?,
+        interface_name: interface_names
+            .iter()
+            .find(|s| s.0 == interface_index)
+            .map(|s| s.1.clone())
+            .unwrap_or(interface_index.to_string()),
+        state: describe_neighbor_state(state),
+    })
+}
+
+fn describe_neighbor_state(state: u16) -> &'static str {
+    match state {
+        s if s & NUD_PERMANENT != 0 => "permanent",
+        s if s & NUD_REACHABLE != 0 => "reachable",
+        s if s & NUD_STALE != 0 => "stale",
+        s if s & NUD_DELAY != 0 => "delay",
+        s if s & NUD_PROBE != 0 => "probe",
+        _ => "unknown",
+    }
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([buffer[offset], buffer[offset + 1]])
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([
+        buffer[offset],
+        buffer[offset + 1],
+        buffer[offset + 2],
+        buffer[offset + 3],
+    ])
+}
+
+fn align_to_4(len: usize) -> usize {
+    (len + 3) & !3
+}
@@ -0,0 +1,183 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use pnet::util::MacAddr;
+use rand::random;
+
+use super::{
+    dns::{build_dns_query, read_name, read_u16, DNS_CLASS_IN, DNS_HEADER_SIZE},
+    interfaces::SourceBinding,
+};
+
+pub const NETBIOS_NAME_SERVICE_PORT: u16 = 137;
+pub const NETBIOS_RECORD_TYPE_NB: u16 = 0x20;
+pub const NETBIOS_RECORD_TYPE_NBSTAT: u16 = 0x21;
+
+const NETBIOS_NAME_LENGTH: usize = 16;
+const NETBIOS_ENCODED_NAME_LENGTH: usize = NETBIOS_NAME_LENGTH * 2;
+// 15 bytes of name + suffix + 2 bytes of flags
+const NETBIOS_NODE_STATUS_NAME_SIZE: usize = NETBIOS_NAME_LENGTH + 2;
+const NETBIOS_GROUP_NAME_FLAG: u16 = 0x8000;
+
+const NETBIOS_SUFFIX_WORKSTATION: u8 = 0x00;
+const NETBIOS_SUFFIX_DOMAIN_CONTROLLERS: u8 = 0x1c;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetBiosName {
+    pub name: String,
+    pub suffix: u8,
+    pub is_group: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetBiosInfo {
+    pub computer_name: Option<String>,
+    pub workgroup: Option<String>, // workgroup or domain, NetBIOS can't tell the difference
+    pub is_domain_controller: bool,
+    pub mac: Option<MacAddr>,
+    pub names: Vec<NetBiosName>,
+}
+
+// Sends node status request (NBSTAT) for wildcard name => host answers with all its registered names + mac
+pub fn query_netbios_node_status(
+    ip: IpAddr,
+    timeout: Duration,
+    source: &SourceBinding,
+) -> anyhow::Result<NetBiosInfo> {
+    if ip.is_ipv6() {
+        return Err(anyhow!("NetBIOS does not support IPv6"));
+    }
+    let socket = source.bind_udp(ip)?;
+    socket.set_read_timeout(Some(timeout))?;
+
+    let transaction_id = random::<u16>();
+    let query = build_dns_query(
+        transaction_id,
+        &encode_netbios_name("*", NETBIOS_SUFFIX_WORKSTATION),
+        NETBIOS_RECORD_TYPE_NBSTAT,
+        DNS_CLASS_IN,
+        false,
+    )?;
+    socket.send_to(&query, SocketAddr::new(ip, NETBIOS_NAME_SERVICE_PORT))?;
+
+    let mut buffer = [0u8; 2048];
+    loop {
+        let (bytes_read, responder) = socket.recv_from(&mut buffer)?;
+        if responder.ip() != ip || read_u16(&buffer[..bytes_read], 0)? != transaction_id {
+            continue;
+        }
+        return parse_node_status_response(&buffer[..bytes_read]);
+    }
+}
+
+fn parse_node_status_response(response: &[u8]) -> anyhow::Result<NetBiosInfo> {
+    let answer_count = read_u16(response, 6)?;
+    if answer_count == 0 {
+        return Err(anyhow!("NetBIOS node status response contains no answers"));
+    }
+
+    let mut offset = DNS_HEADER_SIZE;
+    read_name(response, &mut offset)?;
+    if read_u16(response, offset)? != NETBIOS_RECORD_TYPE_NBSTAT {
+        return Err(anyhow!(
+            "Unexpected record type in NetBIOS node status response"
+        ));
+    }
+    offset += 10; // type, class, ttl, data length
+
+    let name_count = *response
+        .get(offset)
+        .ok_or_else(|| anyhow!("NetBIOS node status response is truncated"))?
+        as usize;
+    offset += 1;
+
+    let mut names = vec![];
+    for _ in 0..name_count {
+        let entry = response
+            .get(offset..offset + NETBIOS_NODE_STATUS_NAME_SIZE)
+            .ok_or_else(|| anyhow!("NetBIOS node status response is truncated"))?;
+        let flags = u16::from_be_bytes([entry[16], entry[17]]);
+        names.push(NetBiosName {
+            name: decode_netbios_raw_name(&entry[..NETBIOS_NAME_LENGTH - 1]),
+            suffix: entry[NETBIOS_NAME_LENGTH - 1],
+            is_group: flags & NETBIOS_GROUP_NAME_FLAG != 0,
+        });
+        offset += NETBIOS_NODE_STATUS_NAME_SIZE;
+    }
+
+    // Unit id => mac address, Samba sends zeros
+    let mac = response
+        .get(offset..offset + 6)
+        .map(|s| MacAddr::new(s[0], s[1], s[2], s[3], s[4], s[5]))
+        .filter(|s| !s.is_zero());
+
+    Ok(NetBiosInfo {
+        computer_name: names
+            .iter()
+            .find(|s| !s.is_group && s.suffix == NETBIOS_SUFFIX_WORKSTATION)
+            .map(|s| s.name.clone()),
+        workgroup: names
+            .iter()
+            .find(|s| s.is_group && s.suffix == NETBIOS_SUFFIX_WORKSTATION)
+            .map(|s| s.name.clone()),
+        is_domain_controller: names
+            .iter()
+            .any(|s| s.is_group && s.suffix == NETBIOS_SUFFIX_DOMAIN_CONTROLLERS),
+        mac,
+        names,
+    })
+}
+
+// First level encoding (RFC 1001, 14.1) => every nibble is stored as 'A' + nibble.
+// Names are padded with spaces to 15 characters, the 16th byte is the suffix (service type).
+fn encode_netbios_name(name: &str, suffix: u8) -> String {
+    let mut raw_name = [b' '; NETBIOS_NAME_LENGTH];
+    for (i, s) in name
+        .to_uppercase()
+        .bytes()
+        .take(NETBIOS_NAME_LENGTH - 1)
+        .enumerate()
+    {
+        raw_name[i] = s;
+    }
+    // Wildcard name is padded with zeros instead of spaces
+    if name == "*" {
+        raw_name[1..].fill(0);
+    }
+    raw_name[NETBIOS_NAME_LENGTH - 1] = suffix;
+
+    raw_name
+        .iter()
+        .flat_map(|s| [(b'A' + (s >> 4)) as char, (b'A' + (s & 0x0f)) as char])
+        .collect()
+}
+
+// Returns (trimmed name, suffix), expects only the first label of the DNS-like name
+pub fn decode_netbios_name(encoded_name: &str) -> Option<(String, u8)> {
+    let encoded_name = encoded_name.split('.').next()?.as_bytes();
+    if encoded_name.len() != NETBIOS_ENCODED_NAME_LENGTH {
+        return None;
+    }
+
+    let mut raw_name = Vec::with_capacity(NETBIOS_NAME_LENGTH);
+    for pair in encoded_name.chunks(2) {
+        let high = pair[0].checked_sub(b'A')?;
+        let low = pair[1].checked_sub(b'A')?;
+        if high > 0x0f || low > 0x0f {
+            return None;
+        }
+        raw_name.push((high << 4) | low);
+    }
+
+    let suffix = raw_name.pop()?;
+    Some((decode_netbios_raw_name(&raw_name), suffix))
+}
+
+pub fn decode_netbios_raw_name(raw_name: &[u8]) -> String {
+    String::from_utf8_lossy(raw_name)
+        .trim_end_matches([' ', '\0'])
+        .to_string()
+}
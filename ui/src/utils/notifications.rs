@@ -0,0 +1,59 @@
+use std::process::{Command, Stdio};
+
+const NOTIFICATION_APP_NAME: &str = "Naive network scanner";
+
+// Goes through whatever the OS ships with, so it's best effort (e.g. notify-send might not be installed)
+pub fn send_desktop_notification(title: &str, body: &str) -> anyhow::Result<()> {
+    let mut command = desktop_notification_command(title, body);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Not waiting for it here, the windows one sleeps until the balloon is gone. Still has to be reaped, or it stays around as a zombie.
+    let mut child = command.spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// Linux and the BSDs (libnotify)
+#[cfg(not(any(target_os = "macos", windows)))]
+fn desktop_notification_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command
+        .arg(format!("--app-name={}", NOTIFICATION_APP_NAME))
+        .arg(title)
+        .arg(body);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn desktop_notification_command(title: &str, body: &str) -> Command {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "display notification \"{}\" with title \"{}\" subtitle \"{}\"",
+        escape(body),
+        NOTIFICATION_APP_NAME,
+        escape(title)
+    ));
+    command
+}
+
+#[cfg(windows)]
+fn desktop_notification_command(title: &str, body: &str) -> Command {
+    let escape = |s: &str| s.replace('\'', "''");
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command"])
+        .arg(format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; \
+         $n.Text = '{}'; $n.BalloonTipTitle = '{}'; $n.BalloonTipText = '{}'; $n.Visible = $true; \
+         $n.ShowBalloonTip(10000); Start-Sleep -Seconds 10; $n.Dispose()",
+            NOTIFICATION_APP_NAME,
+            escape(title),
+            escape(body)
+        ));
+    command
+}
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, path::Path, sync::RwLock};
+
+use pnet::util::MacAddr;
+
+use super::env::program_root_dir;
+
+const CUSTOM_OUI_TABLE_FILE_NAME: &str = "oui.txt";
+
+lazy_static! {
+    // First 3 bytes of a MAC => vendor, bundled table + whatever got loaded from a file
+    static ref OUI_TABLE: RwLock<HashMap<[u8; 3], String>> = RwLock::new(load_initial_oui_table());
+}
+
+// Small subset of the IEEE MA-L registry, the usual suspects on home / office networks.
+// The full one (oui.txt / oui.csv from standards-oui.ieee.org, wireshark's manuf, nmap-mac-prefixes) can be loaded on top.
+static BUNDLED_OUI_TABLE: &[(&str, &str)] = &[
+    ("00:00:0C", "Cisco Systems"),
+    ("00:00:48", "Seiko Epson"),
+    ("00:00:5E", "IANA (VRRP / multicast)"),
+    ("00:00:74", "Ricoh"),
+    ("00:00:85", "Canon"),
+    ("00:00:AA", "Xerox"),
+    ("00:03:93", "Apple"),
+    ("00:04:4B", "NVIDIA"),
+    ("00:05:69", "VMware"),
+    ("00:06:25", "Linksys"),
+    ("00:08:9B", "QNAP Systems"),
+    ("00:09:0F", "Fortinet"),
+    ("00:09:BF", "Nintendo"),
+    ("00:0A:95", "Apple"),
+    ("00:0A:EB", "TP-Link"),
+    ("00:0B:86", "Aruba Networks"),
+    ("00:0C:29", "VMware"),
+    ("00:0C:42", "MikroTik (Routerboard)"),
+    ("00:0D:3A", "Microsoft"),
+    ("00:0D:B9", "PC Engines"),
+    ("00:0E:58", "Sonos"),
+    ("00:0F:E2", "H3C Technologies"),
+    ("00:10:18", "Broadcom"),
+    ("00:11:24", "Apple"),
+    ("00:11:32", "Synology"),
+    ("00:12:17", "Cisco-Linksys"),
+    ("00:14:22", "Dell"),
+    ("00:14:6C", "Netgear"),
+    ("00:14:BF", "Cisco-Linksys"),
+    ("00:15:5D", "Microsoft (Hyper-V)"),
+    ("00:16:3E", "Xen"),
+    ("00:16:CB", "Apple"),
+    ("00:17:88", "Philips Lighting (Hue)"),
+    ("00:17:F2", "Apple"),
+    ("00:18:0A", "Cisco Meraki"),
+    ("00:18:82", "Huawei"),
+    ("00:1A:11", "Google"),
+    ("00:1A:A0", "Dell"),
+    ("00:1B:17", "Palo Alto Networks"),
+    ("00:1B:21", "Intel"),
+    ("00:1B:2F", "Netgear"),
+    ("00:1B:63", "Apple"),
+    ("00:1B:A9", "Brother Industries"),
+    ("00:1C:14", "VMware"),
+    ("00:1C:42", "Parallels"),
+    ("00:1C:F0", "D-Link"),
+    ("00:1E:C2", "Apple"),
+    ("00:1F:32", "Nintendo"),
+    ("00:21:5A", "Hewlett Packard"),
+    ("00:24:B2", "Netgear"),
+    ("00:25:00", "Apple"),
+    ("00:25:90", "Super Micro Computer"),
+    ("00:25:9E", "Huawei"),
+    ("00:26:5A", "D-Link"),
+    ("00:27:22", "Ubiquiti"),
+    ("00:30:48", "Super Micro Computer"),
+    ("00:40:8C", "Axis Communications"),
+    ("00:50:56", "VMware"),
+    ("00:50:F2", "Microsoft"),
+    ("00:80:77", "Brother Industries"),
+    ("00:90:A9", "Western Digital"),
+    ("00:E0:4C", "Realtek"),
+    ("00:E0:FC", "Huawei"),
+    ("04:18:D6", "Ubiquiti"),
+    ("08:00:27", "PCS Systemtechnik (VirtualBox)"),
+    ("14:CC:20", "TP-Link"),
+    ("18:B4:30", "Nest Labs"),
+    ("18:FE:34", "Espressif"),
+    ("24:0A:C4", "Espressif"),
+    ("24:5E:BE", "QNAP Systems"),
+    ("24:6F:28", "Espressif"),
+    ("24:A4:3C", "Ubiquiti"),
+    ("28:57:BE", "Hikvision"),
+    ("28:CD:C1", "Raspberry Pi"),
+    ("28:CF:E9", "Apple"),
+    ("30:AE:A4", "Espressif"),
+    ("3C:5A:B4", "Google"),
+    ("3C:D9:2B", "Hewlett Packard"),
+    ("3C:EF:8C", "Dahua Technology"),
+    ("44:19:B6", "Hikvision"),
+    ("44:65:0D", "Amazon"),
+    ("44:D9:E7", "Ubiquiti"),
+    ("48:B0:2D", "NVIDIA"),
+    ("4C:5E:0C", "MikroTik (Routerboard)"),
+    ("50:C7:BF", "TP-Link"),
+    ("52:54:00", "QEMU / KVM"),
+    ("5C:AA:FD", "Sonos"),
+    ("5C:CF:7F", "Espressif"),
+    ("60:E3:27", "TP-Link"),
+    ("64:16:66", "Nest Labs"),
+    ("64:D1:54", "MikroTik (Routerboard)"),
+    ("68:72:51", "Ubiquiti"),
+    ("74:C2:46", "Amazon"),
+    ("78:8A:20", "Ubiquiti"),
+    ("7C:1E:52", "Microsoft"),
+    ("80:2A:A8", "Ubiquiti"),
+    ("84:F3:EB", "Espressif"),
+    ("90:02:A9", "Dahua Technology"),
+    ("94:9F:3E", "Sonos"),
+    ("A4:5E:60", "Apple"),
+    ("A4:CF:12", "Espressif"),
+    ("AC:1F:6B", "Super Micro Computer"),
+    ("AC:BC:32", "Apple"),
+    ("AC:CC:8E", "Axis Communications"),
+    ("B8:27:EB", "Raspberry Pi"),
+    ("B8:A4:4F", "Axis Communications"),
+    ("B8:AC:6F", "Dell"),
+    ("BC:AD:28", "Hikvision"),
+    ("C0:56:E3", "Hikvision"),
+    ("D4:CA:6D", "MikroTik (Routerboard)"),
+    ("D8:3A:DD", "Raspberry Pi"),
+    ("DC:9F:DB", "Ubiquiti"),
+    ("DC:A6:32", "Raspberry Pi"),
+    ("E4:5F:01", "Raspberry Pi"),
+    ("E4:8D:8C", "MikroTik (Routerboard)"),
+    ("EC:B5:FA", "Philips Lighting (Hue)"),
+    ("EC:FA:BC", "Espressif"),
+    ("F0:18:98", "Apple"),
+    ("F0:27:2D", "Amazon"),
+    ("F0:9F:C2", "Ubiquiti"),
+    ("F4:F2:6D", "TP-Link"),
+    ("F4:F5:D8", "Google"),
+    ("FC:65:DE", "Amazon"),
+    ("FC:EC:DA", "Ubiquiti"),
+];
+
+pub fn lookup_mac_vendor(mac: MacAddr) -> Option<String> {
+    let vendor = OUI_TABLE
+        .read()
+        .unwrap()
+        .get(&[mac.0, mac.1, mac.2])
+        .cloned();
+    // Phones / laptops randomize their MAC per network, there's no vendor behind those
+    if vendor.is_none() && mac.0 & 0x02 != 0 {
+        return Some("Locally administered (randomized)".to_owned());
+    }
+    vendor
+}
+
+pub fn get_oui_table_len() -> usize {
+    OUI_TABLE.read().unwrap().len()
+}
+
+// Entries from the file win over the bundled ones. The file gets copied into the program's directory, so it's used on the next start as well.
+pub fn load_oui_table_from_file(path: &Path) -> anyhow::Result<usize> {
+    let content = std::fs::read_to_string(path)?;
+    let entries = parse_oui_table(&content);
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No OUI entries found, expected IEEE oui.txt / oui.csv, wireshark's manuf or nmap-mac-prefixes."
+        ));
+    }
+
+    let mut custom_table_path = program_root_dir();
+    custom_table_path.push(CUSTOM_OUI_TABLE_FILE_NAME);
+    if path != custom_table_path {
+        std::fs::write(custom_table_path, content)?;
+    }
+
+    let entries_count = entries.len();
+    OUI_TABLE.write().unwrap().extend(entries);
+    Ok(entries_count)
+}
+
+fn load_initial_oui_table() -> HashMap<[u8; 3], String> {
+    let mut table = BUNDLED_OUI_TABLE
+        .iter()
+        .filter_map(|(prefix, vendor)| Some((parse_oui_prefix(prefix)?, vendor.to_string())))
+        .collect::<HashMap<[u8; 3], String>>();
+
+    let mut custom_table_path = program_root_dir();
+    custom_table_path.push(CUSTOM_OUI_TABLE_FILE_NAME);
+    if let Ok(content) = std::fs::read_to_string(custom_table_path) {
+        table.extend(parse_oui_table(&content));
+    }
+    table
+}
+
+// Recognizes (one entry per line):
+// IEEE oui.txt   "00-00-0C   (hex)\t\tCisco Systems, Inc" (the "(base 16)" lines work the same way)
+// IEEE oui.csv   "MA-L,00000C,Cisco Systems, Inc,170 West Tasman Dr. ..." (or "Cisco Systems, Inc" quoted)
+// manuf          "00:00:0C\tCisco\tCisco Systems, Inc"
+// nmap           "00000C Cisco Systems"
+fn parse_oui_table(content: &str) -> Vec<([u8; 3], String)> {
+    content.lines().filter_map(parse_oui_line).collect()
+}
+
+fn parse_oui_line(line: &str) -> Option<([u8; 3], String)> {
+    // Indented lines are addresses in oui.txt
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+        return None;
+    }
+
+    let line = line.trim_end();
+    if line.starts_with("MA-L,") {
+        let (prefix, vendor) = line.trim_start_matches("MA-L,").split_once(',')?;
+        let vendor = match vendor.strip_prefix('"') {
+            Some(s) => s.split_once('"')?.0,
+            None => vendor.split(',').next()?,
+        };
+        return to_oui_entry(prefix, vendor);
+    }
+
+    let (prefix, vendor) = line.split_once(char::is_whitespace)?;
+    let vendor = vendor
+        .trim()
+        .trim_start_matches("(hex)")
+        .trim_start_matches("(base 16)");
+    // manuf has a short and a long name, the long one is more readable
+    to_oui_entry(prefix, vendor.rsplit('\t').next()?)
+}
+
+fn to_oui_entry(prefix: &str, vendor: &str) -> Option<([u8; 3], String)> {
+    let vendor = vendor.trim();
+    if vendor.is_empty() {
+        return None;
+    }
+    Some((parse_oui_prefix(prefix)?, vendor.to_owned()))
+}
+
+// "00:00:0C", "00-00-0C", "0000.0C" or "00000C", larger prefixes (manuf's "/28", "/36") are skipped
+fn parse_oui_prefix(prefix: &str) -> Option<[u8; 3]> {
+    let hex = prefix
+        .chars()
+        .filter(|s| !matches!(s, ':' | '-' | '.'))
+        .collect::<String>();
+    if hex.len() != 6 || !hex.chars().all(|s| s.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let value = u32::from_str_radix(&hex, 16).ok()?;
+    let bytes = value.to_be_bytes();
+    Some([bytes[1], bytes[2], bytes[3]])
+}
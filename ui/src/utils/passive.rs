@@ -0,0 +1,469 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    net::{IpAddr, Ipv4Addr},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use log::{debug, error};
+use pnet::{
+    datalink::{self, Channel, NetworkInterface},
+    ipnetwork::IpNetwork,
+    packet::{
+        arp::ArpPacket,
+        ethernet::{EtherTypes, EthernetPacket},
+        ip::{IpNextHeaderProtocol, IpNextHeaderProtocols},
+        ipv4::Ipv4Packet,
+        ipv6::Ipv6Packet,
+        udp::UdpPacket,
+        Packet,
+    },
+    util::MacAddr,
+};
+
+use crate::app::{
+    network_topology::{HostnameSource, NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    dns::{DnsMessage, DnsRecordData},
+    llmnr::LLMNR_PORT,
+    mdns::MDNS_PORT,
+    netbios::{decode_netbios_name, NETBIOS_NAME_SERVICE_PORT, NETBIOS_RECORD_TYPE_NB},
+};
+
+const PASSIVE_DISCOVERY_READ_TIMEOUT_MS: u64 = 250;
+// There's no need to lock the graph for every single packet a chatty host sends
+const PASSIVE_DISCOVERY_NODE_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+const DHCP_SERVER_PORT: u16 = 67;
+const DHCP_CLIENT_PORT: u16 = 68;
+const DHCP_MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+const DHCP_OPTIONS_OFFSET: usize = 240;
+const DHCP_OPTION_HOSTNAME: u8 = 12;
+const DHCP_OPTION_REQUESTED_IP: u8 = 50;
+
+pub struct PassiveDiscoveryConfig {
+    pub interface_name: String,
+    pub only_local_subnets: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PassiveObservation {
+    ip: IpAddr,
+    mac: Option<MacAddr>,
+    hostname: Option<(String, HostnameSource)>,
+    source: &'static str,
+}
+impl PassiveObservation {
+    fn new(ip: IpAddr, source: &'static str) -> Self {
+        Self {
+            ip,
+            mac: None,
+            hostname: None,
+            source,
+        }
+    }
+}
+
+pub fn get_passive_discovery_interfaces() -> Vec<NetworkInterface> {
+    datalink::interfaces()
+        .into_iter()
+        .filter(|s| s.is_up() && !s.is_loopback())
+        .collect()
+}
+
+// Returns flag, which stops the discovery when set to true
+pub fn start_passive_discovery(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    config: PassiveDiscoveryConfig,
+) -> anyhow::Result<Arc<AtomicBool>> {
+    let Some(interface) = datalink::interfaces()
+        .into_iter()
+        .find(|s| s.name == config.interface_name)
+    else {
+        return Err(anyhow!(
+            "Interface {} does not exist.",
+            config.interface_name
+        ));
+    };
+
+    // Opening the channel outside of the thread, so permission errors can be shown right away
+    let datalink_config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(PASSIVE_DISCOVERY_READ_TIMEOUT_MS)),
+        promiscuous: true,
+        ..Default::default()
+    };
+    let mut rx = match datalink::channel(&interface, datalink_config)? {
+        Channel::Ethernet(_, rx) => rx,
+        _ => return Err(anyhow!("Unsupported channel type for {}.", interface.name)),
+    };
+
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let thread_should_stop = Arc::clone(&should_stop);
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info(format!(
+            "Started passive discovery on {}. No packets will be sent.",
+            interface.name
+        )),
+    );
+
+    std::thread::spawn(move || {
+        let mut last_node_refresh: HashMap<IpAddr, Instant> = HashMap::new();
+        let mut found_hosts = 0;
+
+        while !thread_should_stop.load(Ordering::Relaxed) {
+            let frame = match rx.next() {
+                Ok(frame) => frame,
+                Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+                Err(e) => {
+                    error!(
+                        "An error occurred while sniffing on {}: {}",
+                        interface.name, e
+                    );
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Err(format!(
+                            "Passive discovery on {} failed, stopping => {}",
+                            interface.name, e
+                        )),
+                    );
+                    break;
+                }
+            };
+
+            for observation in parse_ethernet_frame(frame) {
+                if !should_record_observation(&interface, &observation, config.only_local_subnets) {
+                    continue;
+                }
+
+                let is_refresh_due = last_node_refresh
+                    .get(&observation.ip)
+                    .is_none_or(|s| s.elapsed() > PASSIVE_DISCOVERY_NODE_REFRESH_INTERVAL);
+                if !is_refresh_due && observation.hostname.is_none() {
+                    continue;
+                }
+                last_node_refresh.insert(observation.ip, Instant::now());
+
+                if record_observation(&mut graph_ref, &interface, &observation) {
+                    found_hosts += 1;
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Info(format!(
+                            "Passive discovery found {} (via {})",
+                            observation.ip, observation.source
+                        )),
+                    );
+                }
+            }
+        }
+        // Also when sniffing failed, so the window doesn't think it's still running
+        thread_should_stop.store(true, Ordering::Relaxed);
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Stopped passive discovery on {}. Found {} new hosts.",
+                interface.name, found_hosts
+            )),
+        );
+    });
+
+    Ok(should_stop)
+}
+
+// bool => whether a new node was created
+fn record_observation(
+    graph_ref: &mut NetworkTopologyGraph,
+    interface: &NetworkInterface,
+    observation: &PassiveObservation,
+) -> bool {
+    let now = chrono::Local::now();
+    // When the ip is routed, the frame came from the router, so its source mac belongs to the router
+    let is_on_local_link = is_on_local_link(interface, observation.ip);
+    let (node_index, is_new) = NetworkTopology::get_or_add_node_by_ip(graph_ref, observation.ip);
+    NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+        node.first_seen.get_or_insert(now);
+        node.last_seen = Some(now);
+        if let Some(mac) = observation.mac.filter(|_| is_on_local_link) {
+            node.mac = Some(mac);
+        }
+        if let Some((hostname, hostname_source)) = &observation.hostname {
+            node.add_hostname(hostname, *hostname_source);
+        }
+    });
+
+    if is_on_local_link {
+        NetworkTopology::ensure_edge_from_localhost(graph_ref, node_index);
+    }
+
+    is_new
+}
+
+fn should_record_observation(
+    interface: &NetworkInterface,
+    observation: &PassiveObservation,
+    only_local_subnets: bool,
+) -> bool {
+    let ip = observation.ip;
+    let is_useless = ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_loopback()
+        || ip == IpAddr::V4(Ipv4Addr::BROADCAST)
+        || interface
+            .ips
+            .iter()
+            .any(|s| s.ip() == ip || is_subnet_broadcast(s, ip));
+    if is_useless {
+        return false;
+    }
+
+    !only_local_subnets || is_on_local_link(interface, ip)
+}
+
+pub fn is_on_local_link(interface: &NetworkInterface, ip: IpAddr) -> bool {
+    let is_ipv6_link_local = matches!(ip, IpAddr::V6(s) if (s.segments()[0] & 0xffc0) == 0xfe80);
+    is_ipv6_link_local || interface.ips.iter().any(|s| s.contains(ip))
+}
+
+fn is_subnet_broadcast(network: &IpNetwork, ip: IpAddr) -> bool {
+    match network {
+        IpNetwork::V4(s) => s.prefix() < 31 && IpAddr::V4(s.broadcast()) == ip,
+        IpNetwork::V6(_) => false,
+    }
+}
+
+fn parse_ethernet_frame(frame: &[u8]) -> Vec<PassiveObservation> {
+    let Some(ethernet) = EthernetPacket::new(frame) else {
+        return vec![];
+    };
+    let source_mac = ethernet.get_source();
+
+    match ethernet.get_ethertype() {
+        EtherTypes::Arp => {
+            let Some(arp) = ArpPacket::new(ethernet.payload()) else {
+                return vec![];
+            };
+            let mut observation =
+                PassiveObservation::new(IpAddr::V4(arp.get_sender_proto_addr()), "ARP");
+            observation.mac = Some(arp.get_sender_hw_addr());
+            vec![observation]
+        }
+        EtherTypes::Ipv4 => {
+            let Some(ipv4) = Ipv4Packet::new(ethernet.payload()) else {
+                return vec![];
+            };
+            parse_ip_payload(
+                IpAddr::V4(ipv4.get_source()),
+                source_mac,
+                ipv4.get_next_level_protocol(),
+                ipv4.payload(),
+            )
+        }
+        EtherTypes::Ipv6 => {
+            let Some(ipv6) = Ipv6Packet::new(ethernet.payload()) else {
+                return vec![];
+            };
+            parse_ip_payload(
+                IpAddr::V6(ipv6.get_source()),
+                source_mac,
+                ipv6.get_next_header(),
+                ipv6.payload(),
+            )
+        }
+        _ => vec![],
+    }
+}
+
+// Ethernet source mac gets attached to the source ip only, see `record_observation` for why that's not enough.
+fn parse_ip_payload(
+    source_ip: IpAddr,
+    source_mac: MacAddr,
+    protocol: IpNextHeaderProtocol,
+    payload: &[u8],
+) -> Vec<PassiveObservation> {
+    let mut source_observation = PassiveObservation::new(source_ip, "IP traffic");
+    source_observation.mac = Some(source_mac);
+    let mut observations = vec![source_observation];
+
+    if protocol != IpNextHeaderProtocols::Udp {
+        return observations;
+    }
+    let Some(udp) = UdpPacket::new(payload) else {
+        return observations;
+    };
+    let (source_port, destination_port) = (udp.get_source(), udp.get_destination());
+
+    if source_port == DHCP_CLIENT_PORT && destination_port == DHCP_SERVER_PORT {
+        observations[0].source = "DHCP";
+        observations.extend(parse_dhcp_client_message(udp.payload()));
+    } else if source_port == MDNS_PORT || destination_port == MDNS_PORT {
+        observations[0].source = "mDNS";
+        observations.extend(parse_name_resolution_message(
+            udp.payload(),
+            "mDNS",
+            HostnameSource::Mdns,
+        ));
+    } else if source_port == LLMNR_PORT || destination_port == LLMNR_PORT {
+        observations[0].source = "LLMNR";
+        observations.extend(parse_name_resolution_message(
+            udp.payload(),
+            "LLMNR",
+            HostnameSource::Llmnr,
+        ));
+    } else if source_port == NETBIOS_NAME_SERVICE_PORT {
+        observations[0].source = "NetBIOS";
+        observations.extend(parse_netbios_name_service_message(udp.payload()));
+    }
+
+    observations
+}
+
+fn parse_dhcp_client_message(payload: &[u8]) -> Vec<PassiveObservation> {
+    if payload.len() < DHCP_OPTIONS_OFFSET || payload[236..240] != DHCP_MAGIC_COOKIE {
+        return vec![];
+    }
+    let hardware_address_length = payload[2] as usize;
+    if hardware_address_length != 6 {
+        return vec![];
+    }
+    let client_mac = MacAddr::new(
+        payload[28],
+        payload[29],
+        payload[30],
+        payload[31],
+        payload[32],
+        payload[33],
+    );
+    let client_ip = Ipv4Addr::new(payload[12], payload[13], payload[14], payload[15]);
+
+    let mut requested_ip = None;
+    let mut hostname = None;
+    let mut offset = DHCP_OPTIONS_OFFSET;
+    while offset < payload.len() {
+        let option = payload[offset];
+        match option {
+            0 => {
+                offset += 1;
+                continue;
+            }
+            255 => break,
+            _ => {}
+        }
+        let Some(&option_length) = payload.get(offset + 1) else {
+            break;
+        };
+        let Some(option_data) = payload.get(offset + 2..offset + 2 + option_length as usize) else {
+            break;
+        };
+        match option {
+            DHCP_OPTION_HOSTNAME => {
+                hostname = Some(String::from_utf8_lossy(option_data).into_owned());
+            }
+            DHCP_OPTION_REQUESTED_IP if option_data.len() == 4 => {
+                requested_ip = Some(Ipv4Addr::new(
+                    option_data[0],
+                    option_data[1],
+                    option_data[2],
+                    option_data[3],
+                ));
+            }
+            _ => {}
+        }
+        offset += 2 + option_length as usize;
+    }
+
+    let client_ip = if client_ip.is_unspecified() {
+        match requested_ip {
+            Some(s) => s,
+            None => return vec![],
+        }
+    } else {
+        client_ip
+    };
+
+    debug!(
+        "DHCP client {} ({}) with hostname {:?}",
+        client_ip, client_mac, hostname
+    );
+    let mut observation = PassiveObservation::new(IpAddr::V4(client_ip), "DHCP");
+    observation.mac = Some(client_mac);
+    observation.hostname = hostname.map(|s| (s, HostnameSource::Dhcp));
+    vec![observation]
+}
+
+// mDNS and LLMNR both use DNS message format, answers tie names to addresses
+fn parse_name_resolution_message(
+    payload: &[u8],
+    source: &'static str,
+    hostname_source: HostnameSource,
+) -> Vec<PassiveObservation> {
+    let Ok(message) = DnsMessage::parse(payload) else {
+        return vec![];
+    };
+    if !message.is_response() {
+        return vec![];
+    }
+
+    message
+        .all_records()
+        .filter_map(|s| {
+            let ip = match s.data {
+                DnsRecordData::A(ip) => IpAddr::V4(ip),
+                DnsRecordData::Aaaa(ip) => IpAddr::V6(ip),
+                _ => return None,
+            };
+            let mut observation = PassiveObservation::new(ip, source);
+            observation.hostname = Some((s.name.trim_end_matches('.').to_owned(), hostname_source));
+            Some(observation)
+        })
+        .collect()
+}
+
+// Registrations (opcode 5), refreshes (8, 9) and positive query responses carry NB records => name + ip
+fn parse_netbios_name_service_message(payload: &[u8]) -> Vec<PassiveObservation> {
+    let Ok(message) = DnsMessage::parse(payload) else {
+        return vec![];
+    };
+
+    let mut observations = vec![];
+    for record in message
+        .all_records()
+        .filter(|s| s.record_type == NETBIOS_RECORD_TYPE_NB)
+    {
+        let DnsRecordData::Other(data) = &record.data else {
+            continue;
+        };
+        let Some((name, suffix)) = decode_netbios_name(&record.name) else {
+            continue;
+        };
+        // Only workstation (0x00) and server (0x20) names identify the host itself, the rest are groups / services
+        if name.starts_with('*') || (suffix != 0x00 && suffix != 0x20) {
+            continue;
+        }
+
+        // Each entry => 2 bytes of flags + ipv4
+        for entry in data.chunks_exact(6) {
+            // Group names are shared between multiple hosts
+            if entry[0] & 0x80 != 0 {
+                continue;
+            }
+            let mut observation = PassiveObservation::new(
+                IpAddr::V4(Ipv4Addr::new(entry[2], entry[3], entry[4], entry[5])),
+                "NetBIOS",
+            );
+            observation.hostname = Some((name.clone(), HostnameSource::NetBios));
+            observations.push(observation);
+        }
+    }
+
+    observations
+}
@@ -6,21 +6,34 @@ use std::{
 
 use log::{error, info};
 
-use crate::utils::constants::PORT_FUZZING_COMMANDS;
+use crate::utils::{bytes::bytes_to_utf8, constants::PORT_FUZZING_COMMANDS};
 
-use super::ip::{BannerGrabResult, FuzzingResult, FuzzingResults, Port, ScanIpPortsConfig};
+use super::ip::{
+    BannerGrab, BannerGrabResult, FuzzingResult, FuzzingResults, Port, ScanIpPortsConfig,
+};
 
 // Hey, future me, I have no fucking idea why I have to do this windows vs linux shit,
 // but it doesn't for the love of good want to work without it :(
 
+// Through the proxy when there's one, from the project's source otherwise
+pub fn connect_tcp_stream(
+    target: SocketAddr,
+    config: &ScanIpPortsConfig,
+) -> std::io::Result<TcpStream> {
+    let connection_timeout = Duration::from_millis(config.connection_timeout_ms);
+    match config.proxy.as_ref() {
+        Some(proxy) => proxy.connect(target, connection_timeout, &config.source),
+        None => config.source.connect_tcp(target, connection_timeout),
+    }
+}
+
 // bool => whether the connection was successfully established
 pub fn is_port_open_using_tcp_stream(
     ip: IpAddr,
     port: Port,
     config: &ScanIpPortsConfig,
 ) -> (bool, BannerGrabResult, FuzzingResults) {
-    let target = SocketAddr::new(ip, port);
-    match TcpStream::connect_timeout(&target, Duration::from_millis(config.connection_timeout_ms)) {
+    match connect_tcp_stream(SocketAddr::new(ip, port), config) {
         Ok(mut connected_socket) => {
             if !config.should_banner_grab && !config.should_fuzz {
                 return (true, None, None);
@@ -46,14 +59,14 @@ pub fn is_port_open_using_tcp_stream(
             }
 
             // grab banner
-            let mut banner: String = String::new();
+            let mut banner: Vec<u8> = vec![];
             if config.should_banner_grab {
                 #[cfg(target_os = "windows")]
                 {
                     let mut buffer = Vec::new();
                     // for some reason windows throws error even though it succeeds
                     _ = connected_socket.read_to_end(&mut buffer);
-                    banner = socket_buffer_to_string(&buffer);
+                    banner = buffer;
                 }
 
                 #[cfg(target_os = "linux")]
@@ -64,7 +77,7 @@ pub fn is_port_open_using_tcp_stream(
                     );
                     match grabbed_output {
                         Ok(grabbed_output) => {
-                            banner = socket_buffer_to_string(&grabbed_output);
+                            banner = grabbed_output;
                         }
                         Err(e) => {
                             error!("An error occurred while banner grabbing: {}", e);
@@ -131,7 +144,14 @@ pub fn is_port_open_using_tcp_stream(
                 }
             }
 
-            (true, Some(banner), Some(fuzzing_results))
+            (
+                true,
+                Some(BannerGrab {
+                    result: socket_buffer_to_string(&banner),
+                    result_raw: banner,
+                }),
+                Some(fuzzing_results),
+            )
         }
         Err(e) => {
             info!("Unable to connect to port {}, error: {}", port, e);
@@ -174,9 +194,33 @@ fn read_everything_from_socket(
     }
 }
 
+// Only used for quick previews (status log, hover texts), raw bytes are kept next to it for the viewer.
 fn socket_buffer_to_string(buffer: &[u8]) -> String {
-    buffer
-        .iter()
-        .map(|s| char::from_u32((*s).into()).unwrap())
-        .collect::<String>()
+    bytes_to_utf8(buffer)
+}
+
+// "22, 80 443\n8000-8100" => every port once, sorted. Err => the entries that aren't a port or a range.
+pub fn parse_port_list(value: &str) -> Result<Vec<Port>, Vec<String>> {
+    let mut ports = vec![];
+    let mut invalid_entries = vec![];
+    for entry in value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+    {
+        let range = match entry.split_once('-') {
+            Some((from, to)) => from.parse::<Port>().ok().zip(to.parse::<Port>().ok()),
+            None => entry.parse::<Port>().ok().map(|s| (s, s)),
+        };
+        match range {
+            Some((from, to)) if from <= to => ports.extend(from..=to),
+            _ => invalid_entries.push(entry.to_owned()),
+        }
+    }
+    if !invalid_entries.is_empty() {
+        return Err(invalid_entries);
+    }
+
+    ports.sort();
+    ports.dedup();
+    Ok(ports)
 }
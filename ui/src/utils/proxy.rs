@@ -0,0 +1,423 @@
+use std::{
+    fmt::Display,
+    io::{self, Read, Write},
+    net::{IpAddr, SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use super::{bytes::bytes_to_base64, interfaces::SourceBinding};
+
+const SOCKS5_VERSION: u8 = 0x05;
+const SOCKS5_AUTH_NONE: u8 = 0x00;
+const SOCKS5_AUTH_USERNAME_PASSWORD: u8 = 0x02;
+const SOCKS5_AUTH_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const SOCKS5_COMMAND_CONNECT: u8 = 0x01;
+const SOCKS5_ADDRESS_IPV4: u8 = 0x01;
+const SOCKS5_ADDRESS_DOMAIN: u8 = 0x03;
+const SOCKS5_ADDRESS_IPV6: u8 = 0x04;
+const HTTP_CONNECT_MAX_RESPONSE_HEADER_SIZE: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProxyKind {
+    Socks5,
+    HttpConnect,
+}
+impl Display for ProxyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Socks5 => write!(f, "SOCKS5"),
+            Self::HttpConnect => write!(f, "HTTP CONNECT"),
+        }
+    }
+}
+
+// TCP connects go through this one instead (e.g. `ssh -D` into a segmented network)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConfig {
+    pub kind: ProxyKind,
+    pub address: SocketAddr,
+    pub credentials: Option<(String, String)>, // (username, password)
+}
+impl ProxyConfig {
+    // Ok => the target accepted the connection through the proxy, anything else reads as a closed port
+    pub fn connect(
+        &self,
+        target: SocketAddr,
+        timeout: Duration,
+        source: &SourceBinding,
+    ) -> io::Result<TcpStream> {
+        let mut stream = self.open(timeout, source)?;
+        match self.kind {
+            ProxyKind::Socks5 => socks5_connect(&mut stream, target)?,
+            ProxyKind::HttpConnect => self.http_connect(&mut stream, target)?,
+        }
+
+        // Callers set their own timeouts for the data that follows
+        stream.set_read_timeout(None)?;
+        stream.set_write_timeout(None)?;
+        Ok(stream)
+    }
+
+    // Tells "proxy is down / rejects us" apart from "every port is closed", before a scan starts
+    pub fn ensure_reachable(&self, timeout: Duration, source: &SourceBinding) -> io::Result<()> {
+        self.open(timeout, source).map(|_| ())
+    }
+
+    // Connected stream, with SOCKS5 authentication already done
+    fn open(&self, timeout: Duration, source: &SourceBinding) -> io::Result<TcpStream> {
+        let mut stream = source.connect_tcp(self.address, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        if self.kind == ProxyKind::Socks5 {
+            self.socks5_authenticate(&mut stream)?;
+        }
+        Ok(stream)
+    }
+
+    fn socks5_authenticate(&self, stream: &mut TcpStream) -> io::Result<()> {
+        let methods: &[u8] = match self.credentials {
+            Some(_) => &[SOCKS5_AUTH_NONE, SOCKS5_AUTH_USERNAME_PASSWORD],
+            None => &[SOCKS5_AUTH_NONE],
+        };
+        let mut greeting = vec![SOCKS5_VERSION, methods.len() as u8];
+        greeting.extend_from_slice(methods);
+        stream.write_all(&greeting)?;
+
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply)?;
+        if reply[0] != SOCKS5_VERSION {
+            return Err(proxy_err("proxy doesn't speak SOCKS5"));
+        }
+        match (reply[1], self.credentials.as_ref()) {
+            (SOCKS5_AUTH_NONE, _) => Ok(()),
+            // RFC 1929
+            (SOCKS5_AUTH_USERNAME_PASSWORD, Some((username, password))) => {
+                // Both are length prefixed with a single byte
+                let (Ok(username_len), Ok(password_len)) =
+                    (u8::try_from(username.len()), u8::try_from(password.len()))
+                else {
+                    return Err(proxy_err(
+                        "SOCKS5 username and password can't be longer than 255 bytes",
+                    ));
+                };
+                let mut request = vec![0x01, username_len];
+                request.extend_from_slice(username.as_bytes());
+                request.push(password_len);
+                request.extend_from_slice(password.as_bytes());
+                stream.write_all(&request)?;
+
+                let mut reply = [0u8; 2];
+                stream.read_exact(&mut reply)?;
+                if reply[1] != 0x00 {
+                    return Err(proxy_err("SOCKS5 proxy rejected the username / password"));
+                }
+                Ok(())
+            }
+            (SOCKS5_AUTH_NO_ACCEPTABLE_METHOD, _) => Err(proxy_err(
+                "SOCKS5 proxy requires an authentication method we don't support",
+            )),
+            _ => Err(proxy_err("SOCKS5 proxy picked a method we didn't offer")),
+        }
+    }
+
+    fn http_connect(&self, stream: &mut TcpStream, target: SocketAddr) -> io::Result<()> {
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+        if let Some((username, password)) = self.credentials.as_ref() {
+            request.push_str(&format!(
+                "Proxy-Authorization: Basic {}\r\n",
+                bytes_to_base64(format!("{}:{}", username, password).as_bytes())
+            ));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes())?;
+
+        // Byte by byte, since whatever follows the headers is already the target's banner
+        let mut response = vec![];
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= HTTP_CONNECT_MAX_RESPONSE_HEADER_SIZE {
+                return Err(proxy_err("HTTP proxy response headers are too large"));
+            }
+            stream.read_exact(&mut byte)?;
+            response.push(byte[0]);
+        }
+
+        let status_line = String::from_utf8_lossy(&response);
+        let status_code = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse::<u16>().ok())
+            .ok_or_else(|| proxy_err("HTTP proxy sent a malformed response"))?;
+        match status_code {
+            200..=299 => Ok(()),
+            407 => Err(proxy_err("HTTP proxy requires authentication")),
+            s => Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("HTTP proxy replied with status {}", s),
+            )),
+        }
+    }
+}
+
+fn socks5_connect(stream: &mut TcpStream, target: SocketAddr) -> io::Result<()> {
+    let mut request = vec![SOCKS5_VERSION, SOCKS5_COMMAND_CONNECT, 0x00];
+    match target.ip() {
+        IpAddr::V4(s) => {
+            request.push(SOCKS5_ADDRESS_IPV4);
+            request.extend_from_slice(&s.octets());
+        }
+        IpAddr::V6(s) => {
+            request.push(SOCKS5_ADDRESS_IPV6);
+            request.extend_from_slice(&s.octets());
+        }
+    }
+    request.extend_from_slice(&target.port().to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply)?;
+    if reply[1] != 0x00 {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("SOCKS5 proxy replied {}", describe_socks5_reply(reply[1])),
+        ));
+    }
+
+    // Bound address + port, nobody needs them, but they have to be read before the target's data
+    let bound_address_len = match reply[3] {
+        SOCKS5_ADDRESS_IPV4 => 4,
+        SOCKS5_ADDRESS_IPV6 => 16,
+        SOCKS5_ADDRESS_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err(proxy_err("SOCKS5 proxy sent an unknown address type")),
+    };
+    stream.read_exact(&mut vec![0u8; bound_address_len + 2])?;
+    Ok(())
+}
+
+// RFC 1928, section 6
+fn describe_socks5_reply(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn proxy_err(text: &str) -> io::Error {
+    io::Error::other(text.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::JoinHandle;
+
+    use crate::utils::test_helpers::{serve, serve_with_handle};
+
+    use super::*;
+
+    const TARGET: &str = "10.0.0.1:22";
+    const TARGET_BANNER: &[u8] = b"SSH-2.0-OpenSSH_9.6\r\n";
+
+    fn connect(
+        kind: ProxyKind,
+        address: SocketAddr,
+        credentials: Option<(&str, &str)>,
+    ) -> io::Result<TcpStream> {
+        let proxy = ProxyConfig {
+            kind,
+            address,
+            credentials: credentials
+                .map(|(username, password)| (username.to_owned(), password.to_owned())),
+        };
+        proxy.connect(
+            TARGET.parse().unwrap(),
+            Duration::from_secs(1),
+            &SourceBinding::default(),
+        )
+    }
+
+    fn read_banner(stream: &mut TcpStream) -> Vec<u8> {
+        let mut banner = vec![0u8; TARGET_BANNER.len()];
+        stream.read_exact(&mut banner).unwrap();
+        banner
+    }
+
+    fn read_vec(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+        let mut data = vec![0u8; len];
+        stream.read_exact(&mut data).unwrap();
+        data
+    }
+
+    // Answers the CONNECT request with `reply`, then talks as the target
+    fn socks5_connect_reply(stream: &mut TcpStream, reply: u8) {
+        let request = read_vec(stream, 10);
+        assert_eq!(
+            request,
+            [
+                SOCKS5_VERSION,
+                SOCKS5_COMMAND_CONNECT,
+                0x00,
+                SOCKS5_ADDRESS_IPV4,
+                10,
+                0,
+                0,
+                1,
+                0,
+                22
+            ]
+        );
+        stream
+            .write_all(&[
+                SOCKS5_VERSION,
+                reply,
+                0x00,
+                SOCKS5_ADDRESS_IPV4,
+                127,
+                0,
+                0,
+                1,
+                0x1f,
+                0x90,
+            ])
+            .unwrap();
+        if reply == 0x00 {
+            stream.write_all(TARGET_BANNER).unwrap();
+        }
+    }
+
+    #[test]
+    fn socks5_connect_without_authentication() {
+        let address = serve(|mut stream| {
+            assert_eq!(
+                read_vec(&mut stream, 3),
+                [SOCKS5_VERSION, 1, SOCKS5_AUTH_NONE]
+            );
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_NONE])
+                .unwrap();
+            socks5_connect_reply(&mut stream, 0x00);
+        });
+        let mut stream = connect(ProxyKind::Socks5, address, None).unwrap();
+        assert_eq!(read_banner(&mut stream), TARGET_BANNER);
+    }
+
+    #[test]
+    fn socks5_connect_with_authentication() {
+        let address = serve(|mut stream| {
+            read_vec(&mut stream, 4);
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_USERNAME_PASSWORD])
+                .unwrap();
+            assert_eq!(read_vec(&mut stream, 13), b"\x01\x04user\x06secret");
+            stream.write_all(&[0x01, 0x00]).unwrap();
+            socks5_connect_reply(&mut stream, 0x00);
+        });
+        let mut stream = connect(ProxyKind::Socks5, address, Some(("user", "secret"))).unwrap();
+        assert_eq!(read_banner(&mut stream), TARGET_BANNER);
+    }
+
+    #[test]
+    fn socks5_connection_refused() {
+        let address = serve(|mut stream| {
+            read_vec(&mut stream, 3);
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_NONE])
+                .unwrap();
+            socks5_connect_reply(&mut stream, 0x05);
+        });
+        let err = connect(ProxyKind::Socks5, address, None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+        assert!(err.to_string().contains("connection refused"));
+    }
+
+    #[test]
+    fn socks5_authentication_failure() {
+        let address = serve(|mut stream| {
+            read_vec(&mut stream, 4);
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_USERNAME_PASSWORD])
+                .unwrap();
+            read_vec(&mut stream, 12);
+            stream.write_all(&[0x01, 0x01]).unwrap();
+        });
+        let err = connect(ProxyKind::Socks5, address, Some(("user", "wrong"))).unwrap_err();
+        assert!(err.to_string().contains("rejected the username / password"));
+
+        let address = serve(|mut stream| {
+            read_vec(&mut stream, 3);
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_NO_ACCEPTABLE_METHOD])
+                .unwrap();
+        });
+        let err = connect(ProxyKind::Socks5, address, None).unwrap_err();
+        assert!(err.to_string().contains("authentication method"));
+    }
+
+    #[test]
+    fn socks5_credentials_too_long() {
+        let address = serve(|mut stream| {
+            read_vec(&mut stream, 4);
+            stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_AUTH_USERNAME_PASSWORD])
+                .unwrap();
+        });
+        let username = "u".repeat(256);
+        let err = connect(ProxyKind::Socks5, address, Some((&username, "secret"))).unwrap_err();
+        assert!(err.to_string().contains("255 bytes"));
+    }
+
+    // Reads the CONNECT request headers, answers with `response` and talks as the target on success
+    fn http_proxy(response: &'static str) -> (SocketAddr, JoinHandle<String>) {
+        serve_with_handle(move |mut stream| {
+            let mut request = vec![];
+            let mut byte = [0u8; 1];
+            while !request.ends_with(b"\r\n\r\n") {
+                stream.read_exact(&mut byte).unwrap();
+                request.push(byte[0]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            if response.starts_with("HTTP/1.1 200") {
+                stream.write_all(TARGET_BANNER).unwrap();
+            }
+            String::from_utf8(request).unwrap()
+        })
+    }
+
+    #[test]
+    fn http_connect() {
+        let (address, handle) = http_proxy("HTTP/1.1 200 Connection established\r\n\r\n");
+        let mut stream =
+            connect(ProxyKind::HttpConnect, address, Some(("user", "secret"))).unwrap();
+        assert_eq!(read_banner(&mut stream), TARGET_BANNER);
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("CONNECT 10.0.0.1:22 HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n"));
+    }
+
+    #[test]
+    fn http_connect_refused() {
+        let (address, _) = http_proxy("HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n");
+        let err = connect(ProxyKind::HttpConnect, address, None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+        assert!(err.to_string().contains("502"));
+    }
+
+    #[test]
+    fn http_connect_authentication_failure() {
+        let (address, _) = http_proxy(
+            "HTTP/1.1 407 Proxy Authentication Required\r\nProxy-Authenticate: Basic realm=\"proxy\"\r\n\r\n",
+        );
+        let err = connect(ProxyKind::HttpConnect, address, Some(("user", "wrong"))).unwrap_err();
+        assert!(err.to_string().contains("requires authentication"));
+    }
+}
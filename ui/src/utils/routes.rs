@@ -0,0 +1,182 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ipnet::IpNet;
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::interfaces::get_source_interfaces;
+
+// <linux/route.h> / <linux/ipv6_route.h>
+const RTF_UP: u32 = 0x0001;
+const RTF_REJECT: u32 = 0x0200;
+const RTF_LOCAL: u32 = 0x8000_0000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub interface_name: String,
+    pub destination: IpNet,
+    pub gateway: Option<IpAddr>, // None => directly connected
+    pub metric: u32,
+}
+impl Route {
+    pub fn is_default(&self) -> bool {
+        self.destination.prefix_len() == 0
+    }
+}
+
+// Both IP versions, loopback and rejecting routes are left out
+#[cfg(target_os = "linux")]
+pub fn get_routes() -> anyhow::Result<Vec<Route>> {
+    let mut routes = parse_proc_net_route(&std::fs::read_to_string("/proc/net/route")?);
+    // IPv6 might be disabled, that's fine
+    if let Ok(s) = std::fs::read_to_string("/proc/net/ipv6_route") {
+        routes.extend(parse_proc_net_ipv6_route(&s));
+    }
+    Ok(routes)
+}
+#[cfg(not(target_os = "linux"))]
+pub fn get_routes() -> anyhow::Result<Vec<Route>> {
+    Err(anyhow::anyhow!(
+        "Reading the routing table is only supported on linux atm."
+    ))
+}
+
+// Iface Destination Gateway Flags RefCnt Use Metric Mask ..., addresses are hex in host byte order
+fn parse_proc_net_route(content: &str) -> Vec<Route> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<&str>>();
+            let parse_address = |s: &str| {
+                u32::from_str_radix(s, 16)
+                    .ok()
+                    .map(|s| Ipv4Addr::from(s.to_ne_bytes()))
+            };
+            let flags = u32::from_str_radix(columns.get(3)?, 16).ok()?;
+            if !is_usable_route(columns[0], flags) {
+                return None;
+            }
+
+            let destination = parse_address(columns.get(1)?)?;
+            let gateway = parse_address(columns.get(2)?)?;
+            let mask = parse_address(columns.get(7)?)?;
+            Some(Route {
+                interface_name: columns[0].to_owned(),
+                destination: IpNet::new(
+                    IpAddr::V4(destination),
+                    u32::from(mask).count_ones() as u8,
+                )
+                .ok()?,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V4(gateway)),
+                metric: columns.get(6)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// Destination Prefix Source Prefix Gateway Metric RefCnt Use Flags Iface, everything in hex
+fn parse_proc_net_ipv6_route(content: &str) -> Vec<Route> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<&str>>();
+            let parse_address = |s: &str| u128::from_str_radix(s, 16).ok().map(Ipv6Addr::from);
+            let interface_name = columns.get(9)?;
+            let flags = u32::from_str_radix(columns.get(8)?, 16).ok()?;
+            if !is_usable_route(interface_name, flags) {
+                return None;
+            }
+
+            let destination = parse_address(columns.first()?)?;
+            let gateway = parse_address(columns.get(4)?)?;
+            Some(Route {
+                interface_name: interface_name.to_string(),
+                destination: IpNet::new(
+                    IpAddr::V6(destination),
+                    u8::from_str_radix(columns.get(1)?, 16).ok()?,
+                )
+                .ok()?,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V6(gateway)),
+                metric: u32::from_str_radix(columns.get(5)?, 16).ok()?,
+            })
+        })
+        .collect()
+}
+
+fn is_usable_route(interface_name: &str, flags: u32) -> bool {
+    interface_name != "lo"
+        && flags & RTF_UP != 0
+        && flags & RTF_REJECT == 0
+        && flags & RTF_LOCAL == 0
+}
+
+// (interface name, subnet) of every IPv4 address, that's what "scan my subnet" means.
+// IPv6 subnets are left out, since a /64 can't be swept (IPv6 neighbor discovery is there for that).
+pub fn get_local_subnets() -> Vec<(String, IpNet)> {
+    let mut subnets: Vec<(String, IpNet)> = vec![];
+    for interface in get_source_interfaces() {
+        for network in interface.ips.iter().filter(|s| s.is_ipv4()) {
+            let Ok(subnet) = IpNet::new(network.ip(), network.prefix()) else {
+                continue;
+            };
+            let subnet = subnet.trunc();
+            if subnet.prefix_len() < 32 && !subnets.iter().any(|s| s.1 == subnet) {
+                subnets.push((interface.name.clone(), subnet));
+            }
+        }
+    }
+    subnets
+}
+
+// Gateways of the default routes become nodes directly reachable from localhost
+pub fn add_default_gateways(mut graph_ref: NetworkTopologyGraph, status_info_ref: StatusInfoRef) {
+    let routes = match get_routes() {
+        Ok(s) => s,
+        Err(e) => {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Err(format!("Unable to read the routing table => {}", e)),
+            );
+            return;
+        }
+    };
+
+    let mut gateways_count = 0;
+    for route in routes.iter().filter(|s| s.is_default()) {
+        let Some(gateway) = route.gateway else {
+            continue;
+        };
+        let (node_index, is_new) = NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, gateway);
+        NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+            if node.notes.is_empty() {
+                node.notes = format!("Default gateway ({}).", route.interface_name);
+            }
+        });
+        NetworkTopology::ensure_edge_from_localhost(&mut graph_ref, node_index);
+        gateways_count += 1;
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Default gateway {} on {}{}",
+                gateway,
+                route.interface_name,
+                if is_new {
+                    " added."
+                } else {
+                    " already exists."
+                }
+            )),
+        );
+    }
+
+    if gateways_count == 0 {
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Warn("No default gateway found in the routing table.".to_owned()),
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::app::network_topology::{NetworkTopology, NetworkTopologyGraph, PortInfo};
+
+use super::{
+    ip::Port,
+    targets::{TargetExclusions, TargetList},
+};
+
+const MAX_SCAN_SNAPSHOTS: usize = 200;
+const MAX_BANNER_PREVIEW_LEN: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortSnapshot {
+    pub service: String,
+    pub banner: Option<String>, // None => wasn't grabbed (or nothing came back)
+}
+impl From<&PortInfo> for PortSnapshot {
+    fn from(value: &PortInfo) -> Self {
+        Self {
+            service: value.possible_service_name.clone(),
+            banner: value.banner.as_ref().map(|s| s.result.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HostSnapshot {
+    pub hostname: String,
+    pub ports: BTreeMap<Port, PortSnapshot>, // only the open ones
+}
+
+// What a single scan saw, nodes only keep the latest answer
+#[derive(Debug, Clone)]
+pub struct ScanSnapshot {
+    pub id: usize,
+    pub taken_at: DateTime<Local>,
+    pub description: String,
+    pub scanned_targets: TargetList, // hosts only get compared when both scans covered them
+    pub excluded_targets: TargetExclusions,
+    pub scanned_ports: Vec<Port>, // empty => host discovery only, ports don't get compared
+    pub hosts: BTreeMap<IpAddr, HostSnapshot>, // every host that answered, port scans always keep their host
+}
+impl ScanSnapshot {
+    pub fn has_scanned(&self, ip: IpAddr) -> bool {
+        self.scanned_targets.contains(ip) && !self.excluded_targets.is_excluded(ip)
+    }
+}
+impl Display for ScanSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {} - {}",
+            self.id,
+            self.taken_at.format("%Y-%m-%d %H:%M:%S"),
+            self.description
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ScanSnapshots {
+    pub snapshots: Vec<ScanSnapshot>,
+    next_id: usize,
+}
+pub type ScanSnapshotsRef = Arc<Mutex<ScanSnapshots>>;
+
+// Hostnames are taken from the nodes, so call it once the scan is done with them
+pub fn record_scan_snapshot(
+    snapshots_ref: &ScanSnapshotsRef,
+    graph_ref: &mut NetworkTopologyGraph,
+    description: String,
+    scanned_targets: TargetList,
+    excluded_targets: TargetExclusions,
+    scanned_ports: Vec<Port>,
+    results: Vec<(IpAddr, Vec<PortInfo>)>,
+) {
+    let hosts = results
+        .into_iter()
+        .map(|(ip, ports)| {
+            let hostname = NetworkTopology::get_node_by_ip(graph_ref, ip)
+                .map(|s| s.1.data().unwrap().hostname.clone())
+                .unwrap_or_default();
+            let ports = ports
+                .iter()
+                .map(|s| (s.number, PortSnapshot::from(s)))
+                .collect();
+            (ip, HostSnapshot { hostname, ports })
+        })
+        .collect();
+
+    let mut snapshots = snapshots_ref.lock().unwrap();
+    let id = snapshots.next_id + 1;
+    snapshots.next_id = id;
+    snapshots.snapshots.push(ScanSnapshot {
+        id,
+        taken_at: Local::now(),
+        description,
+        scanned_targets,
+        excluded_targets,
+        scanned_ports,
+        hosts,
+    });
+    let overflow = snapshots.snapshots.len().saturating_sub(MAX_SCAN_SNAPSHOTS);
+    snapshots.snapshots.drain(..overflow);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanDiffEntry {
+    HostAdded(IpAddr, Vec<Port>),
+    HostRemoved(IpAddr),
+    HostnameChanged(IpAddr, String, String),
+    PortOpened(IpAddr, Port, String),
+    PortClosed(IpAddr, Port, String),
+    ServiceChanged(IpAddr, Port, String, String),
+    BannerChanged(IpAddr, Port, String, String),
+}
+impl Display for ScanDiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HostAdded(ip, ports) if ports.is_empty() => write!(f, "Host {} added", ip),
+            Self::HostAdded(ip, ports) => write!(
+                f,
+                "Host {} added, open ports {}",
+                ip,
+                ports
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Self::HostRemoved(ip) => write!(f, "Host {} removed", ip),
+            Self::HostnameChanged(ip, old, new) => write!(
+                f,
+                "Hostname of {} changed from \"{}\" to \"{}\"",
+                ip, old, new
+            ),
+            Self::PortOpened(ip, port, service) => {
+                write!(f, "Port {} ({}) opened on {}", port, service, ip)
+            }
+            Self::PortClosed(ip, port, service) => {
+                write!(f, "Port {} ({}) closed on {}", port, service, ip)
+            }
+            Self::ServiceChanged(ip, port, old, new) => write!(
+                f,
+                "Service on {} port {} changed from {} to {}",
+                ip, port, old, new
+            ),
+            Self::BannerChanged(ip, port, old, new) => write!(
+                f,
+                "Banner on {} port {} changed from {} to {}",
+                ip,
+                port,
+                preview_banner(old),
+                preview_banner(new)
+            ),
+        }
+    }
+}
+
+// First line only, banners tend to be whole HTTP responses
+fn preview_banner(banner: &str) -> String {
+    let first_line = banner.lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() > MAX_BANNER_PREVIEW_LEN || banner.trim() != first_line {
+        format!(
+            "\"{}...\"",
+            first_line
+                .chars()
+                .take(MAX_BANNER_PREVIEW_LEN)
+                .collect::<String>()
+        )
+    } else {
+        format!("\"{}\"", first_line)
+    }
+}
+
+// Hosts / ports are only compared when both scans probed them, a port scan of 22 says nothing about 80
+pub fn diff_scan_snapshots(older: &ScanSnapshot, newer: &ScanSnapshot) -> Vec<ScanDiffEntry> {
+    let mut entries = vec![];
+    for (ip, new_host) in newer.hosts.iter() {
+        let Some(old_host) = older.hosts.get(ip) else {
+            if older.has_scanned(*ip) {
+                entries.push(ScanDiffEntry::HostAdded(
+                    *ip,
+                    new_host.ports.keys().copied().collect(),
+                ));
+            }
+            continue;
+        };
+
+        if old_host.hostname != new_host.hostname {
+            entries.push(ScanDiffEntry::HostnameChanged(
+                *ip,
+                old_host.hostname.clone(),
+                new_host.hostname.clone(),
+            ));
+        }
+
+        for (port, new_port) in new_host.ports.iter() {
+            match old_host.ports.get(port) {
+                None if older.scanned_ports.contains(port) => entries.push(
+                    ScanDiffEntry::PortOpened(*ip, *port, new_port.service.clone()),
+                ),
+                None => {}
+                Some(old_port) => {
+                    if old_port.service != new_port.service {
+                        entries.push(ScanDiffEntry::ServiceChanged(
+                            *ip,
+                            *port,
+                            old_port.service.clone(),
+                            new_port.service.clone(),
+                        ));
+                    }
+                    // Banner grabbing is optional, missing one doesn't mean it changed
+                    let has_both_banners = old_port.banner.is_some() && new_port.banner.is_some();
+                    if has_both_banners && old_port.banner != new_port.banner {
+                        entries.push(ScanDiffEntry::BannerChanged(
+                            *ip,
+                            *port,
+                            old_port.banner.clone().unwrap_or_default(),
+                            new_port.banner.clone().unwrap_or_default(),
+                        ));
+                    }
+                }
+            }
+        }
+        entries.extend(
+            old_host
+                .ports
+                .iter()
+                .filter(|s| !new_host.ports.contains_key(s.0) && newer.scanned_ports.contains(s.0))
+                .map(|(port, old_port)| {
+                    ScanDiffEntry::PortClosed(*ip, *port, old_port.service.clone())
+                }),
+        );
+    }
+    entries.extend(
+        older
+            .hosts
+            .keys()
+            .filter(|s| !newer.hosts.contains_key(s) && newer.has_scanned(**s))
+            .map(|s| ScanDiffEntry::HostRemoved(*s)),
+    );
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::targets::TargetRange;
+
+    use super::*;
+
+    fn snapshot(
+        targets: &str,
+        scanned_ports: Vec<Port>,
+        hosts: &[(&str, &[Port])],
+    ) -> ScanSnapshot {
+        ScanSnapshot {
+            id: 0,
+            taken_at: Local::now(),
+            description: "".to_owned(),
+            scanned_targets: TargetList {
+                entries: vec![TargetRange::parse(targets).unwrap()],
+            },
+            excluded_targets: TargetExclusions::default(),
+            scanned_ports,
+            hosts: hosts
+                .iter()
+                .map(|(ip, ports)| {
+                    let ports = ports
+                        .iter()
+                        .map(|s| {
+                            let port = PortSnapshot {
+                                service: "".to_owned(),
+                                banner: None,
+                            };
+                            (*s, port)
+                        })
+                        .collect();
+                    let host = HostSnapshot {
+                        hostname: "".to_owned(),
+                        ports,
+                    };
+                    (ip.parse().unwrap(), host)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn port_scans_of_different_hosts() {
+        let older = snapshot("10.0.0.1", vec![22, 80], &[("10.0.0.1", &[22])]);
+        let newer = snapshot("10.0.0.2", vec![22, 80], &[("10.0.0.2", &[80])]);
+        assert!(diff_scan_snapshots(&older, &newer).is_empty());
+    }
+
+    #[test]
+    fn ping_sweeps_of_overlapping_ranges() {
+        let older = snapshot(
+            "10.0.0.0/30",
+            vec![],
+            &[("10.0.0.1", &[]), ("10.0.0.2", &[])],
+        );
+        let newer = snapshot(
+            "10.0.0.2-10.0.0.5",
+            vec![],
+            &[("10.0.0.3", &[]), ("10.0.0.5", &[])],
+        );
+        assert_eq!(
+            diff_scan_snapshots(&older, &newer),
+            vec![
+                ScanDiffEntry::HostAdded("10.0.0.3".parse().unwrap(), vec![]),
+                ScanDiffEntry::HostRemoved("10.0.0.2".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn excluded_hosts_are_not_removed() {
+        let older = snapshot("10.0.0.0/30", vec![], &[("10.0.0.1", &[])]);
+        let mut newer = snapshot("10.0.0.0/30", vec![], &[]);
+        newer.excluded_targets = TargetExclusions::parse("10.0.0.1").unwrap();
+        assert!(diff_scan_snapshots(&older, &newer).is_empty());
+    }
+
+    #[test]
+    fn port_changes_of_the_same_host() {
+        let older = snapshot("10.0.0.1", vec![22, 80, 443], &[("10.0.0.1", &[22, 80])]);
+        let newer = snapshot("10.0.0.1", vec![22, 80], &[("10.0.0.1", &[80])]);
+        let ip = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            diff_scan_snapshots(&older, &newer),
+            vec![ScanDiffEntry::PortClosed(ip, 22, "".to_owned())]
+        );
+        assert_eq!(
+            diff_scan_snapshots(&newer, &older),
+            vec![ScanDiffEntry::PortOpened(ip, 22, "".to_owned())]
+        );
+    }
+
+    #[test]
+    fn every_port_closed() {
+        let older = snapshot("10.0.0.1", vec![22, 80], &[("10.0.0.1", &[22, 80])]);
+        let newer = snapshot("10.0.0.1", vec![22, 80], &[("10.0.0.1", &[])]);
+        let ip = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            diff_scan_snapshots(&older, &newer),
+            vec![
+                ScanDiffEntry::PortClosed(ip, 22, "".to_owned()),
+                ScanDiffEntry::PortClosed(ip, 80, "".to_owned()),
+            ]
+        );
+    }
+}
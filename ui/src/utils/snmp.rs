@@ -0,0 +1,935 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use pnet::util::MacAddr;
+use rand::random;
+
+use crate::app::{
+    network_topology::{HostnameSource, NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{interfaces::SourceBinding, targets::TargetExclusions};
+
+pub const SNMP_PORT: u16 = 161;
+pub const DEFAULT_SNMP_TIMEOUT_MS: u64 = 1000;
+pub const DEFAULT_SNMP_COMMUNITIES: &str = "public\nprivate";
+
+// Safety net against agents that return the same oid over and over again
+const SNMP_MAX_WALK_ENTRIES: usize = 2048;
+
+const BER_TAG_INTEGER: u8 = 0x02;
+const BER_TAG_OCTET_STRING: u8 = 0x04;
+const BER_TAG_NULL: u8 = 0x05;
+const BER_TAG_OBJECT_ID: u8 = 0x06;
+const BER_TAG_SEQUENCE: u8 = 0x30;
+const SNMP_TAG_IP_ADDRESS: u8 = 0x40;
+const SNMP_TAG_COUNTER32: u8 = 0x41;
+const SNMP_TAG_GAUGE32: u8 = 0x42;
+const SNMP_TAG_TIME_TICKS: u8 = 0x43;
+const SNMP_TAG_COUNTER64: u8 = 0x46;
+const SNMP_TAG_NO_SUCH_OBJECT: u8 = 0x80;
+const SNMP_TAG_NO_SUCH_INSTANCE: u8 = 0x81;
+const SNMP_TAG_END_OF_MIB_VIEW: u8 = 0x82;
+const SNMP_PDU_GET_REQUEST: u8 = 0xa0;
+const SNMP_PDU_GET_NEXT_REQUEST: u8 = 0xa1;
+const SNMP_PDU_RESPONSE: u8 = 0xa2;
+
+// System group (RFC 1213)
+const OID_SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+const OID_SYS_OBJECT_ID: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 2, 0];
+const OID_SYS_UPTIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+const OID_SYS_CONTACT: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 4, 0];
+const OID_SYS_NAME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 5, 0];
+const OID_SYS_LOCATION: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 6, 0];
+// Interface table columns, indexed by ifIndex
+const OID_IF_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2];
+const OID_IF_PHYS_ADDRESS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 6];
+const OID_IF_OPER_STATUS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8];
+// ipNetToMediaPhysAddress, indexed by ifIndex.a.b.c.d
+const OID_IP_NET_TO_MEDIA_PHYS_ADDRESS: &[u32] = &[1, 3, 6, 1, 2, 1, 4, 22, 1, 2];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SnmpVersion {
+    V1,
+    V2c,
+}
+impl SnmpVersion {
+    fn to_ber_value(self) -> i64 {
+        match self {
+            SnmpVersion::V1 => 0,
+            SnmpVersion::V2c => 1,
+        }
+    }
+}
+impl Display for SnmpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnmpVersion::V1 => write!(f, "v1"),
+            SnmpVersion::V2c => write!(f, "v2c"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpProbeConfig {
+    pub communities: Vec<String>,
+    pub version: SnmpVersion,
+    pub walk_tables: bool,
+    pub timeout_ms: u64,
+    pub exclusions: TargetExclusions,
+    pub source: SourceBinding,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpInterface {
+    pub index: u32,
+    pub description: String,
+    pub mac: Option<MacAddr>,
+    pub is_up: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpArpEntry {
+    pub interface_index: u32,
+    pub ip: Ipv4Addr,
+    pub mac: MacAddr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnmpInfo {
+    pub version: SnmpVersion,
+    pub community: String,
+    pub sys_descr: String,
+    pub sys_object_id: String,
+    pub sys_uptime: Option<Duration>,
+    pub sys_contact: String,
+    pub sys_name: String,
+    pub sys_location: String,
+    pub interfaces: Vec<SnmpInterface>,
+    pub arp_entries: Vec<SnmpArpEntry>,
+}
+impl SnmpInfo {
+    pub fn uptime_label(&self) -> String {
+        let Some(uptime) = self.sys_uptime else {
+            return "unknown".to_owned();
+        };
+        let seconds = uptime.as_secs();
+        format!(
+            "{}d {}h {}m {}s",
+            seconds / 86400,
+            (seconds % 86400) / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnmpValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectId(Vec<u32>),
+    IpAddress(Ipv4Addr),
+    Unsigned(u64), // Counter32, Gauge32, Counter64
+    TimeTicks(u32),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+    Other(u8, Vec<u8>),
+}
+impl Display for SnmpValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnmpValue::Integer(s) => write!(f, "{}", s),
+            SnmpValue::OctetString(s) => write!(f, "{}", String::from_utf8_lossy(s).trim()),
+            SnmpValue::Null => write!(f, ""),
+            SnmpValue::ObjectId(s) => write!(f, "{}", oid_to_string(s)),
+            SnmpValue::IpAddress(s) => write!(f, "{}", s),
+            SnmpValue::Unsigned(s) => write!(f, "{}", s),
+            SnmpValue::TimeTicks(s) => write!(f, "{}", s),
+            SnmpValue::NoSuchObject => write!(f, "noSuchObject"),
+            SnmpValue::NoSuchInstance => write!(f, "noSuchInstance"),
+            SnmpValue::EndOfMibView => write!(f, "endOfMibView"),
+            SnmpValue::Other(tag, data) => write!(f, "0x{:02x}: {:02x?}", tag, data),
+        }
+    }
+}
+impl SnmpValue {
+    fn is_missing(&self) -> bool {
+        matches!(
+            self,
+            SnmpValue::Null
+                | SnmpValue::NoSuchObject
+                | SnmpValue::NoSuchInstance
+                | SnmpValue::EndOfMibView
+        )
+    }
+
+    fn to_mac(&self) -> Option<MacAddr> {
+        match self {
+            SnmpValue::OctetString(s) if s.len() == 6 => {
+                Some(MacAddr::new(s[0], s[1], s[2], s[3], s[4], s[5])).filter(|s| !s.is_zero())
+            }
+            _ => None,
+        }
+    }
+}
+
+pub type SnmpVarBind = (Vec<u32>, SnmpValue);
+
+fn oid_to_string(oid: &[u32]) -> String {
+    oid.iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+pub struct SnmpSession {
+    socket: UdpSocket,
+    target: SocketAddr,
+    version: SnmpVersion,
+    community: String,
+}
+impl SnmpSession {
+    pub fn new(
+        target: SocketAddr,
+        version: SnmpVersion,
+        community: &str,
+        timeout: Duration,
+        source: &SourceBinding,
+    ) -> anyhow::Result<Self> {
+        let socket = source.bind_udp(target.ip())?;
+        socket.set_read_timeout(Some(timeout))?;
+        Ok(Self {
+            socket,
+            target,
+            version,
+            community: community.to_owned(),
+        })
+    }
+
+    pub fn get(&self, oids: &[&[u32]]) -> anyhow::Result<Vec<SnmpVarBind>> {
+        self.request(SNMP_PDU_GET_REQUEST, oids)
+    }
+
+    pub fn get_next(&self, oid: &[u32]) -> anyhow::Result<Option<SnmpVarBind>> {
+        Ok(self
+            .request(SNMP_PDU_GET_NEXT_REQUEST, &[oid])?
+            .into_iter()
+            .next())
+    }
+
+    // GetNext until we leave the subtree (GetBulk would be faster, but v1 doesn't support it)
+    pub fn walk(&self, root: &[u32]) -> anyhow::Result<Vec<SnmpVarBind>> {
+        let mut results = vec![];
+        let mut current_oid = root.to_vec();
+        while results.len() < SNMP_MAX_WALK_ENTRIES {
+            let next = match self.get_next(&current_oid) {
+                Ok(s) => s,
+                // v1 agents signal end of the mib with noSuchName error
+                Err(_) if self.version == SnmpVersion::V1 && !results.is_empty() => break,
+                Err(e) => return Err(e),
+            };
+            let Some((oid, value)) = next else {
+                break;
+            };
+            if !oid.starts_with(root) || oid <= current_oid || value == SnmpValue::EndOfMibView {
+                break;
+            }
+            current_oid = oid.clone();
+            results.push((oid, value));
+        }
+
+        Ok(results)
+    }
+
+    fn request(&self, pdu_type: u8, oids: &[&[u32]]) -> anyhow::Result<Vec<SnmpVarBind>> {
+        let request_id = random::<u16>() as i64;
+        let message = build_snmp_message(self.version, &self.community, pdu_type, request_id, oids);
+        self.socket.send_to(&message, self.target)?;
+
+        let mut buffer = [0u8; 65535];
+        loop {
+            let (bytes_read, responder) = self.socket.recv_from(&mut buffer)?;
+            if responder.ip() != self.target.ip() {
+                continue;
+            }
+            let Ok((response_id, error_status, var_binds)) =
+                parse_snmp_response(&buffer[..bytes_read])
+            else {
+                continue;
+            };
+            if response_id != request_id {
+                continue;
+            }
+            if error_status != 0 {
+                return Err(anyhow!("SNMP agent returned error status {}", error_status));
+            }
+            return Ok(var_binds);
+        }
+    }
+}
+
+// Tries communities one by one, first one that gets an answer wins
+pub fn query_snmp_info(target: SocketAddr, config: &SnmpProbeConfig) -> anyhow::Result<SnmpInfo> {
+    let timeout = Duration::from_millis(config.timeout_ms);
+    for community in config.communities.iter() {
+        let session = SnmpSession::new(target, config.version, community, timeout, &config.source)?;
+        let Ok(system_values) = session.get(&[
+            OID_SYS_DESCR,
+            OID_SYS_OBJECT_ID,
+            OID_SYS_UPTIME,
+            OID_SYS_CONTACT,
+            OID_SYS_NAME,
+            OID_SYS_LOCATION,
+        ]) else {
+            continue;
+        };
+
+        let value_of = |oid: &[u32]| {
+            system_values
+                .iter()
+                .find(|s| s.0 == oid && !s.1.is_missing())
+                .map(|s| s.1.to_string())
+                .unwrap_or_default()
+        };
+        let mut snmp_info = SnmpInfo {
+            version: config.version,
+            community: community.clone(),
+            sys_descr: value_of(OID_SYS_DESCR),
+            sys_object_id: value_of(OID_SYS_OBJECT_ID),
+            sys_uptime: system_values.iter().find_map(|s| match s.1 {
+                SnmpValue::TimeTicks(ticks) if s.0 == OID_SYS_UPTIME => {
+                    Some(Duration::from_millis(ticks as u64 * 10))
+                }
+                _ => None,
+            }),
+            sys_contact: value_of(OID_SYS_CONTACT),
+            sys_name: value_of(OID_SYS_NAME),
+            sys_location: value_of(OID_SYS_LOCATION),
+            interfaces: vec![],
+            arp_entries: vec![],
+        };
+
+        // Not every agent exposes the tables to every community, system info is still worth keeping
+        if config.walk_tables {
+            snmp_info.interfaces = walk_interfaces(&session).unwrap_or_default();
+            snmp_info.arp_entries = walk_arp_table(&session).unwrap_or_default();
+        }
+
+        return Ok(snmp_info);
+    }
+
+    Err(anyhow!("No community got an answer from {}", target))
+}
+
+fn walk_interfaces(session: &SnmpSession) -> anyhow::Result<Vec<SnmpInterface>> {
+    let mut interfaces: BTreeMap<u32, SnmpInterface> = BTreeMap::new();
+    for (oid, value) in session.walk(OID_IF_DESCR)? {
+        let Some(index) = oid.last() else {
+            continue;
+        };
+        interfaces.insert(
+            *index,
+            SnmpInterface {
+                index: *index,
+                description: value.to_string(),
+                mac: None,
+                is_up: None,
+            },
+        );
+    }
+    for (oid, value) in session.walk(OID_IF_PHYS_ADDRESS)? {
+        if let Some(interface) = oid.last().and_then(|s| interfaces.get_mut(s)) {
+            interface.mac = value.to_mac();
+        }
+    }
+    for (oid, value) in session.walk(OID_IF_OPER_STATUS)? {
+        if let Some(interface) = oid.last().and_then(|s| interfaces.get_mut(s)) {
+            // 1 = up, 2 = down, everything else (testing, dormant, ...) is not really either
+            interface.is_up = match value {
+                SnmpValue::Integer(1) => Some(true),
+                SnmpValue::Integer(2) => Some(false),
+                _ => None,
+            };
+        }
+    }
+
+    Ok(interfaces.into_values().collect())
+}
+
+fn walk_arp_table(session: &SnmpSession) -> anyhow::Result<Vec<SnmpArpEntry>> {
+    let mut arp_entries = vec![];
+    for (oid, value) in session.walk(OID_IP_NET_TO_MEDIA_PHYS_ADDRESS)? {
+        let index = &oid[OID_IP_NET_TO_MEDIA_PHYS_ADDRESS.len()..];
+        let (Some(mac), [interface_index, a, b, c, d]) = (value.to_mac(), index) else {
+            continue;
+        };
+        arp_entries.push(SnmpArpEntry {
+            interface_index: *interface_index,
+            ip: Ipv4Addr::new(*a as u8, *b as u8, *c as u8, *d as u8),
+            mac,
+        });
+    }
+
+    Ok(arp_entries)
+}
+
+pub fn probe_snmp_list(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    ips_to_probe: Vec<IpAddr>,
+    config: SnmpProbeConfig,
+) {
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info(format!(
+            "Initiating SNMP {} probe of {} devices using {} communities.",
+            config.version,
+            ips_to_probe.len(),
+            config.communities.len()
+        )),
+    );
+
+    std::thread::spawn(move || {
+        let config = SnmpProbeConfig {
+            source: config.source.resolved(),
+            ..config
+        };
+        let mut answered = 0;
+        let mut excluded_ips_count = 0;
+        for ip in ips_to_probe {
+            if config.exclusions.is_excluded(ip) {
+                excluded_ips_count += 1;
+                continue;
+            }
+
+            let snmp_info = match query_snmp_info(SocketAddr::new(ip, SNMP_PORT), &config) {
+                Ok(s) => s,
+                Err(e) => {
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Info(format!("SNMP: {} => {}", ip, e)),
+                    );
+                    continue;
+                }
+            };
+
+            answered += 1;
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info(format!(
+                    "SNMP: {} answered to community \"{}\" => \"{}\" ({}), {} interfaces, {} arp entries",
+                    ip,
+                    snmp_info.community,
+                    snmp_info.sys_name,
+                    snmp_info.sys_descr,
+                    snmp_info.interfaces.len(),
+                    snmp_info.arp_entries.len()
+                )),
+            );
+
+            let now = chrono::Local::now();
+            let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(&mut graph_ref, ip);
+            NetworkTopology::update_node_data(&mut graph_ref, node_index, |node| {
+                node.first_seen.get_or_insert(now);
+                node.last_seen = Some(now);
+                node.add_hostname(&snmp_info.sys_name, HostnameSource::Snmp);
+                node.snmp_info = Some(snmp_info);
+            });
+        }
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Finished SNMP probe. {} devices answered.",
+                answered
+            )),
+        );
+        if excluded_ips_count > 0 {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info(format!("Skipped {} excluded targets.", excluded_ips_count)),
+            );
+        }
+    });
+}
+
+fn build_snmp_message(
+    version: SnmpVersion,
+    community: &str,
+    pdu_type: u8,
+    request_id: i64,
+    oids: &[&[u32]],
+) -> Vec<u8> {
+    let var_binds = oids
+        .iter()
+        .flat_map(|s| {
+            encode_tlv(
+                BER_TAG_SEQUENCE,
+                &[encode_oid(s), encode_tlv(BER_TAG_NULL, &[])].concat(),
+            )
+        })
+        .collect::<Vec<u8>>();
+    let pdu = encode_tlv(
+        pdu_type,
+        &[
+            encode_integer(request_id),
+            encode_integer(0), // error status
+            encode_integer(0), // error index
+            encode_tlv(BER_TAG_SEQUENCE, &var_binds),
+        ]
+        .concat(),
+    );
+
+    encode_tlv(
+        BER_TAG_SEQUENCE,
+        &[
+            encode_integer(version.to_ber_value()),
+            encode_tlv(BER_TAG_OCTET_STRING, community.as_bytes()),
+            pdu,
+        ]
+        .concat(),
+    )
+}
+
+// Returns (request id, error status, var binds)
+fn parse_snmp_response(message: &[u8]) -> anyhow::Result<(i64, i64, Vec<SnmpVarBind>)> {
+    let mut offset = 0;
+    let message = read_expected_tlv(message, &mut offset, BER_TAG_SEQUENCE)?;
+
+    let mut offset = 0;
+    read_expected_tlv(message, &mut offset, BER_TAG_INTEGER)?; // version
+    read_expected_tlv(message, &mut offset, BER_TAG_OCTET_STRING)?; // community
+    let pdu = read_expected_tlv(message, &mut offset, SNMP_PDU_RESPONSE)?;
+
+    let mut offset = 0;
+    let request_id = decode_integer(read_expected_tlv(pdu, &mut offset, BER_TAG_INTEGER)?);
+    let error_status = decode_integer(read_expected_tlv(pdu, &mut offset, BER_TAG_INTEGER)?);
+    read_expected_tlv(pdu, &mut offset, BER_TAG_INTEGER)?; // error index
+    let var_bind_list = read_expected_tlv(pdu, &mut offset, BER_TAG_SEQUENCE)?;
+
+    let mut var_binds = vec![];
+    let mut offset = 0;
+    while offset < var_bind_list.len() {
+        let var_bind = read_expected_tlv(var_bind_list, &mut offset, BER_TAG_SEQUENCE)?;
+        let mut var_bind_offset = 0;
+        let oid = decode_oid(read_expected_tlv(
+            var_bind,
+            &mut var_bind_offset,
+            BER_TAG_OBJECT_ID,
+        )?);
+        let (tag, value) = read_tlv(var_bind, &mut var_bind_offset)?;
+        var_binds.push((oid, decode_value(tag, value)));
+    }
+
+    Ok((request_id, error_status, var_binds))
+}
+
+fn decode_value(tag: u8, value: &[u8]) -> SnmpValue {
+    match tag {
+        BER_TAG_INTEGER => SnmpValue::Integer(decode_integer(value)),
+        BER_TAG_OCTET_STRING => SnmpValue::OctetString(value.to_vec()),
+        BER_TAG_NULL => SnmpValue::Null,
+        BER_TAG_OBJECT_ID => SnmpValue::ObjectId(decode_oid(value)),
+        SNMP_TAG_IP_ADDRESS if value.len() == 4 => {
+            SnmpValue::IpAddress(Ipv4Addr::new(value[0], value[1], value[2], value[3]))
+        }
+        SNMP_TAG_COUNTER32 | SNMP_TAG_GAUGE32 | SNMP_TAG_COUNTER64 => {
+            SnmpValue::Unsigned(decode_unsigned(value))
+        }
+        SNMP_TAG_TIME_TICKS => SnmpValue::TimeTicks(decode_unsigned(value) as u32),
+        SNMP_TAG_NO_SUCH_OBJECT => SnmpValue::NoSuchObject,
+        SNMP_TAG_NO_SUCH_INSTANCE => SnmpValue::NoSuchInstance,
+        SNMP_TAG_END_OF_MIB_VIEW => SnmpValue::EndOfMibView,
+        _ => SnmpValue::Other(tag, value.to_vec()),
+    }
+}
+
+fn read_tlv<'a>(data: &'a [u8], offset: &mut usize) -> anyhow::Result<(u8, &'a [u8])> {
+    let truncated = || anyhow!("BER data is truncated");
+    let tag = *data.get(*offset).ok_or_else(truncated)?;
+    let first_length_byte = *data.get(*offset + 1).ok_or_else(truncated)?;
+    *offset += 2;
+
+    // Short form => length itself, long form => number of following length bytes
+    let length = if first_length_byte & 0x80 == 0 {
+        first_length_byte as usize
+    } else {
+        let length_bytes_count = (first_length_byte & 0x7f) as usize;
+        if length_bytes_count == 0 || length_bytes_count > 4 {
+            return Err(anyhow!("Unsupported BER length"));
+        }
+        let length_bytes = data
+            .get(*offset..*offset + length_bytes_count)
+            .ok_or_else(truncated)?;
+        *offset += length_bytes_count;
+        length_bytes
+            .iter()
+            .fold(0usize, |acc, s| (acc << 8) | *s as usize)
+    };
+
+    let value_end = offset.checked_add(length).ok_or_else(truncated)?;
+    let value = data.get(*offset..value_end).ok_or_else(truncated)?;
+    *offset = value_end;
+    Ok((tag, value))
+}
+
+fn read_expected_tlv<'a>(
+    data: &'a [u8],
+    offset: &mut usize,
+    expected_tag: u8,
+) -> anyhow::Result<&'a [u8]> {
+    let (tag, value) = read_tlv(data, offset)?;
+    if tag != expected_tag {
+        return Err(anyhow!(
+            "Unexpected BER tag 0x{:02x} (expected 0x{:02x})",
+            tag,
+            expected_tag
+        ));
+    }
+    Ok(value)
+}
+
+fn encode_tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    if value.len() < 0x80 {
+        encoded.push(value.len() as u8);
+    } else {
+        let length_bytes = (value.len() as u32)
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|s| *s == 0)
+            .collect::<Vec<u8>>();
+        encoded.push(0x80 | length_bytes.len() as u8);
+        encoded.extend(length_bytes);
+    }
+    encoded.extend_from_slice(value);
+    encoded
+}
+
+// Minimal two's complement representation
+fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < bytes.len() - 1
+        && ((bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+    encode_tlv(BER_TAG_INTEGER, &bytes[start..])
+}
+
+fn decode_integer(value: &[u8]) -> i64 {
+    let initial = if value.first().is_some_and(|s| s & 0x80 != 0) {
+        -1
+    } else {
+        0
+    };
+    value
+        .iter()
+        .take(8)
+        .fold(initial, |acc: i64, s| (acc << 8) | *s as i64)
+}
+
+fn decode_unsigned(value: &[u8]) -> u64 {
+    value
+        .iter()
+        .take(9)
+        .fold(0u64, |acc, s| (acc << 8) | *s as u64)
+}
+
+// First two arcs are packed into one byte, the rest is base 128 with the top bit as "more bytes follow"
+fn encode_oid(oid: &[u32]) -> Vec<u8> {
+    let mut encoded = vec![];
+    if oid.len() >= 2 {
+        encoded.push((oid[0] * 40 + oid[1]) as u8);
+    }
+    for arc in oid.iter().skip(2) {
+        let mut arc_bytes = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            arc_bytes.push(0x80 | (rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        encoded.extend(arc_bytes.iter().rev());
+    }
+    encode_tlv(BER_TAG_OBJECT_ID, &encoded)
+}
+
+fn decode_oid(value: &[u8]) -> Vec<u32> {
+    let Some(first_byte) = value.first() else {
+        return vec![];
+    };
+    let mut oid = vec![(first_byte / 40) as u32, (first_byte % 40) as u32];
+    let mut current_arc = 0u32;
+    for byte in value.iter().skip(1) {
+        current_arc = (current_arc << 7) | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            oid.push(current_arc);
+            current_arc = 0;
+        }
+    }
+    oid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_var_bind(oid: &[u32], value: Vec<u8>) -> Vec<u8> {
+        encode_tlv(BER_TAG_SEQUENCE, &[encode_oid(oid), value].concat())
+    }
+
+    fn get_response(request_id: i64, error_status: i64, var_binds: &[Vec<u8>]) -> Vec<u8> {
+        let pdu = encode_tlv(
+            SNMP_PDU_RESPONSE,
+            &[
+                encode_integer(request_id),
+                encode_integer(error_status),
+                encode_integer(0),
+                encode_tlv(BER_TAG_SEQUENCE, &var_binds.concat()),
+            ]
+            .concat(),
+        );
+        encode_tlv(
+            BER_TAG_SEQUENCE,
+            &[
+                encode_integer(SnmpVersion::V2c.to_ber_value()),
+                encode_tlv(BER_TAG_OCTET_STRING, b"public"),
+                pdu,
+            ]
+            .concat(),
+        )
+    }
+
+    fn sample_response() -> Vec<u8> {
+        get_response(
+            4660,
+            0,
+            &[
+                encode_var_bind(
+                    OID_SYS_DESCR,
+                    encode_tlv(BER_TAG_OCTET_STRING, &[b'x'; 200]),
+                ),
+                encode_var_bind(
+                    OID_SYS_OBJECT_ID,
+                    encode_oid(&[1, 3, 6, 1, 4, 1, 8072, 3, 2, 10]),
+                ),
+                encode_var_bind(
+                    OID_SYS_UPTIME,
+                    encode_tlv(SNMP_TAG_TIME_TICKS, &[0x00, 0x9a, 0x7e, 0xc8]),
+                ),
+                encode_var_bind(
+                    &[1, 3, 6, 1, 2, 1, 4, 20, 1, 1, 10, 0, 0, 1],
+                    encode_tlv(SNMP_TAG_IP_ADDRESS, &[10, 0, 0, 1]),
+                ),
+                encode_var_bind(
+                    &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1],
+                    encode_tlv(SNMP_TAG_COUNTER64, &[0x00, 0xff, 0, 0, 0, 0, 0, 0, 0x01]),
+                ),
+                encode_var_bind(OID_SYS_CONTACT, encode_tlv(SNMP_TAG_NO_SUCH_OBJECT, &[])),
+                encode_var_bind(OID_SYS_LOCATION, encode_tlv(SNMP_TAG_END_OF_MIB_VIEW, &[])),
+            ],
+        )
+    }
+
+    #[test]
+    fn get_request_encoding() {
+        let message = build_snmp_message(
+            SnmpVersion::V2c,
+            "public",
+            SNMP_PDU_GET_REQUEST,
+            1,
+            &[OID_SYS_DESCR],
+        );
+        #[rustfmt::skip]
+        let expected = [
+            0x30, 0x26,
+            0x02, 0x01, 0x01, // version
+            0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c',
+            0xa0, 0x19,
+            0x02, 0x01, 0x01, // request id
+            0x02, 0x01, 0x00, // error status
+            0x02, 0x01, 0x00, // error index
+            0x30, 0x0e,
+            0x30, 0x0c,
+            0x06, 0x08, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00,
+            0x05, 0x00,
+        ];
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn get_response_parsing() {
+        let (request_id, error_status, var_binds) =
+            parse_snmp_response(&sample_response()).unwrap();
+        assert_eq!(request_id, 4660);
+        assert_eq!(error_status, 0);
+        assert_eq!(
+            var_binds,
+            vec![
+                (
+                    OID_SYS_DESCR.to_vec(),
+                    SnmpValue::OctetString(vec![b'x'; 200])
+                ),
+                (
+                    OID_SYS_OBJECT_ID.to_vec(),
+                    SnmpValue::ObjectId(vec![1, 3, 6, 1, 4, 1, 8072, 3, 2, 10])
+                ),
+                (OID_SYS_UPTIME.to_vec(), SnmpValue::TimeTicks(10125000)),
+                (
+                    vec![1, 3, 6, 1, 2, 1, 4, 20, 1, 1, 10, 0, 0, 1],
+                    SnmpValue::IpAddress(Ipv4Addr::new(10, 0, 0, 1))
+                ),
+                (
+                    vec![1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1],
+                    SnmpValue::Unsigned(0xff00_0000_0000_0001)
+                ),
+                (OID_SYS_CONTACT.to_vec(), SnmpValue::NoSuchObject),
+                (OID_SYS_LOCATION.to_vec(), SnmpValue::EndOfMibView),
+            ]
+        );
+
+        let (_, error_status, _) = parse_snmp_response(&get_response(1, 2, &[])).unwrap();
+        assert_eq!(error_status, 2);
+
+        // Our own request isn't a response
+        let request = build_snmp_message(
+            SnmpVersion::V1,
+            "public",
+            SNMP_PDU_GET_NEXT_REQUEST,
+            1,
+            &[OID_SYS_NAME],
+        );
+        assert!(parse_snmp_response(&request).is_err());
+    }
+
+    #[test]
+    fn integer_round_trip() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            255,
+            256,
+            -1,
+            -128,
+            -129,
+            i32::MAX as i64,
+            i64::MIN,
+            i64::MAX,
+        ] {
+            let encoded = encode_integer(value);
+            let mut offset = 0;
+            let decoded =
+                decode_integer(read_expected_tlv(&encoded, &mut offset, BER_TAG_INTEGER).unwrap());
+            assert_eq!(decoded, value);
+            assert_eq!(offset, encoded.len());
+        }
+        // Shortest form, with a leading byte only when the sign bit would flip
+        assert_eq!(encode_integer(127), [0x02, 0x01, 0x7f]);
+        assert_eq!(encode_integer(128), [0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(encode_integer(-128), [0x02, 0x01, 0x80]);
+        assert_eq!(encode_integer(-129), [0x02, 0x02, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn oid_round_trip() {
+        for oid in [
+            OID_SYS_DESCR.to_vec(),
+            OID_IP_NET_TO_MEDIA_PHYS_ADDRESS.to_vec(),
+            vec![1, 3, 6, 1, 4, 1, 311, 21, 20],
+            vec![1, 3, 6, 1, 4, 1, 16383, 16384, 2097152, u32::MAX],
+        ] {
+            let encoded = encode_oid(&oid);
+            let mut offset = 0;
+            let decoded =
+                decode_oid(read_expected_tlv(&encoded, &mut offset, BER_TAG_OBJECT_ID).unwrap());
+            assert_eq!(decoded, oid);
+        }
+        // 311 doesn't fit into 7 bits
+        assert_eq!(
+            encode_oid(&[1, 3, 6, 1, 4, 1, 311]),
+            [0x06, 0x07, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37]
+        );
+    }
+
+    #[test]
+    fn length_forms() {
+        for len in [0, 1, 0x7f, 0x80, 0xff, 0x100, 300, 0x1_0000] {
+            let value = vec![0xab; len];
+            let encoded = encode_tlv(BER_TAG_OCTET_STRING, &value);
+            let expected_header_len = match len {
+                0..=0x7f => 2,
+                0x80..=0xff => 3,
+                0x100..=0xffff => 4,
+                _ => 5,
+            };
+            assert_eq!(encoded.len(), expected_header_len + len);
+
+            let mut offset = 0;
+            let (tag, decoded) = read_tlv(&encoded, &mut offset).unwrap();
+            assert_eq!(tag, BER_TAG_OCTET_STRING);
+            assert_eq!(decoded, value);
+            assert_eq!(offset, encoded.len());
+        }
+        assert_eq!(
+            encode_tlv(BER_TAG_OCTET_STRING, &[0; 300])[..4],
+            [0x04, 0x82, 0x01, 0x2c]
+        );
+
+        // Long form with a non-minimal length is still fine
+        let mut offset = 0;
+        let (_, value) = read_tlv(&[0x04, 0x81, 0x02, 0xaa, 0xbb], &mut offset).unwrap();
+        assert_eq!(value, [0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn malformed_lengths() {
+        for data in [
+            &[][..],
+            &[0x04],
+            &[0x04, 0x05, 0x01, 0x02], // shorter than its length
+            &[0x04, 0x80, 0x00, 0x00], // indefinite
+            &[0x04, 0x85, 0x01, 0x01, 0x01, 0x01, 0x01], // more length bytes than supported
+            &[0x04, 0x82, 0x01],       // truncated length
+            &[0x04, 0x84, 0xff, 0xff, 0xff, 0xff, 0x00], // way past the end
+        ] {
+            let mut offset = 0;
+            assert!(read_tlv(data, &mut offset).is_err(), "{:02x?}", data);
+        }
+    }
+
+    #[test]
+    fn malformed_responses_never_panic() {
+        let response = sample_response();
+        for len in 0..response.len() {
+            assert!(parse_snmp_response(&response[..len]).is_err());
+        }
+
+        for position in 0..response.len() {
+            for byte in [0x00, 0x01, 0x7f, 0x80, 0x81, 0x84, 0x85, 0xff] {
+                let mut corrupted = response.clone();
+                corrupted[position] = byte;
+                _ = parse_snmp_response(&corrupted);
+            }
+        }
+
+        // Dangling "more bytes follow" and arcs longer than 32 bits
+        assert_eq!(decode_oid(&[0x2b, 0x06, 0x81]), vec![1, 3, 6]);
+        decode_oid(&[0x2b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+        assert_eq!(decode_oid(&[]), Vec::<u32>::new());
+        decode_integer(&[0xff; 16]);
+        decode_unsigned(&[0xff; 16]);
+    }
+}
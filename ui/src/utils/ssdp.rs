@@ -0,0 +1,409 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use log::debug;
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph, PortInfo},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{interfaces::SourceBinding, ip::Port, targets::TargetExclusions};
+
+pub const SSDP_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+pub const SSDP_PORT: u16 = 1900;
+pub const DEFAULT_SSDP_RESPONSE_WAIT_MS: u64 = 3000;
+
+const SSDP_SEARCH_MAX_WAIT_S: u64 = 2;
+const SSDP_SOCKET_READ_TIMEOUT_MS: u64 = 100;
+const UPNP_DESCRIPTION_TIMEOUT_MS: u64 = 2000;
+const UPNP_DESCRIPTION_MAX_SIZE: usize = 512 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpnpDeviceInfo {
+    pub location: String,
+    pub server: String,
+    pub device_type: String,
+    pub friendly_name: String,
+    pub manufacturer: String,
+    pub model_name: String,
+    pub model_number: String,
+    pub model_description: String,
+    pub services: Vec<String>,
+}
+impl UpnpDeviceInfo {
+    pub fn title(&self) -> String {
+        let title = [
+            self.friendly_name.as_str(),
+            self.manufacturer.as_str(),
+            self.model_name.as_str(),
+        ]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<&str>>()
+        .join(" - ");
+
+        if title.is_empty() {
+            self.location.clone()
+        } else {
+            title
+        }
+    }
+}
+
+// Headers of a M-SEARCH response, keys are uppercase
+struct SsdpResponse {
+    responder: IpAddr,
+    headers: BTreeMap<String, String>,
+}
+
+// Plain http url with a literal ip, hostnames aren't resolved
+#[derive(Debug, PartialEq)]
+struct HttpLocation {
+    authority: String,
+    address: SocketAddr,
+    path: String,
+}
+
+pub fn discover_ssdp_devices(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    response_wait_ms: u64,
+    exclusions: TargetExclusions,
+    source: SourceBinding,
+) {
+    AppState::log_to_status_generic(
+        &status_info_ref,
+        StatusMessage::Info("Initiating SSDP / UPnP discovery.".to_owned()),
+    );
+
+    std::thread::spawn(move || {
+        let source = source.resolved();
+        let responses = match send_ssdp_search(response_wait_ms) {
+            Ok(s) => s,
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    &status_info_ref,
+                    StatusMessage::Err(format!("Unable to perform SSDP search => {:?}", e)),
+                );
+                return;
+            }
+        };
+
+        // Every device answers once per service / embedded device, but all of them point to the same few descriptions
+        let mut locations: BTreeMap<String, (IpAddr, String)> = BTreeMap::new();
+        for response in responses.iter() {
+            let Some(location) = response.headers.get("LOCATION") else {
+                continue;
+            };
+            locations.entry(location.clone()).or_insert((
+                response.responder,
+                response.headers.get("SERVER").cloned().unwrap_or_default(),
+            ));
+        }
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "SSDP received {} responses pointing to {} device descriptions.",
+                responses.len(),
+                locations.len()
+            )),
+        );
+
+        for (location, (responder, server)) in locations {
+            let mut device_info = UpnpDeviceInfo {
+                location: location.clone(),
+                server,
+                ..Default::default()
+            };
+            // Anyone can answer with any url, only follow the ones pointing back to the responder
+            let description_location = match parse_http_location(&location) {
+                Ok(s) if s.address.ip() != responder => {
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Warn(format!(
+                            "Not fetching UPnP description from {}, it doesn't point to the responder {}",
+                            location, responder
+                        )),
+                    );
+                    None
+                }
+                Ok(_) if exclusions.is_excluded(responder) => {
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Info(format!(
+                            "Not fetching UPnP description from {}, {} is excluded from probing",
+                            location, responder
+                        )),
+                    );
+                    None
+                }
+                Ok(s) => Some(s),
+                Err(e) => {
+                    AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Warn(format!(
+                            "Not fetching UPnP description from {} => {}",
+                            location, e
+                        )),
+                    );
+                    None
+                }
+            };
+            if let Some(description_location) = &description_location {
+                match fetch_http_document(description_location, &source) {
+                    Ok(description) => parse_upnp_description(&description, &mut device_info),
+                    Err(e) => AppState::log_to_status_generic(
+                        &status_info_ref,
+                        StatusMessage::Warn(format!(
+                            "Unable to fetch UPnP description from {} => {}",
+                            location, e
+                        )),
+                    ),
+                }
+            }
+
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Info(format!(
+                    "UPnP: {} is \"{}\" exposing {} services",
+                    responder,
+                    device_info.title(),
+                    device_info.services.len()
+                )),
+            );
+            record_upnp_device(
+                &mut graph_ref,
+                responder,
+                description_location.map(|s| s.address.port()),
+                device_info,
+            );
+        }
+
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info("Finished SSDP / UPnP discovery.".to_owned()),
+        );
+    });
+}
+
+fn send_ssdp_search(response_wait_ms: u64) -> anyhow::Result<Vec<SsdpResponse>> {
+    let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0))?;
+    socket.set_read_timeout(Some(Duration::from_millis(SSDP_SOCKET_READ_TIMEOUT_MS)))?;
+    socket.set_multicast_ttl_v4(2)?;
+
+    let search_request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}:{}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: ssdp:all\r\n\r\n",
+        SSDP_MULTICAST_ADDR, SSDP_PORT, SSDP_SEARCH_MAX_WAIT_S
+    );
+    socket.send_to(
+        search_request.as_bytes(),
+        SocketAddr::new(IpAddr::V4(SSDP_MULTICAST_ADDR), SSDP_PORT),
+    )?;
+
+    let mut responses = vec![];
+    let started_at = Instant::now();
+    let mut buffer = [0u8; 4096];
+    while started_at.elapsed() < Duration::from_millis(response_wait_ms) {
+        let Ok((bytes_read, responder)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let response = String::from_utf8_lossy(&buffer[..bytes_read]);
+        debug!("SSDP response from {}: {}", responder, response);
+        responses.push(SsdpResponse {
+            responder: responder.ip(),
+            headers: parse_http_headers(&response),
+        });
+    }
+
+    Ok(responses)
+}
+
+fn parse_http_headers(response: &str) -> BTreeMap<String, String> {
+    response
+        .lines()
+        .skip(1) // status line
+        .take_while(|s| !s.trim().is_empty())
+        .filter_map(|s| s.split_once(':'))
+        .map(|(key, value)| (key.trim().to_uppercase(), value.trim().to_owned()))
+        .collect()
+}
+
+// Only plain http is supported, that's what (pretty much) every UPnP device uses for descriptions
+fn parse_http_location(url: &str) -> anyhow::Result<HttpLocation> {
+    let Some(url_without_scheme) = url.strip_prefix("http://") else {
+        return Err(anyhow!("Unsupported url scheme"));
+    };
+    let (authority, path) = match url_without_scheme.find('/') {
+        Some(s) => url_without_scheme.split_at(s),
+        None => (url_without_scheme, "/"),
+    };
+    let address = match authority.parse::<SocketAddr>() {
+        Ok(s) => s,
+        Err(_) => {
+            let host = authority.trim_start_matches('[').trim_end_matches(']');
+            let Ok(ip) = host.parse::<IpAddr>() else {
+                return Err(anyhow!("{} isn't an ip address", authority));
+            };
+            SocketAddr::new(ip, 80)
+        }
+    };
+
+    Ok(HttpLocation {
+        authority: authority.to_owned(),
+        address,
+        path: path.to_owned(),
+    })
+}
+
+fn fetch_http_document(location: &HttpLocation, source: &SourceBinding) -> anyhow::Result<String> {
+    let timeout = Duration::from_millis(UPNP_DESCRIPTION_TIMEOUT_MS);
+    let mut stream = source.connect_tcp(location.address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    // HTTP/1.0 => no chunked encoding and the server closes the connection once it's done
+    stream.write_all(
+        format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            location.path, location.authority
+        )
+        .as_bytes(),
+    )?;
+
+    let mut response = vec![];
+    stream
+        .take(UPNP_DESCRIPTION_MAX_SIZE as u64)
+        .read_to_end(&mut response)?;
+    let response = String::from_utf8_lossy(&response).into_owned();
+
+    let Some((head, body)) = response.split_once("\r\n\r\n") else {
+        return Err(anyhow!("Malformed http response"));
+    };
+    let status_line = head.lines().next().unwrap_or_default();
+    if !status_line.contains(" 200") {
+        return Err(anyhow!("Unexpected response \"{}\"", status_line));
+    }
+
+    Ok(body.to_owned())
+}
+
+// The description is simple enough, that there's no need for a full blown xml parser
+fn parse_upnp_description(description: &str, device_info: &mut UpnpDeviceInfo) {
+    let first_value = |tag: &str| {
+        extract_xml_tag_values(description, tag)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    };
+
+    device_info.device_type = first_value("deviceType");
+    device_info.friendly_name = first_value("friendlyName");
+    device_info.manufacturer = first_value("manufacturer");
+    device_info.model_name = first_value("modelName");
+    device_info.model_number = first_value("modelNumber");
+    device_info.model_description = first_value("modelDescription");
+    device_info.services = extract_xml_tag_values(description, "serviceType");
+}
+
+fn extract_xml_tag_values(xml: &str, tag: &str) -> Vec<String> {
+    let mut values = vec![];
+    let opening_tag = format!("<{}>", tag);
+    let closing_tag = format!("</{}>", tag);
+
+    let mut rest = xml;
+    while let Some(start) = rest.find(&opening_tag) {
+        rest = &rest[start + opening_tag.len()..];
+        let Some(end) = rest.find(&closing_tag) else {
+            break;
+        };
+        values.push(decode_xml_entities(rest[..end].trim()));
+        rest = &rest[end + closing_tag.len()..];
+    }
+
+    values
+}
+
+fn decode_xml_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// description_port => only when the description was served by this node
+fn record_upnp_device(
+    graph_ref: &mut NetworkTopologyGraph,
+    ip: IpAddr,
+    description_port: Option<Port>,
+    device_info: UpnpDeviceInfo,
+) {
+    let now = chrono::Local::now();
+    let (node_index, _) = NetworkTopology::get_or_add_node_by_ip(graph_ref, ip);
+    NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+        node.first_seen.get_or_insert(now);
+        node.last_seen = Some(now);
+
+        if let Some(description_port) = description_port {
+            if !node
+                .opened_ports
+                .iter()
+                .any(|s| s.number == description_port)
+            {
+                node.opened_ports.push(PortInfo::new(
+                    description_port,
+                    None,
+                    None,
+                    "UPnP (http)".to_owned(),
+                    Some(format!(
+                        "Serves UPnP device description at {}",
+                        device_info.location
+                    )),
+                ));
+                node.opened_ports.sort_by_key(|s| s.number);
+            }
+        }
+
+        match node
+            .upnp_devices
+            .iter_mut()
+            .find(|s| s.location == device_info.location)
+        {
+            Some(existing_device) => *existing_device = device_info,
+            None => node.upnp_devices.push(device_info),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_location() {
+        let location = parse_http_location("http://192.168.1.1:49152/rootDesc.xml").unwrap();
+        assert_eq!(location.authority, "192.168.1.1:49152");
+        assert_eq!(location.address, "192.168.1.1:49152".parse().unwrap());
+        assert_eq!(location.path, "/rootDesc.xml");
+
+        let location = parse_http_location("http://192.168.1.1").unwrap();
+        assert_eq!(location.address, "192.168.1.1:80".parse().unwrap());
+        assert_eq!(location.path, "/");
+
+        let location = parse_http_location("http://[fe80::1]/desc.xml").unwrap();
+        assert_eq!(location.address, "[fe80::1]:80".parse().unwrap());
+        let location = parse_http_location("http://[fe80::1]:8080/desc.xml").unwrap();
+        assert_eq!(location.address, "[fe80::1]:8080".parse().unwrap());
+
+        // Hostnames would mean resolving and connecting to whatever they point to
+        assert!(parse_http_location("http://router.lan:80/desc.xml").is_err());
+        assert!(parse_http_location("https://192.168.1.1/desc.xml").is_err());
+    }
+}
@@ -0,0 +1,441 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use ipnet::IpNet;
+
+use super::dns_resolver::is_valid_hostname;
+
+// Scans with more targets have to be confirmed first
+pub const DEFAULT_MAX_TARGETS_WITHOUT_CONFIRMATION: u64 = 4096;
+
+// One entry of a target / exclusion list
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum TargetRange {
+    Single(IpAddr),
+    Range(IpAddr, IpAddr), // inclusive
+    Network(IpNet),
+}
+impl TargetRange {
+    // "10.0.0.1", "10.0.0.1-10.0.0.50", "10.0.0.1-50" (last octet / hextet only) or "10.0.0.0/24"
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(ip) = IpAddr::from_str(value) {
+            return Some(Self::Single(ip));
+        }
+        if let Ok(network) = IpNet::from_str(value) {
+            return Some(Self::Network(network));
+        }
+
+        let (from, to) = value.split_once('-')?;
+        let from = IpAddr::from_str(from.trim()).ok()?;
+        let to = match (from, to.trim()) {
+            (_, to) if IpAddr::from_str(to).is_ok() => IpAddr::from_str(to).ok()?,
+            (IpAddr::V4(from), to) => {
+                let mut octets = from.octets();
+                octets[3] = u8::from_str(to).ok()?;
+                IpAddr::V4(Ipv4Addr::from(octets))
+            }
+            (IpAddr::V6(from), to) => {
+                let mut segments = from.segments();
+                segments[7] = u16::from_str_radix(to, 16).ok()?;
+                IpAddr::V6(Ipv6Addr::from(segments))
+            }
+        };
+        (from.is_ipv4() == to.is_ipv4() && ip_to_u128(from) <= ip_to_u128(to))
+            .then_some(Self::Range(from, to))
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match self {
+            Self::Single(s) => *s == ip,
+            Self::Range(from, to) => {
+                from.is_ipv4() == ip.is_ipv4()
+                    && (ip_to_u128(*from)..=ip_to_u128(*to)).contains(&ip_to_u128(ip))
+            }
+            Self::Network(s) => s.contains(&ip),
+        }
+    }
+
+    // Saturates at u128::MAX (only reachable with ::/0)
+    pub fn count(&self) -> u128 {
+        let (first, last) = self.bounds();
+        (ip_to_u128(last) - ip_to_u128(first)).saturating_add(1)
+    }
+
+    // Lazy, so even a /8 doesn't get materialised
+    pub fn addresses(&self) -> impl Iterator<Item = IpAddr> + Send + 'static {
+        let (first, last) = self.bounds();
+        let is_ipv4 = first.is_ipv4();
+        (ip_to_u128(first)..=ip_to_u128(last)).map(move |s| u128_to_ip(s, is_ipv4))
+    }
+
+    // First and last address to scan. Networks skip their network / broadcast address, same as `IpNet::hosts`.
+    fn bounds(&self) -> (IpAddr, IpAddr) {
+        match self {
+            Self::Single(s) => (*s, *s),
+            Self::Range(from, to) => (*from, *to),
+            Self::Network(IpNet::V4(s)) if s.prefix_len() < 31 => (
+                IpAddr::V4(Ipv4Addr::from(u32::from(s.network()) + 1)),
+                IpAddr::V4(Ipv4Addr::from(u32::from(s.broadcast()) - 1)),
+            ),
+            Self::Network(s) => (s.network(), s.broadcast()),
+        }
+    }
+
+    // (is ipv4, first, last) of everything `contains` matches, networks include their network / broadcast address
+    fn span(&self) -> (bool, u128, u128) {
+        let (first, last) = match self {
+            Self::Network(s) => (s.network(), s.broadcast()),
+            _ => self.bounds(),
+        };
+        (first.is_ipv4(), ip_to_u128(first), ip_to_u128(last))
+    }
+}
+impl Display for TargetRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single(s) => write!(f, "{}", s),
+            Self::Range(from, to) => write!(f, "{}-{}", from, to),
+            Self::Network(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// What a scan should go through, addresses are only generated while scanning
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TargetList {
+    pub entries: Vec<TargetRange>,
+}
+impl TargetList {
+    pub fn count(&self) -> u128 {
+        self.entries
+            .iter()
+            .fold(0u128, |acc, s| acc.saturating_add(s.count()))
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.entries.iter().any(|s| s.contains(ip))
+    }
+
+    // Same as `count`, minus the excluded addresses. Goes by the bounds, so even a /8 doesn't get enumerated.
+    pub fn count_without(&self, exclusions: &TargetExclusions) -> u128 {
+        let excluded_spans = merge_spans(exclusions.entries.iter().map(|s| s.span()).collect());
+        self.entries.iter().fold(0u128, |acc, target| {
+            let (first, last) = target.bounds();
+            let (is_ipv4, first, last) = (first.is_ipv4(), ip_to_u128(first), ip_to_u128(last));
+            let excluded_count = excluded_spans
+                .iter()
+                .filter(|s| s.0 == is_ipv4 && s.1 <= last && s.2 >= first)
+                .fold(0u128, |acc, s| {
+                    acc.saturating_add((s.2.min(last) - s.1.max(first)).saturating_add(1))
+                });
+            acc.saturating_add(target.count().saturating_sub(excluded_count))
+        })
+    }
+
+    pub fn into_addresses(self) -> impl Iterator<Item = IpAddr> + Send + 'static {
+        self.entries.into_iter().flat_map(|s| s.addresses())
+    }
+}
+impl From<Vec<IpAddr>> for TargetList {
+    fn from(value: Vec<IpAddr>) -> Self {
+        Self {
+            entries: value.into_iter().map(TargetRange::Single).collect(),
+        }
+    }
+}
+
+// Free-form target list, see `parse_target_expressions`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TargetExpressions {
+    pub targets: TargetList,
+    pub hostnames: Vec<String>, // get resolved right before scanning
+}
+impl TargetExpressions {
+    pub fn count(&self) -> u128 {
+        self.targets
+            .count()
+            .saturating_add(self.hostnames.len() as u128)
+    }
+
+    // Hostnames aren't resolved yet, so they count as one target each
+    pub fn count_without(&self, exclusions: &TargetExclusions) -> u128 {
+        self.targets
+            .count_without(exclusions)
+            .saturating_add(self.hostnames.len() as u128)
+    }
+}
+
+// Sorted, with overlapping / adjacent spans of the same IP version joined
+fn merge_spans(mut spans: Vec<(bool, u128, u128)>) -> Vec<(bool, u128, u128)> {
+    spans.sort();
+    let mut merged: Vec<(bool, u128, u128)> = vec![];
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if last.0 == span.0 && span.1 <= last.2.saturating_add(1) => {
+                last.2 = last.2.max(span.2);
+            }
+            _ => merged.push(span),
+        }
+    }
+    merged
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetTokenErr {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub reason: &'static str,
+}
+impl Display for TargetTokenErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: \"{}\" {}",
+            self.line, self.column, self.token, self.reason
+        )
+    }
+}
+
+// Single IPs, ranges, CIDRs and hostnames separated by whitespace / commas, "#" starts a comment.
+// Err => every token that is not valid, with its position (1-based).
+pub fn parse_target_expressions(value: &str) -> Result<TargetExpressions, Vec<TargetTokenErr>> {
+    let mut expressions = TargetExpressions::default();
+    let mut errors = vec![];
+    for (line_index, line) in value.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        for (column, token) in split_tokens(line) {
+            match parse_target_token(token) {
+                Ok(TargetToken::Target(s)) => expressions.targets.entries.push(s),
+                Ok(TargetToken::Hostname(s)) => {
+                    if !expressions.hostnames.contains(&s) {
+                        expressions.hostnames.push(s);
+                    }
+                }
+                Err(reason) => errors.push(TargetTokenErr {
+                    line: line_index + 1,
+                    column,
+                    token: token.to_owned(),
+                    reason,
+                }),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(expressions)
+    } else {
+        Err(errors)
+    }
+}
+
+// Scope documents, either plain text (read the same way as `parse_target_expressions`) or CSV.
+// CSV rows contribute every cell holding an IP, range, CIDR or FQDN, so description columns and the header row get skipped.
+// (entries in file order without duplicates, tokens / rows that couldn't be used)
+pub fn parse_target_file(content: &str) -> (Vec<String>, Vec<TargetTokenErr>) {
+    let mut entries: Vec<String> = vec![];
+    let mut errors = vec![];
+    let mut push_entry = |entry: &str| {
+        if !entries.iter().any(|s| s == entry) {
+            entries.push(entry.to_owned());
+        }
+    };
+
+    for (line_index, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        if !line.contains([',', ';', '\t']) {
+            for (column, token) in split_tokens(line) {
+                match parse_target_token(token) {
+                    Ok(_) => push_entry(token),
+                    Err(reason) => errors.push(TargetTokenErr {
+                        line: line_index + 1,
+                        column,
+                        token: token.to_owned(),
+                        reason,
+                    }),
+                }
+            }
+            continue;
+        }
+
+        let mut has_target = false;
+        for cell in line
+            .split([',', ';', '\t'])
+            .map(|s| s.trim().trim_matches(|c| c == '"' || c == '\''))
+        {
+            // Single labels ("ip", "notes") are way more likely to be headers / descriptions than hosts
+            let is_target = match parse_target_token(cell) {
+                Ok(TargetToken::Target(_)) => true,
+                Ok(TargetToken::Hostname(s)) => s.contains('.'),
+                Err(_) => false,
+            };
+            if is_target {
+                push_entry(cell);
+                has_target = true;
+            }
+        }
+        if !has_target && line_index > 0 && !line.trim().is_empty() {
+            errors.push(TargetTokenErr {
+                line: line_index + 1,
+                column: 1,
+                token: line.trim().to_owned(),
+                reason: "has no IP, range, CIDR or hostname column.",
+            });
+        }
+    }
+    (entries, errors)
+}
+
+enum TargetToken {
+    Target(TargetRange),
+    Hostname(String),
+}
+
+// Err => why the token is not valid
+fn parse_target_token(token: &str) -> Result<TargetToken, &'static str> {
+    if let Some(s) = TargetRange::parse(token) {
+        return Ok(TargetToken::Target(s));
+    }
+
+    // Anything that looks like an address should have been one, "10.0.0.300" is a typo, not a hostname
+    let looks_like_address = token.contains(':')
+        || token.contains('/')
+        || token
+            .chars()
+            .all(|s| s.is_ascii_digit() || s == '.' || s == '-');
+    if !looks_like_address && is_valid_hostname(token) {
+        return Ok(TargetToken::Hostname(
+            token.trim_end_matches('.').to_lowercase(),
+        ));
+    }
+
+    Err(if !looks_like_address {
+        "is not a valid IP, range, CIDR or hostname."
+    } else if token.contains('/') {
+        "is not a valid CIDR."
+    } else if token.contains('-') {
+        "is not a valid range (both ends have to be of the same IP version and the end can't be lower than the start)."
+    } else {
+        "is not a valid IP."
+    })
+}
+
+// (1-based column, token)
+fn split_tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut token_start = None;
+    for (char_index, (byte_index, char)) in line.char_indices().enumerate() {
+        let is_separator = char.is_whitespace() || char == ',';
+        match (token_start, is_separator) {
+            (None, false) => token_start = Some((char_index + 1, byte_index)),
+            (Some((column, start)), true) => {
+                tokens.push((column, &line[start..byte_index]));
+                token_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((column, start)) = token_start {
+        tokens.push((column, &line[start..]));
+    }
+    tokens
+}
+
+// IPs, ranges and CIDRs that must never be probed
+#[derive(Debug, Clone, PartialEq, Default, Hash)]
+pub struct TargetExclusions {
+    pub entries: Vec<TargetRange>,
+}
+impl TargetExclusions {
+    // One entry per line (or comma separated), "#" starts a comment. Err => entries that are not valid.
+    pub fn parse(value: &str) -> Result<Self, Vec<String>> {
+        let mut entries = vec![];
+        let mut invalid_entries = vec![];
+        for entry in value
+            .lines()
+            .map(|s| s.split('#').next().unwrap_or_default())
+            .flat_map(|s| s.split(','))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            match TargetRange::parse(entry) {
+                Some(s) => entries.push(s),
+                None => invalid_entries.push(entry.to_owned()),
+            }
+        }
+
+        if invalid_entries.is_empty() {
+            Ok(Self { entries })
+        } else {
+            Err(invalid_entries)
+        }
+    }
+
+    pub fn is_excluded(&self, ip: IpAddr) -> bool {
+        self.entries.iter().any(|s| s.contains(ip))
+    }
+
+    pub fn merged_with(&self, other: &TargetExclusions) -> Self {
+        let mut entries = self.entries.clone();
+        entries.extend(other.entries.iter().cloned());
+        Self { entries }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// IPv4 gets mapped into IPv6, so both can be compared the same way
+fn ip_to_u128(ip: IpAddr) -> u128 {
+    u128::from_be_bytes(
+        match ip {
+            IpAddr::V4(s) => s.to_ipv6_mapped(),
+            IpAddr::V6(s) => s,
+        }
+        .octets(),
+    )
+}
+
+fn u128_to_ip(value: u128, is_ipv4: bool) -> IpAddr {
+    if is_ipv4 {
+        IpAddr::V4(Ipv4Addr::from(value as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target_list(value: &str) -> TargetList {
+        parse_target_expressions(value).unwrap().targets
+    }
+
+    #[test]
+    fn count_without_exclusions() {
+        let targets = target_list("10.0.0.0/24 10.0.1.1-10.0.1.10 fd00::1");
+        assert_eq!(targets.count_without(&TargetExclusions::default()), 265);
+
+        let exclusions =
+            TargetExclusions::parse("10.0.0.1\n10.0.0.1-10.0.0.4\n10.0.1.0/30\nfd00::1").unwrap();
+        // .1-.4 once, .1-.3 of the second range (.0 isn't a target), the IPv6 one
+        assert_eq!(targets.count_without(&exclusions), 265 - 4 - 3 - 1);
+
+        // Network / broadcast addresses aren't scanned, excluding them changes nothing
+        let exclusions = TargetExclusions::parse("10.0.0.0\n10.0.0.255\n10.0.2.0/24").unwrap();
+        assert_eq!(targets.count_without(&exclusions), 265);
+
+        let exclusions = TargetExclusions::parse("10.0.0.0/8").unwrap();
+        assert_eq!(target_list("10.0.0.0/8").count_without(&exclusions), 0);
+    }
+}
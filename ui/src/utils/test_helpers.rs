@@ -0,0 +1,22 @@
+use std::{
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread::{self, JoinHandle},
+};
+
+// Stand-in service (or proxy) answering a single connection
+pub fn serve(handler: impl FnOnce(TcpStream) + Send + 'static) -> SocketAddr {
+    serve_with_handle(handler).0
+}
+
+// Same, for tests that check what the stand-in received once it's done
+pub fn serve_with_handle<T: Send + 'static>(
+    handler: impl FnOnce(TcpStream) -> T + Send + 'static,
+) -> (SocketAddr, JoinHandle<T>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        handler(stream)
+    });
+    (address, handle)
+}
@@ -0,0 +1,197 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+    time::{Duration, Instant},
+};
+
+use petgraph::stable_graph::NodeIndex;
+use pnet::{ipnetwork::IpNetwork, packet::icmp::IcmpTypes, util::MacAddr};
+use socket2::{Domain, Protocol, SockRef, Socket, Type};
+
+use crate::app::{
+    network_topology::{NetworkTopology, NetworkTopologyGraph},
+    workspace_models::{AppState, StatusInfoRef, StatusMessage},
+};
+
+use super::{
+    icmp::send_icmp_echo_request_ping,
+    interfaces::{get_source_interfaces, SourceBinding},
+};
+
+pub const DEFAULT_WAKE_ON_LAN_PORT: u16 = 9;
+pub const DEFAULT_WAKE_ON_LAN_WATCH_S: u64 = 120;
+// UDP gets lost, sleeping NICs don't answer, so the packet is simply sent a few times
+const MAGIC_PACKET_REPEAT: usize = 3;
+const WATCH_PING_TIMEOUT_MS: u64 = 1000;
+const WATCH_PING_CHECKUP_MS: u64 = 20;
+const WATCH_PING_INTERVAL_MS: u64 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WakeOnLanDestination {
+    Broadcast,          // 255.255.255.255, only reaches the local segment
+    Directed(Ipv4Addr), // subnet broadcast, routers have to forward it (often disabled)
+}
+
+pub struct WakeOnLanConfig {
+    pub mac: MacAddr,
+    pub destination: WakeOnLanDestination,
+    pub port: u16,
+    pub secure_on_password: Option<MacAddr>, // 6 bytes, written the same way as a MAC
+    pub watch_for: Option<Duration>,         // Some => ping the node until it's up
+    pub source: SourceBinding,
+}
+
+pub fn wake_on_lan(
+    mut graph_ref: NetworkTopologyGraph,
+    status_info_ref: StatusInfoRef,
+    node_index: NodeIndex,
+    ip: IpAddr,
+    config: WakeOnLanConfig,
+) {
+    thread::spawn(move || {
+        let destination = match config.destination {
+            WakeOnLanDestination::Broadcast => Ipv4Addr::BROADCAST,
+            WakeOnLanDestination::Directed(s) => s,
+        };
+        let destination = SocketAddr::new(IpAddr::V4(destination), config.port);
+        if let Err(e) = send_magic_packet(&config, destination) {
+            AppState::log_to_status_generic(
+                &status_info_ref,
+                StatusMessage::Err(format!(
+                    "Unable to send the Wake-on-LAN packet to {} => {}",
+                    destination, e
+                )),
+            );
+            return;
+        }
+        AppState::log_to_status_generic(
+            &status_info_ref,
+            StatusMessage::Info(format!(
+                "Sent Wake-on-LAN packet for {} ({}) to {}{}.",
+                ip,
+                config.mac,
+                destination,
+                if config.secure_on_password.is_some() {
+                    " with SecureOn password"
+                } else {
+                    ""
+                }
+            )),
+        );
+
+        if let Some(watch_for) = config.watch_for {
+            watch_until_up(
+                &mut graph_ref,
+                &status_info_ref,
+                node_index,
+                ip,
+                watch_for,
+                &config.source.resolved(),
+            );
+        }
+    });
+}
+
+fn send_magic_packet(config: &WakeOnLanConfig, destination: SocketAddr) -> anyhow::Result<()> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_broadcast(true)?;
+    config
+        .source
+        .bind_socket(SockRef::from(&socket), destination.ip())?;
+
+    let packet = build_magic_packet(config.mac, config.secure_on_password);
+    for _ in 0..MAGIC_PACKET_REPEAT {
+        socket.send_to(&packet, &destination.into())?;
+    }
+    Ok(())
+}
+
+// 6x 0xff, 16x the target's MAC, optionally followed by the 6 byte SecureOn password
+fn build_magic_packet(mac: MacAddr, secure_on_password: Option<MacAddr>) -> Vec<u8> {
+    let mac_bytes = [mac.0, mac.1, mac.2, mac.3, mac.4, mac.5];
+    let mut packet = vec![0xff; 6];
+    for _ in 0..16 {
+        packet.extend_from_slice(&mac_bytes);
+    }
+    if let Some(s) = secure_on_password {
+        packet.extend_from_slice(&[s.0, s.1, s.2, s.3, s.4, s.5]);
+    }
+    packet
+}
+
+fn watch_until_up(
+    graph_ref: &mut NetworkTopologyGraph,
+    status_info_ref: &StatusInfoRef,
+    node_index: NodeIndex,
+    ip: IpAddr,
+    watch_for: Duration,
+    source: &SourceBinding,
+) {
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Info(format!(
+            "Waiting up to {}s for {} to come up...",
+            watch_for.as_secs(),
+            ip
+        )),
+    );
+
+    let started_at = Instant::now();
+    while started_at.elapsed() < watch_for {
+        match send_icmp_echo_request_ping(ip, WATCH_PING_TIMEOUT_MS, WATCH_PING_CHECKUP_MS, source)
+        {
+            Ok(Some(reply)) if reply.addr == ip && reply.icmp_type == IcmpTypes::EchoReply => {
+                let now = chrono::Local::now();
+                NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+                    node.first_seen.get_or_insert(now);
+                    node.last_seen = Some(now);
+                    node.record_ping(Some(reply.rtt));
+                });
+                NetworkTopology::ensure_edge_from_localhost(graph_ref, node_index);
+                AppState::log_to_status_generic(
+                    status_info_ref,
+                    StatusMessage::Info(format!(
+                        "{} is up, {}s after the Wake-on-LAN packet.",
+                        ip,
+                        started_at.elapsed().as_secs()
+                    )),
+                );
+                return;
+            }
+            Ok(_) => {
+                NetworkTopology::update_node_data(graph_ref, node_index, |node| {
+                    node.record_ping(None)
+                });
+            }
+            Err(e) => {
+                AppState::log_to_status_generic(
+                    status_info_ref,
+                    StatusMessage::Err(format!("Unable to ping {} => {}", ip, e)),
+                );
+                return;
+            }
+        }
+        thread::sleep(Duration::from_millis(WATCH_PING_INTERVAL_MS));
+    }
+
+    AppState::log_to_status_generic(
+        status_info_ref,
+        StatusMessage::Warn(format!(
+            "{} didn't come up within {}s of the Wake-on-LAN packet.",
+            ip,
+            watch_for.as_secs()
+        )),
+    );
+}
+
+// Broadcast address of the local subnet the ip is in, None => not on any of our subnets
+pub fn get_directed_broadcast(ip: IpAddr) -> Option<Ipv4Addr> {
+    get_source_interfaces()
+        .iter()
+        .flat_map(|s| s.ips.iter())
+        .filter(|s| s.contains(ip))
+        .find_map(|s| match s {
+            IpNetwork::V4(s) if s.prefix() < 31 => Some(s.broadcast()),
+            _ => None,
+        })
+}